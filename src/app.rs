@@ -35,6 +35,8 @@ pub struct App {
     pub app_config_home: PathBuf,
     pub app_keys_dir:    PathBuf,
     pub repos_file:      PathBuf,
+    pub credentials_file: PathBuf,
+    pub forges_file:     PathBuf,
     pub app_data_home:   PathBuf,
     pub app_cache_home:  PathBuf,
 
@@ -97,6 +99,8 @@ impl Default for App {
             app_config_home: xdg_config_home.join(command_name),
             app_keys_dir:    xdg_config_home.join(format!("{command_name}/keys")),
             repos_file:      xdg_config_home.join(format!("{command_name}/repos.json")),
+            credentials_file: xdg_config_home.join(format!("{command_name}/credentials")),
+            forges_file:     xdg_config_home.join(format!("{command_name}/forges")),
             app_data_home:   xdg_data_home.join(command_name),
             app_cache_home:  xdg_cache_home.join(command_name),
 
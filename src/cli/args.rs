@@ -25,15 +25,26 @@ impl App {
         let command = args.get(1).ok_or("Missing <command>")?;
 
         match command.as_str() {
-            "clone"     => self.cli_command_clone(args)?,
-            "init"      => self.cli_command_init(args)?,
-            "sync"      => self.cli_command_sync(args)?,
-            "status"    => self.cli_command_status(args)?, // Not displayed
-            "check"     => self.cli_command_check(args)?, // Not displayed
-            "--help"    => self.cli_option_help(),
-            "--version" => self.cli_option_version(),
-            "--deps"    => self.cli_option_deps(),
-            "--env"     => self.cli_option_env()?,
+            "clone"      => self.cli_command_clone(args)?,
+            "init"       => self.cli_command_init(args)?,
+            "sync"       => self.cli_command_sync(args)?,
+            "fetch-history" => self.cli_command_fetch_history(args)?,
+            "status"     => self.cli_command_status(args)?, // Not displayed
+            "check"      => self.cli_command_check(args)?, // Not displayed
+            "credential" => self.cli_command_credential(args)?,
+            "keys"       => self.cli_command_keys(args)?,
+            "lfs"        => self.cli_command_lfs(args)?,
+            "select"     => self.cli_command_select(args)?,
+            "lock"       => self.cli_command_lock(args)?,
+            "unlock"     => self.cli_command_unlock(args)?,
+            "compact"    => self.cli_command_compact(args)?,
+            "confirm-deletions" => self.cli_command_confirm_deletions(args)?,
+            "trash"      => self.cli_command_trash(args)?,
+            "relay"      => self.cli_command_relay(args)?,
+            "--help"     => self.cli_option_help(),
+            "--version"  => self.cli_option_version(),
+            "--deps"     => self.cli_option_deps(),
+            "--env"      => self.cli_option_env()?,
             _ => {
                 self.cli_option_help();
                 return Err("Unknown command".into());
@@ -48,10 +59,23 @@ impl App {
         println!("Usage: {} <command> [args…]", self.command);
         println!();
         println!("Commands:");
-        println!("    clone <user@host:path> [path]");
-        println!("    init  <user@host:path> [path]");
+        println!("    clone <user@host:path|https://host/path> [path] [--yes] [--depth=1|--full]");
+        println!("    init  <user@host:path|https://host/path> [path]");
         println!("    sync  [path] [--interval=60]");
         println!();
+        println!("    select list|add|remove|all [folder] [path]");
+        println!("    lock   <path>");
+        println!("    unlock <path> [--force]");
+        println!("    confirm-deletions [path] [--undo]");
+        println!("    trash  list|restore|empty [file] [path]");
+        println!();
+        println!("    keys list|show|renew|delete|trust [host]");
+        println!("    credential set <https://host> <username>");
+        println!("    lfs migrate|usage|prune [path]");
+        println!("    compact [path] [--yes]");
+        println!("    fetch-history [path]");
+        println!("    relay --listen=<address:port>");
+        println!();
         println!("Support:");
         println!("    check [path]");
         println!("    {}",
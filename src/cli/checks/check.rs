@@ -10,6 +10,7 @@ use std::path::Path;
 
 use crate::app::App;
 use crate::cli::util::*;
+use crate::twinkle::twinkle_ssh::twinkle_ssh_forges;
 
 use super::check_config::*;
 use super::check_platform::*;
@@ -49,6 +50,8 @@ impl App {
         run_check("Git", &is_git_installed, path);
         run_check("Git LFS", &is_git_lfs_installed, path);
 
+        let forges = twinkle_ssh_forges(&self.forges_file);
+
         print_header("Secure Shell");
         run_check("ssh-agent running", &is_ssh_agent_running, path);
         run_check("ssh-agent has keys", &is_ssh_agent_has_keys, path);
@@ -57,8 +60,9 @@ impl App {
         run_check("Host key", &is_ssh_host_supporting_ed25519, path);
         run_check("Host key", &is_ssh_host_supporting_ecdsa, path);
         run_check("Host key", &is_ssh_host_supporting_rsa, path);
-        run_check("Host knows client", &is_ssh_client_key_known_to_host, path);
+        run_check("Host knows client", &move |path| is_ssh_client_key_known_to_host(path, &forges), path);
         run_check("Client knows host", &is_ssh_host_known, path);
+        run_check("Certificate", &is_ssh_certificate_valid, path);
 
         print_header("Repository");
         run_check(".git/", &is_git_dir_present, path);
@@ -91,6 +95,7 @@ impl App {
         run_check("twinkle.enabled", &is_twinkle_enabled_set, path);
         run_check("twinkle.lfs.enabled", &is_twinkle_lfs_enabled_set, path);
         run_check("twinkle.push.enabled", &is_twinkle_push_enabled_set, path);
+        run_check("File sizes", &is_twinkle_file_size_within_limits, path);
 
         // TODO: Find all git config options/filters/hooks that may have been added by the user
 
@@ -132,6 +137,12 @@ pub fn run_check(
                 "    {} {title}",
                 cli_red(&outcome.to_string()),
             ),
+        Outcome::Warning(ref s) =>
+            println!(
+                "    {} {title}: {}",
+                cli_yellow(&outcome.to_string()),
+                cli_yellow(s),
+            ),
         Outcome::Missing =>
             println!(
                 "    {} {title}: {}",
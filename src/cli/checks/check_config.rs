@@ -9,6 +9,8 @@ use std::env;
 use std::path::Path;
 
 use crate::git::objects::environment::GitEnvironment;
+use crate::twinkle::objects::repository::TwinkleRepository;
+use crate::twinkle::twinkle_limits::twinkle_oversize_files;
 use super::outcome::Outcome;
 
 
@@ -128,3 +130,20 @@ pub fn is_twinkle_push_enabled_set(path: &Path) -> Outcome {
     let name = env!("CARGO_BIN_NAME");
     get_from_config(path, &format!("{name}.push.enabled"), Some("true"))
 }
+
+
+/// Files over the size limit won't sync, the repository itself still does
+pub fn is_twinkle_file_size_within_limits(path: &Path) -> Outcome {
+    let repo = TwinkleRepository::new(path);
+
+    match twinkle_oversize_files(&repo) {
+        Ok(oversize) if oversize.is_empty() => Outcome::Pass(None),
+        Ok(oversize) => Outcome::Warning(
+            oversize.iter()
+                .map(|file| file.to_string())
+                .collect::<Vec<_>>()
+                .join("; ")
+        ),
+        Err(_) => Outcome::Error,
+    }
+}
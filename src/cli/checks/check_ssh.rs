@@ -12,10 +12,16 @@ use std::path::Path;
 use std::process::{ Command, Stdio };
 use std::time::Duration;
 
+use chrono::Utc;
+
 use crate::git::objects::environment::GitEnvironment;
 use crate::ssh::keys::key_type::KeyType;
 use crate::ssh::keyscan::ssh_keyscan;
+use crate::ssh::objects::forge::KnownForges;
 use crate::ssh::objects::url::SshUrl;
+use crate::twinkle::objects::repository::TwinkleRepository;
+use crate::twinkle::twinkle_keys::twinkle_keypair_certificate_error;
+use crate::twinkle::twinkle_pretty::twinkle_pretty_datetime;
 
 use super::outcome::Outcome;
 
@@ -136,7 +142,7 @@ fn is_ssh_host_supporting_key_type(path: &Path, key_type: KeyType) -> Outcome {
 }
 
 
-pub fn is_ssh_client_key_known_to_host(path: &Path) -> Outcome {
+pub fn is_ssh_client_key_known_to_host(path: &Path, forges: &KnownForges) -> Outcome {
     // TODO: What if keys stored in Secrets when running in Flatpak?
 
     let option = GitEnvironment::new(path)
@@ -162,7 +168,7 @@ pub fn is_ssh_client_key_known_to_host(path: &Path) -> Outcome {
 
                 match status.code() {
                     Some(n) => {
-                        if  url.host == "github.com" && n == 1 {
+                        if forges.get(&url.host).is_some_and(|forge| forge.is_test_success(n)) {
                             return Outcome::Pass(None)
                         } else {
                             return Outcome::Fail(None)
@@ -179,6 +185,31 @@ pub fn is_ssh_client_key_known_to_host(path: &Path) -> Outcome {
 }
 
 
+pub fn is_ssh_certificate_valid(path: &Path) -> Outcome {
+    let Some(key_pair) = TwinkleRepository::new(path).key_pair() else {
+        return Outcome::Pass(Some("no key".into()));
+    };
+
+    let Some(certificate) = &key_pair.certificate else {
+        return Outcome::Pass(Some("none".into()));
+    };
+
+    if let Some(e) = twinkle_keypair_certificate_error(&key_pair) {
+        return Outcome::Fail(Some(e.to_string()));
+    }
+
+    let now = Utc::now();
+
+    match certificate.valid_before {
+        Some(before) if certificate.expires_soon(now) =>
+            Outcome::Warning(format!("expires on {}", twinkle_pretty_datetime(before.timestamp()))),
+        Some(before) =>
+            Outcome::Pass(Some(format!("valid until {}", twinkle_pretty_datetime(before.timestamp())))),
+        None => Outcome::Pass(Some("valid forever".into())),
+    }
+}
+
+
 fn check_host_port(host: &str, port: u16, timeout_secs: u64) -> bool {
     format!("{}:{}", host.trim(), port)
         .to_socket_addrs()
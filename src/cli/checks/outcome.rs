@@ -13,6 +13,7 @@ pub enum Outcome {
     Fail(Option<String>),
     Missing,
     Pass(Option<String>),
+    Warning(String),
 }
 
 impl fmt::Display for Outcome {
@@ -21,6 +22,7 @@ impl fmt::Display for Outcome {
             Self::Error   => write!(f, "!"),
             Self::Fail(_) => write!(f, "!"),
             Self::Missing => write!(f, "?"),
+            Self::Warning(_) => write!(f, "?"),
             Self::Pass(_) => write!(f, "✓"),
         }
     }
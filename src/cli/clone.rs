@@ -8,23 +8,45 @@
 use std::env;
 use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{ Path, PathBuf };
 
 use crate::app::App;
 use crate::log;
-use crate::ssh::objects::url::SshUrl;
+use crate::ssh::keys::fingerprint::Fingerprint;
+use crate::twinkle::defaults::common::COMMON_CLONE_DEPTH;
+use crate::twinkle::twinkle_keys::twinkle_hostkey_trust;
+use crate::twinkle::twinkle_select::twinkle_select_normalize;
 
 use crate::twinkle::twinkle_clone::{
     TwinkleCloneError,
     twinkle_clone_complete,
+    twinkle_clone_prepare_keys,
     twinkle_clone_start,
 };
 
+use super::util::{
+    cli_bold,
+    cli_option_flag,
+    cli_option_value,
+    cli_positional_args,
+    cli_trust_host_key,
+    cli_wait_for_enter,
+};
+
 
 impl App {
     fn cli_command_clone_usage() {
         println!("Usage: twinkle clone <user@host:path> [path]");
         println!("               clone <ssh://user@host[:port]/path> [path]");
+        println!("               clone <https://host[:port]/path> [path]");
+        println!("               clone <file:///path> [path]");
+        println!("               clone </path> [path]");
+        println!();
+        println!("Options: --yes                        Trust the host key and don't ask questions");
+        println!("         --trust-fingerprint=SHA256:… Only trust a host key with this fingerprint");
+        println!("         --select=art,docs            Only sync these folders, and the files in the root");
+        println!("         --depth=1                    Only fetch this many commits of history (default)");
+        println!("         --full                       Fetch all of the history");
         println!();
     }
 
@@ -34,15 +56,51 @@ impl App {
         args: &Vec<String>
     ) -> Result<(), Box<dyn Error>>
     {
-        self.cli_require_args(2, args).map_err(|_| {
+        let assume_yes = cli_option_flag(args, "--yes");
+
+        let trusted_fingerprint = match cli_option_value(args, "--trust-fingerprint") {
+            Some(s) => Some(s.parse::<Fingerprint>().map_err(|_| {
+                Self::cli_command_clone_usage();
+                "Not a valid --trust-fingerprint"
+            })?),
+            None => None,
+        };
+
+        let selection = match cli_option_value(args, "--select") {
+            Some(s) => s.split(',')
+                .map(|dir| twinkle_select_normalize(Path::new(dir)))
+                .collect::<Result<Vec<_>, _>>()
+                .inspect_err(|_| Self::cli_command_clone_usage())?,
+            None => Vec::new(),
+        };
+
+        let depth = match (cli_option_value(args, "--depth"), cli_option_flag(args, "--full")) {
+            (Some(_), true) => {
+                Self::cli_command_clone_usage();
+                return Err("Use either --depth or --full".into());
+            },
+            (Some(s), false) => match s.parse::<u32>() {
+                Ok(depth) if depth > 0 => Some(depth),
+                _ => {
+                    Self::cli_command_clone_usage();
+                    return Err("Not a valid --depth".into());
+                },
+            },
+            (None, true) => None,
+            (None, false) => Some(COMMON_CLONE_DEPTH),
+        };
+
+        let args = cli_positional_args(args);
+
+        self.cli_require_args(2, &args).map_err(|_| {
             Self::cli_command_clone_usage();
             "Missing <user@host:path>"
         })?;
 
-        let ssh_url = args.get(2)
+        let remote_url = args.get(2)
             .ok_or("Missing <user@host:path>")?;
 
-        let ssh_url = ssh_url.parse::<SshUrl>().map_err(|_| {
+        let remote_url = self.cli_parse_remote_url(remote_url).map_err(|_| {
             Self::cli_command_clone_usage();
             "Not a valid <user@host:path>"
         })?;
@@ -57,72 +115,46 @@ impl App {
             "Not a valid <path>"
         })?;
 
-        let mut repo = twinkle_clone_start(&ssh_url, None, &path)?;
-        twinkle_clone_complete(&mut repo, None)?;
+        let key_pair = loop {
+            let e = match twinkle_clone_prepare_keys(&remote_url, &self.app_keys_dir, &self.forges_file) {
+                Ok(key_pair) => break key_pair,
+                Err(e) => e,
+            };
+
+            match e.downcast_ref::<TwinkleCloneError>() {
+                Some(TwinkleCloneError::NeedsNetwork(_)) => return Err(e),
+                Some(TwinkleCloneError::NeedsTrust(host_key)) => {
+                    if !cli_trust_host_key(host_key, trusted_fingerprint.as_ref(), assume_yes)? {
+                        return Err(e);
+                    }
+
+                    twinkle_hostkey_trust(host_key, &self.app_keys_dir)?;
+                },
+                Some(TwinkleCloneError::NeedsAuth(host_key, key_pair)) => {
+                    self.cli_show_public_key(&host_key.host, key_pair);
+
+                    if assume_yes {
+                        return Err(e);
+                    }
+
+                    cli_wait_for_enter(&format!(
+                        "Then, press {enter} to clone {url}… ",
+                        enter = cli_bold("[Enter]"),
+                        url = cli_bold(&remote_url.original())
+                    ))?;
+                },
+                None => return Err(e),
+            }
+        };
+
+        let mut repo = twinkle_clone_start(&remote_url, key_pair.as_ref(), &path, depth)?;
+        repo.set_select(&selection)?;
+        twinkle_clone_complete(&mut repo, key_pair.as_ref())?;
 
         if repo.git.lfs_version().is_none() {
             log::warning("git-lfs command not found");
         }
 
         Ok(())
-
-        // loop {
-        //     match twinkle_clone_prepare(&ssh_url, &self.app_keys_dir) {
-        //         Err(e) => match e.downcast_ref::<TwinkleCloneError>() {
-        //             Some(TwinkleCloneError::NeedsNetwork) => {
-        //                 println!("Could not connect to {}", ssh_url.host);
-        //             },
-        //             Some(TwinkleCloneError::NeedsTrust(host_key)) => {
-        //                 let fingerprint = host_key.fingerprint.clone().unwrap_or_else(|| {
-        //                     log::error_and_exit("No host key fingerprint");
-        //                 });
-
-        //                 println!("Connected to {} {} {} ",
-        //                     host_key.host, cli_dimmed("–"), cli_dimmed(&fingerprint.to_string()));
-
-        //                 io::stdout().flush()?;
-        //                 twinkle_hostkey_trust(host_key, &self.app_keys_dir)?;
-        //             },
-        //             Some(TwinkleCloneError::NeedsAuth(host_key, key_pair)) => {
-        //                 let url = twinkle_settings_url_for(host_key.host.clone());
-        //                 let url = url.unwrap_or(&host_key.host);
-        //                 let settings_url = cli_link(url, None);
-
-        //                 println!();
-        //                 println!("First, add this SSH key to {settings_url}:");
-        //                 println!("{}", cli_bold(&key_pair.public_key));
-        //                 println!();
-        //                 print!("Then, press {enter} to clone {url}… ",
-        //                     enter=cli_bold("[Enter]"),
-        //                     url=cli_bold(&ssh_url.original));
-
-        //                 io::stdout().flush()?;
-        //                 let mut input = String::new();
-        //                 io::stdin().read_line(&mut input)?;
-        //             },
-        //             None => return Err(e),
-        //         },
-        //         Ok(key_pair) => {
-        //             let mut repo = twinkle_clone_start(&ssh_url, &key_pair, &path)?;
-        //             twinkle_clone_complete(&mut repo, &key_pair)?;
-        //             self.config.add(&repo)?;
-
-        //             return Ok(());
-        //         }
-        //     };
-
-        //     thread::sleep(Duration::from_millis(500));
-        // }
-    }
-}
-
-
-impl TwinkleCloneError {
-    pub fn to_exit_code(&self) -> u8 {
-        match self {
-            Self::NeedsNetwork => 1,
-            Self::NeedsTrust(_) => 2,
-            Self::NeedsAuth(_, _) => 3,
-        }
     }
 }
@@ -0,0 +1,67 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::path::Path;
+
+use crate::app::App;
+use crate::twinkle::objects::repository::TwinkleRepository;
+
+use crate::twinkle::twinkle_compact::{
+    twinkle_compact,
+    twinkle_compact_branch_name,
+    twinkle_compact_cutoff,
+    twinkle_compact_generation,
+    twinkle_compact_snapshots,
+};
+
+use super::util::*;
+
+
+impl App {
+    pub fn cli_command_compact(&self, args: &[String]) -> Result<(), Box<dyn Error>> {
+        let assume_yes = cli_option_flag(args, "--yes");
+        let args = cli_positional_args(args);
+
+        let default_path = ".".to_string();
+        let path = self.cli_prepare_path(Path::new(args.get(2).unwrap_or(&default_path)))?;
+        let repo = TwinkleRepository::new(&path);
+
+        let cutoff = twinkle_compact_cutoff(&repo);
+        let history = repo.git.log_first_parent()?;
+        let (snapshots, count) = twinkle_compact_snapshots(&history, cutoff, repo.compact_period());
+
+        if count <= snapshots.len() {
+            println!("Nothing to compact from before {}", cutoff.format("%Y-%m-%d"));
+            return Ok(());
+        }
+
+        let branch = repo.branch().ok_or("Not on a branch")?;
+        let next_branch = twinkle_compact_branch_name(&branch, twinkle_compact_generation(&repo));
+
+        println!();
+        println!("    {} changes from before {} become {} snapshots, one per {} days.",
+            cli_bold(&count.to_string()),
+            cutoff.format("%Y-%m-%d"),
+            cli_bold(&snapshots.len().to_string()),
+            repo.compact_period(),
+        );
+        println!("    Everyone moves over to {} on their next sync.", cli_bold(&next_branch));
+        println!("    {}", cli_dimmed(&format!("`{branch}` stays on the remote for clients that haven't synced yet.")));
+        println!();
+
+        if !assume_yes && !cli_confirm("Compact the history?")? {
+            return Ok(());
+        }
+
+        if let Some(next_branch) = twinkle_compact(&repo)? {
+            println!("Done. Now on {}", cli_bold(&next_branch));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::path::Path;
+
+use crate::app::App;
+use crate::twinkle::objects::repository::TwinkleRepository;
+use crate::twinkle::twinkle_deletions::{ twinkle_deletions_confirm, twinkle_deletions_undo };
+
+use super::util::*;
+
+
+impl App {
+    pub fn cli_command_confirm_deletions(&self, args: &[String]) -> Result<(), Box<dyn Error>> {
+        let undo = cli_option_flag(args, "--undo");
+        let args = cli_positional_args(args);
+
+        let default_path = ".".to_string();
+        let path = self.cli_prepare_path(Path::new(args.get(2).unwrap_or(&default_path)))?;
+        let repo = TwinkleRepository::new(&path);
+
+        if undo {
+            let commit = twinkle_deletions_undo(&repo)?;
+
+            println!("Undid {}. Files that are back sync as usual", cli_bold(&commit));
+            println!("{}", cli_dimmed("Files that are still missing count as deleted again"));
+        } else {
+            let commit = twinkle_deletions_confirm(&repo)?;
+            println!("Confirmed. {} syncs on the next check", cli_bold(&commit));
+        }
+
+        Ok(())
+    }
+}
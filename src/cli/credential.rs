@@ -0,0 +1,118 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::io::{ self, Read, Write };
+
+use crate::app::App;
+
+use crate::twinkle::twinkle_credentials::{
+    TwinkleCredential,
+    twinkle_credential_delete,
+    twinkle_credential_fill,
+    twinkle_credential_set,
+};
+
+use super::util::cli_read_secret;
+
+
+impl App {
+    fn cli_command_credential_usage() {
+        println!("Usage: twinkle credential set <https://host> <username>");
+        println!("                          unset <https://host>");
+        println!();
+        println!("The token is read from stdin.");
+        println!();
+    }
+
+
+    pub fn cli_command_credential(
+        &self,
+        args: &Vec<String>
+    ) -> Result<(), Box<dyn Error>>
+    {
+        self.cli_require_args(2, args).map_err(|_| {
+            Self::cli_command_credential_usage();
+            "Missing <operation>"
+        })?;
+
+        let operation = args.get(2).ok_or("Missing <operation>")?;
+
+        match operation.as_str() {
+            // Called by Git, see: https://git-scm.com/docs/gitcredentials#_custom_helpers
+            "get" => {
+                let mut request = String::new();
+                io::stdin().read_to_string(&mut request)?;
+
+                if let Some(response) = twinkle_credential_fill(&self.credentials_file, &request) {
+                    io::stdout().write_all(response.as_bytes())?;
+                }
+            },
+            "store" | "erase" => {
+                // Tokens are only managed through `set` and `unset`
+                let mut request = String::new();
+                io::stdin().read_to_string(&mut request)?;
+            },
+
+            // Called by the user
+            "set" => {
+                let host = args.get(3).ok_or("Missing <https://host>")?;
+                let host = cli_parse_credential_host(host).ok_or_else(|| {
+                    Self::cli_command_credential_usage();
+                    "Not a valid <https://host>"
+                })?;
+
+                let username = args.get(4).ok_or("Missing <username>")?;
+
+                let token = cli_read_secret(&format!("Token for {host}: "))?;
+                let token = token.trim();
+
+                if token.is_empty() {
+                    return Err("Token cannot be empty".into());
+                }
+
+                twinkle_credential_set(
+                    &self.credentials_file,
+                    &TwinkleCredential {
+                        host,
+                        username: username.to_string(),
+                        token: token.to_string(),
+                    },
+                )?;
+            },
+            "unset" => {
+                let host = args.get(3).ok_or("Missing <https://host>")?;
+                let host = cli_parse_credential_host(host).ok_or_else(|| {
+                    Self::cli_command_credential_usage();
+                    "Not a valid <https://host>"
+                })?;
+
+                twinkle_credential_delete(&self.credentials_file, &host)?;
+            },
+            _ => {
+                Self::cli_command_credential_usage();
+                return Err("Unknown operation".into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
+/// "https://hbons@git.example.com:8443/hbons/Twinkle" -> "git.example.com:8443"
+fn cli_parse_credential_host(s: &str) -> Option<String> {
+    let s = s.trim().strip_prefix("https://")?;
+    let authority = s.split('/').next()?;
+    let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+
+    if host.is_empty() {
+        None
+    } else {
+        Some(host.to_string())
+    }
+}
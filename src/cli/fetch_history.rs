@@ -0,0 +1,33 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::path::Path;
+
+use crate::app::App;
+use crate::twinkle::objects::repository::TwinkleRepository;
+use crate::twinkle::twinkle_history::twinkle_history_fetch;
+
+use super::util::*;
+
+
+impl App {
+    pub fn cli_command_fetch_history(&self, args: &[String]) -> Result<(), Box<dyn Error>> {
+        let args = cli_positional_args(args);
+
+        let default_path = ".".to_string();
+        let path = self.cli_prepare_path(Path::new(args.get(2).unwrap_or(&default_path)))?;
+        let repo = TwinkleRepository::new(&path);
+
+        match twinkle_history_fetch(&repo)? {
+            true  => println!("Fetched all of the history"),
+            false => println!("All of the history is already here"),
+        }
+
+        Ok(())
+    }
+}
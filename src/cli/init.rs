@@ -11,11 +11,9 @@ use std::error::Error;
 
 use crate::app::App;
 
-use crate::ssh::objects::url::SshUrl;
 use crate::twinkle::twinkle_init::twinkle_init;
 
 
-
 impl App {
     pub fn cli_command_init(&self, args: &Vec<String>) -> Result<(), Box<dyn Error>>{
         self.cli_require_args(2, args).map_err(|_| {
@@ -23,14 +21,14 @@ impl App {
             "Missing <user@host:path>"
         })?;
 
-        let ssh_url = args.get(2).ok_or("Missing <user@host:path>")?;
-        let ssh_url = ssh_url.parse::<SshUrl>().map_err(|_| {
+        let remote_url = args.get(2).ok_or("Missing <user@host:path>")?;
+        let remote_url = self.cli_parse_remote_url(remote_url).map_err(|_| {
             Self::cli_command_init_usage();
             "Not a valid <user@host:path>"
         })?;
 
         let path = current_dir()?;
-        twinkle_init(&path, &ssh_url, None)?;
+        twinkle_init(&path, &remote_url, None)?;
 
         Ok(())
     }
@@ -38,6 +36,9 @@ impl App {
     fn cli_command_init_usage() {
         println!("Usage: twinkle init <user@host:path> [path]");
         println!("               init <ssh://user@host[:port]/path> [path]");
+        println!("               init <https://host[:port]/path> [path]");
+        println!("               init <file:///path> [path]");
+        println!("               init </path> [path]");
         println!();
     }
 }
@@ -0,0 +1,428 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::env;
+use std::error::Error;
+use std::path::{ Path, PathBuf };
+
+use crate::app::App;
+use crate::ssh::agent::SshAgent;
+use crate::ssh::keyscan::ssh_keyscan;
+
+use crate::ssh::keygen::{
+    ssh_keygen_change_passphrase,
+    ssh_keygen_fingerprint,
+    ssh_keygen_fingerprint_key_pair,
+    ssh_keygen_fingerprint_public_key,
+    ssh_keygen_is_encrypted,
+};
+
+use crate::ssh::keys::fingerprint::Fingerprint;
+use crate::ssh::keys::host_key::HostKey;
+use crate::ssh::keys::key_pair::KeyPair;
+use crate::ssh::keys::key_type::KeyType;
+use crate::ssh::objects::url::SshUrl;
+
+use crate::twinkle::twinkle_keys::{
+    twinkle_hostkey_changed,
+    twinkle_hostkey_for,
+    twinkle_hostkey_trust,
+    twinkle_keypair_delete,
+    twinkle_keypair_find,
+    twinkle_keypair_from_agent,
+    twinkle_keypair_list,
+    twinkle_keypair_certificate_error,
+    twinkle_keypair_renew,
+    twinkle_keypair_repos,
+    twinkle_keypair_set_certificate,
+    twinkle_keytype_for,
+};
+
+use crate::twinkle::twinkle_pretty::{ twinkle_pretty_datetime, twinkle_pretty_dir };
+
+use crate::twinkle::twinkle_ssh::{
+    twinkle_ssh_config_export,
+    twinkle_ssh_config_import,
+    twinkle_ssh_forge_for,
+    twinkle_ssh_known_hosts_import,
+    twinkle_ssh_scan_url,
+};
+
+use super::util::{
+    cli_bold,
+    cli_confirm,
+    cli_dimmed,
+    cli_option_flag,
+    cli_option_value,
+    cli_positional_args,
+    cli_read_secret,
+    cli_trust_host_key,
+};
+
+
+impl App {
+    fn cli_command_keys_usage() {
+        println!("Usage: twinkle keys list");
+        println!("                    show <host>");
+        println!("                    renew <host> [--yes]");
+        println!("                    delete <host> [--yes]");
+        println!("                    trust <host> [--fingerprint=SHA256:…] [--yes]");
+        println!("                    retrust <host> [--fingerprint=SHA256:…] [--yes]");
+        println!("                    passphrase <host>");
+        println!("                    agent <host> [fingerprint]");
+        println!("                    certificate <host> [path]");
+        println!("                    import <host> [--ssh-config=PATH] [--known-hosts=PATH]");
+        println!("                    export <host>");
+        println!();
+        println!("Passphrases are read from stdin.");
+        println!("Imports read ~/.ssh/config and ~/.ssh/known_hosts by default.");
+        println!();
+    }
+
+
+    pub fn cli_command_keys(
+        &self,
+        args: &Vec<String>
+    ) -> Result<(), Box<dyn Error>>
+    {
+        let assume_yes = cli_option_flag(args, "--yes");
+
+        let trusted_fingerprint = match cli_option_value(args, "--fingerprint") {
+            Some(s) => Some(s.parse::<Fingerprint>().map_err(|_| {
+                Self::cli_command_keys_usage();
+                "Not a valid --fingerprint"
+            })?),
+            None => None,
+        };
+
+        let ssh_config = cli_option_value(args, "--ssh-config").map(PathBuf::from);
+        let known_hosts = cli_option_value(args, "--known-hosts").map(PathBuf::from);
+
+        let args = cli_positional_args(args);
+
+        self.cli_require_args(2, &args).map_err(|_| {
+            Self::cli_command_keys_usage();
+            "Missing <operation>"
+        })?;
+
+        let operation = args.get(2).ok_or("Missing <operation>")?;
+
+        if operation == "list" {
+            return self.cli_command_keys_list();
+        }
+
+        let host = args.get(3).ok_or_else(|| {
+            Self::cli_command_keys_usage();
+            "Missing <host>"
+        })?;
+
+        match operation.as_str() {
+            "show" => {
+                let key_pair = self.cli_keys_find(host)?;
+                self.cli_show_public_key(host, &key_pair);
+            },
+            "renew" => {
+                let key_pair = self.cli_keys_find(host)?;
+                self.cli_keys_print_repos(host);
+
+                if !assume_yes && !cli_confirm("Replace this key? The new key must be added to your account again.")? {
+                    return Ok(());
+                }
+
+                let key_pair = twinkle_keypair_renew(host, key_pair.key_type, &self.app_keys_dir)?;
+                self.cli_show_public_key(host, &key_pair);
+            },
+            "delete" => {
+                let key_pair = self.cli_keys_find(host)?;
+                self.cli_keys_print_repos(host);
+
+                if !assume_yes && !cli_confirm("Delete this key?")? {
+                    return Ok(());
+                }
+
+                twinkle_keypair_delete(&key_pair)?;
+            },
+            "trust" => {
+                let key_type = twinkle_keytype_for(host, &self.app_keys_dir);
+                let host_key = self.cli_keys_scan(host, key_type)?;
+
+                if cli_trust_host_key(&host_key, trusted_fingerprint.as_ref(), assume_yes)? {
+                    twinkle_hostkey_trust(&host_key, &self.app_keys_dir)?;
+                }
+            },
+            "retrust" => {
+                let key_type = twinkle_keytype_for(host, &self.app_keys_dir)
+                    .ok_or_else(|| format!("No trusted host key for {host}"))?;
+
+                let url = SshUrl {
+                    host: host.to_string(),
+                    ..Default::default()
+                };
+
+                let old_key = twinkle_hostkey_for(&url, key_type, &self.app_keys_dir, &self.forges_file)?;
+                let old_fingerprint = ssh_keygen_fingerprint(&old_key)?;
+                let change = twinkle_hostkey_changed(&old_key, &self.app_keys_dir);
+
+                // Scan, so pinned keys don't hide the new key
+                let scan_url = twinkle_ssh_scan_url(&url, &self.app_keys_dir);
+
+                let mut new_key = ssh_keyscan(&scan_url.host, scan_url.port, key_type)
+                    .map_err(|_| format!("Could not connect to {host}"))?;
+
+                new_key.host = host.to_string();
+
+                let new_fingerprint = new_key.fingerprint.clone()
+                    .ok_or("No host key fingerprint")?;
+
+                println!("{} {}", cli_dimmed("Old:"), old_fingerprint);
+
+                if let Some(reported) = change.as_ref().and_then(|change| change.new.as_ref()) {
+                    if reported != &new_fingerprint {
+                        return Err(format!(
+                            "{host} now offers {new_fingerprint}, but offered {reported} before. Not trusting either"
+                        ).into());
+                    }
+                }
+
+                if change.is_none() && new_fingerprint == old_fingerprint {
+                    println!("The host key for {host} hasn't changed");
+                    return Ok(());
+                }
+
+                if cli_trust_host_key(&new_key, trusted_fingerprint.as_ref(), assume_yes)? {
+                    twinkle_hostkey_trust(&new_key, &self.app_keys_dir)?;
+                }
+            },
+            "passphrase" => {
+                let key_pair = self.cli_keys_find(host)?;
+
+                if key_pair.is_agent_identity() {
+                    return Err(format!("The key for {host} is managed by ssh-agent").into());
+                }
+
+                let old = match ssh_keygen_is_encrypted(&key_pair) {
+                    true => cli_read_secret("Current passphrase: ")?,
+                    false => String::new(),
+                };
+
+                let new = cli_read_secret("New passphrase (empty for none): ")?;
+
+                if !new.is_empty() && cli_read_secret("New passphrase again: ")? != new {
+                    return Err("Passphrases don't match".into());
+                }
+
+                ssh_keygen_change_passphrase(&key_pair, &old, &new)?;
+            },
+            "agent" => {
+                let agent = SshAgent::from_env().ok_or("ssh-agent is not running")?;
+                let identities = agent.list()?;
+
+                let Some(fingerprint) = args.get(4) else {
+                    for public_key in identities {
+                        let fingerprint = ssh_keygen_fingerprint_public_key(&public_key)?;
+                        let comment = public_key.split_whitespace().nth(2).unwrap_or_default();
+
+                        println!("{}  {}", fingerprint, cli_dimmed(comment));
+                    }
+
+                    return Ok(());
+                };
+
+                let fingerprint = fingerprint.parse::<Fingerprint>()?;
+
+                let public_key = identities.into_iter()
+                    .find(|public_key| ssh_keygen_fingerprint_public_key(public_key).ok().as_ref() == Some(&fingerprint))
+                    .ok_or_else(|| format!("No identity with fingerprint {fingerprint} in ssh-agent"))?;
+
+                let key_pair = twinkle_keypair_from_agent(host, &public_key, &self.app_keys_dir)?;
+                self.cli_show_public_key(host, &key_pair);
+            },
+            "certificate" => {
+                let mut key_pair = self.cli_keys_find(host)?;
+
+                if let Some(path) = args.get(4) {
+                    key_pair = twinkle_keypair_set_certificate(&key_pair, Path::new(path))?;
+                }
+
+                let certificate = key_pair.certificate.as_ref()
+                    .ok_or_else(|| format!("No certificate for {host}"))?;
+
+                let valid = match certificate.valid_before {
+                    Some(before) => twinkle_pretty_datetime(before.timestamp()),
+                    None => "forever".into(),
+                };
+
+                println!("{} {}", cli_dimmed("Key ID:"), certificate.key_id);
+                println!("{} {}", cli_dimmed("Principals:"), certificate.principals.join(", "));
+                println!("{} {}", cli_dimmed("Valid until:"), valid);
+
+                if let Some(e) = twinkle_keypair_certificate_error(&key_pair) {
+                    return Err(e);
+                }
+            },
+            "import" => {
+                self.cli_command_keys_import(host, ssh_config, known_hosts)?;
+            },
+            "export" => {
+                let key_pair = self.cli_keys_find(host)?;
+                let url = SshUrl {
+                    host: host.to_string(),
+                    ..Default::default()
+                };
+
+                print!("{}", twinkle_ssh_config_export(&url, &key_pair, &self.app_keys_dir));
+            },
+            _ => {
+                Self::cli_command_keys_usage();
+                return Err("Unknown <operation>".into());
+            }
+        }
+
+        Ok(())
+    }
+
+
+    fn cli_command_keys_list(&self) -> Result<(), Box<dyn Error>> {
+        let key_pairs = twinkle_keypair_list(&self.app_keys_dir)?;
+
+        if key_pairs.is_empty() {
+            println!("No keys in {}", twinkle_pretty_dir(&self.app_keys_dir));
+            return Ok(());
+        }
+
+        let width = key_pairs.iter()
+            .map(|(host, _)| host.len())
+            .max()
+            .unwrap_or_default();
+
+        for (host, key_pair) in key_pairs {
+            let fingerprint = ssh_keygen_fingerprint_key_pair(&key_pair)
+                .map(|f| f.to_string())
+                .unwrap_or_else(|_| "–".into());
+
+            let mut notes = Vec::new();
+
+            match key_pair.is_agent_identity() {
+                true => notes.push("ssh-agent"),
+                false if ssh_keygen_is_encrypted(&key_pair) => notes.push("passphrase"),
+                false => {},
+            }
+
+            match twinkle_keypair_certificate_error(&key_pair) {
+                Some(_) => notes.push("certificate expired"),
+                None if key_pair.certificate.is_some() => notes.push("certificate"),
+                None => {},
+            }
+
+            let note = notes.join(", ");
+
+            println!("{}  {}  {}  {}",
+                cli_bold(&format!("{host:width$}")),
+                key_pair.key_type,
+                cli_dimmed(&fingerprint),
+                note);
+
+            for path in twinkle_keypair_repos(&host, &self.repos_home) {
+                println!("{:width$}  {}", "", twinkle_pretty_dir(&path));
+            }
+        }
+
+        Ok(())
+    }
+
+
+    /// Explicit paths must exist, the defaults in ~/.ssh are optional
+    fn cli_command_keys_import(
+        &self,
+        host: &str,
+        ssh_config: Option<PathBuf>,
+        known_hosts: Option<PathBuf>,
+    ) -> Result<(), Box<dyn Error>>
+    {
+        let ssh_dir = PathBuf::from(env::var("HOME")?).join(".ssh");
+
+        let config = match ssh_config {
+            Some(path) => Some(twinkle_ssh_config_import(host, &path, &self.app_keys_dir)?),
+            None => {
+                let path = ssh_dir.join("config");
+
+                match path.exists() {
+                    true => Some(twinkle_ssh_config_import(host, &path, &self.app_keys_dir)?),
+                    false => None,
+                }
+            },
+        };
+
+        let config = config.unwrap_or_default();
+
+        for (keyword, value) in [
+            ("HostName", config.HostName.clone()),
+            ("Port", config.Port.map(|port| port.to_string())),
+            ("ProxyJump", config.ProxyJump.clone()),
+        ] {
+            if let Some(value) = value {
+                println!("{} {}", cli_dimmed(&format!("{keyword}:")), value);
+            }
+        }
+
+        let known_hosts = known_hosts.unwrap_or_else(|| ssh_dir.join("known_hosts"));
+        let host_keys = twinkle_ssh_known_hosts_import(host, &config, &known_hosts, &self.app_keys_dir)?;
+
+        for host_key in host_keys {
+            println!("Trusted {} {}", host_key.key_type, ssh_keygen_fingerprint(&host_key)?);
+        }
+
+        Ok(())
+    }
+
+
+    fn cli_keys_find(&self, host: &str) -> Result<KeyPair, Box<dyn Error>> {
+        twinkle_keypair_find(host, &self.app_keys_dir)?
+            .ok_or_else(|| format!("No key for {host}").into())
+    }
+
+
+    fn cli_keys_print_repos(&self, host: &str) {
+        let paths = twinkle_keypair_repos(host, &self.repos_home);
+
+        if paths.is_empty() {
+            return;
+        }
+
+        println!("Used by:");
+
+        for path in paths {
+            println!("    {}", twinkle_pretty_dir(&path));
+        }
+    }
+
+
+    /// Fetches a fresh host key, of the type the host was set up with if known
+    fn cli_keys_scan(&self, host: &str, key_type: Option<KeyType>) -> Result<HostKey, Box<dyn Error>> {
+        let url = SshUrl {
+            host: host.to_string(),
+            ..Default::default()
+        };
+
+        let url = twinkle_ssh_scan_url(&url, &self.app_keys_dir);
+        let forge = twinkle_ssh_forge_for(&url.host, &self.forges_file);
+
+        let host_key = match key_type {
+            Some(key_type) => HostKey::for_known_host(&url, key_type, forge.as_ref()),
+            None => HostKey::negotiate(&url, forge.as_ref()),
+        };
+
+        let mut host_key = host_key.map_err(|_| format!("Could not connect to {host}"))?;
+        host_key.host = host.to_string();
+
+        if host_key.fingerprint.is_none() {
+            host_key.fingerprint = Some(ssh_keygen_fingerprint(&host_key)?);
+        }
+
+        Ok(host_key)
+    }
+}
@@ -0,0 +1,128 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::path::Path;
+
+use crate::app::App;
+use crate::twinkle::objects::repository::TwinkleRepository;
+use crate::twinkle::twinkle_pretty::twinkle_pretty_size;
+
+use crate::twinkle::twinkle_lfs::{
+    twinkle_lfs_migrate,
+    twinkle_lfs_migrations,
+    twinkle_lfs_store_size,
+    twinkle_lfs_usage,
+};
+
+use super::util::*;
+
+
+impl App {
+    fn cli_command_lfs_usage() {
+        println!("Usage: twinkle lfs migrate [path] [--yes]");
+        println!("                   usage [path]");
+        println!("                   prune [path]");
+        println!();
+        println!("Migrate replaces per-file entries in .gitattributes with patterns, like \"*.psd\".");
+        println!("Patterns come from twinkle.lfs.patterns, or the file's extension if it's binary.");
+        println!();
+        println!("Prune deletes LFS objects that aren't checked out, pushed or from the last");
+        println!("twinkle.lfs.recentDays of history.");
+        println!();
+    }
+
+
+    pub fn cli_command_lfs(&self, args: &[String]) -> Result<(), Box<dyn Error>> {
+        let assume_yes = cli_option_flag(args, "--yes");
+        let args = cli_positional_args(args);
+
+        let Some(operation) = args.get(2) else {
+            Self::cli_command_lfs_usage();
+            return Err("Missing <operation>".into());
+        };
+
+        let default_path = ".".to_string();
+        let path = self.cli_prepare_path(Path::new(args.get(3).unwrap_or(&default_path)))?;
+        let repo = TwinkleRepository::new(&path);
+
+        if repo.git.lfs_version().is_none() {
+            return Err("Git LFS is not installed".into());
+        }
+
+        match operation.as_str() {
+            "migrate" => Self::cli_command_lfs_migrate(&repo, assume_yes),
+            "usage" => Self::cli_command_lfs_disk_usage(&repo),
+            "prune" => {
+                let before = twinkle_lfs_store_size(&repo);
+                repo.git.lfs_prune(repo.lfs_recent_days())?;
+
+                let freed = before.saturating_sub(twinkle_lfs_store_size(&repo));
+                println!("Freed {}", cli_bold(&twinkle_pretty_size(freed)));
+                Ok(())
+            },
+            _ => {
+                Self::cli_command_lfs_usage();
+                Err("Unknown operation".into())
+            },
+        }
+    }
+
+
+    fn cli_command_lfs_migrate(repo: &TwinkleRepository, assume_yes: bool) -> Result<(), Box<dyn Error>> {
+        let migrations = twinkle_lfs_migrations(repo)?;
+
+        if migrations.is_empty() {
+            println!("Nothing to migrate");
+            return Ok(());
+        }
+
+        println!();
+
+        for migration in &migrations {
+            println!("    {} {} {}", migration.path, cli_dimmed("→"), cli_bold(&migration.pattern));
+        }
+
+        println!();
+
+        if !assume_yes && !cli_confirm("Track these with patterns instead?")? {
+            return Ok(());
+        }
+
+        twinkle_lfs_migrate(repo)?;
+        println!("Done. The changes to .gitattributes are committed on the next sync.");
+
+        Ok(())
+    }
+
+
+    fn cli_command_lfs_disk_usage(repo: &TwinkleRepository) -> Result<(), Box<dyn Error>> {
+        let usage = twinkle_lfs_usage(&repo.git.lfs_ls_files_sizes()?);
+
+        println!();
+        println!("    {}", cli_dimmed(&format!("{:>10}  {:>10}  {}", "Local", "Remote", "Folder")));
+
+        for entry in &usage {
+            println!("    {:>10}  {:>10}  {}",
+                twinkle_pretty_size(entry.local),
+                twinkle_pretty_size(entry.remote),
+                entry.dir.display(),
+            );
+        }
+
+        let local: u64 = usage.iter().map(|entry| entry.local).sum();
+        let remote: u64 = usage.iter().map(|entry| entry.remote).sum();
+
+        println!();
+        println!("    {:>10}  {:>10}  {}", twinkle_pretty_size(local), twinkle_pretty_size(remote), cli_bold("Total"));
+        println!();
+        println!("    {} {}", cli_dimmed("Stored in .git/lfs, including old versions:"), twinkle_pretty_size(twinkle_lfs_store_size(repo)));
+        println!();
+
+        Ok(())
+    }
+}
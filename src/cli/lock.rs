@@ -0,0 +1,71 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use crate::app::App;
+use crate::twinkle::objects::repository::TwinkleRepository;
+use crate::twinkle::twinkle_lock::{ twinkle_lock, twinkle_unlock };
+
+use super::util::*;
+
+
+impl App {
+    fn cli_command_lock_usage() {
+        println!("Usage: twinkle lock <path>");
+        println!("       twinkle unlock <path> [--force]");
+        println!();
+        println!("Only files matching twinkle.lfs.lockable can be locked. They're read-only until then.");
+        println!();
+    }
+
+
+    pub fn cli_command_lock(&self, args: &[String]) -> Result<(), Box<dyn Error>> {
+        let (repo, path) = self.cli_lock_prepare(args)?;
+        twinkle_lock(&repo, &path)?;
+
+        println!("Locked {}. Unlock it when you're done.", cli_bold(&path.to_string_lossy()));
+        Ok(())
+    }
+
+
+    pub fn cli_command_unlock(&self, args: &[String]) -> Result<(), Box<dyn Error>> {
+        let force = cli_option_flag(args, "--force");
+
+        let (repo, path) = self.cli_lock_prepare(args)?;
+        twinkle_unlock(&repo, &path, force)?;
+
+        println!("Unlocked {}", cli_bold(&path.to_string_lossy()));
+        Ok(())
+    }
+
+
+    /// The repository a file is in, and its path within it
+    fn cli_lock_prepare(&self, args: &[String]) -> Result<(TwinkleRepository, PathBuf), Box<dyn Error>> {
+        let args = cli_positional_args(args);
+
+        let Some(path) = args.get(2) else {
+            Self::cli_command_lock_usage();
+            return Err("Missing <path>".into());
+        };
+
+        let path = fs::canonicalize(path)?;
+        let parent = path.parent().unwrap_or(Path::new("/"));
+
+        let repo_path = self.cli_prepare_path(parent)?;
+        let repo = TwinkleRepository::new(&repo_path);
+
+        if repo.git.lfs_version().is_none() {
+            return Err("Git LFS is not installed".into());
+        }
+
+        let path = path.strip_prefix(fs::canonicalize(&repo_path)?)?.to_path_buf();
+        Ok((repo, path))
+    }
+}
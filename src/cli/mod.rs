@@ -16,7 +16,17 @@ pub mod checks {
 
 pub mod args;
 pub mod clone;
+pub mod compact;
+pub mod confirm_deletions;
+pub mod credential;
+pub mod fetch_history;
 pub mod init;
+pub mod keys;
+pub mod lfs;
+pub mod lock;
+pub mod relay;
+pub mod select;
 pub mod sync;
 pub mod status;
+pub mod trash;
 pub mod util;
@@ -0,0 +1,35 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+
+use crate::app::App;
+use crate::log;
+use crate::twinkle::twinkle_relay::twinkle_relay_listen;
+
+use super::util::*;
+
+
+impl App {
+    fn cli_command_relay_usage() {
+        println!("Usage: twinkle relay --listen=<address:port>");
+        println!();
+        println!("Then point clients at it: git config twinkle.notify.url ws://<host:port>");
+        println!();
+    }
+
+
+    pub fn cli_command_relay(&self, args: &[String]) -> Result<(), Box<dyn Error>> {
+        let Some(address) = cli_option_value(args, "--listen") else {
+            Self::cli_command_relay_usage();
+            return Err("Missing --listen".into());
+        };
+
+        log::log(&format!("Relaying notifications on {}", cli_bold(address)));
+        twinkle_relay_listen(address)
+    }
+}
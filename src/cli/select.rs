@@ -0,0 +1,77 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::path::Path;
+
+use crate::app::App;
+use crate::twinkle::objects::repository::TwinkleRepository;
+use crate::twinkle::twinkle_select::{ twinkle_select_add, twinkle_select_all, twinkle_select_remove };
+
+use super::util::*;
+
+
+impl App {
+    fn cli_command_select_usage() {
+        println!("Usage: twinkle select list [path]");
+        println!("                      add <folder> [path]");
+        println!("                      remove <folder> [path]");
+        println!("                      all [path]");
+        println!();
+        println!("Folders are relative to the root of the repository, like \"art/covers\".");
+        println!("Files in the root are always synced.");
+        println!();
+    }
+
+
+    pub fn cli_command_select(&self, args: &[String]) -> Result<(), Box<dyn Error>> {
+        let args = cli_positional_args(args);
+        let default_operation = "list".to_string();
+        let operation = args.get(2).unwrap_or(&default_operation);
+
+        let (folder, path) = match operation.as_str() {
+            "add" | "remove" => match args.get(3) {
+                Some(folder) => (Some(Path::new(folder)), args.get(4)),
+                None => {
+                    Self::cli_command_select_usage();
+                    return Err("Missing <folder>".into());
+                },
+            },
+            _ => (None, args.get(3)),
+        };
+
+        let default_path = ".".to_string();
+        let path = self.cli_prepare_path(Path::new(path.unwrap_or(&default_path)))?;
+        let repo = TwinkleRepository::new(&path);
+
+        match (operation.as_str(), folder) {
+            ("list", _) => {},
+            ("add", Some(folder)) => twinkle_select_add(&repo, folder)?,
+            ("remove", Some(folder)) => twinkle_select_remove(&repo, folder)?,
+            ("all", _) => twinkle_select_all(&repo)?,
+            _ => {
+                Self::cli_command_select_usage();
+                return Err("Unknown operation".into());
+            },
+        }
+
+        let selection = repo.select();
+
+        if selection.is_empty() {
+            println!("Syncing all folders");
+            return Ok(());
+        }
+
+        println!("Syncing the files in the root, and:");
+
+        for dir in &selection {
+            println!("    {}", cli_bold(&dir.to_string_lossy()));
+        }
+
+        Ok(())
+    }
+}
@@ -16,6 +16,9 @@ use crate::app::App;
 // use crate::twinkle::twinkle_clone::twinkle_clone_complete;
 // use crate::twinkle::twinkle_keys::twinkle_hostkey_trust;
 use crate::twinkle::twinkle_pretty::{  twinkle_pretty_bool, twinkle_pretty_datetime, twinkle_pretty_dir };
+use crate::twinkle::twinkle_limits::twinkle_oversize_files;
+use crate::twinkle::twinkle_lock::twinkle_locks;
+use crate::twinkle::twinkle_read_only::twinkle_read_only_aside_dir;
 // use crate::twinkle::twinkle_util::twinkle_settings_url_for;
 
 
@@ -44,7 +47,53 @@ impl App {
         println!("    {} {}", cli_dimmed("Enabled:"), repo.enabled());
         println!("         {} {}", cli_dimmed("ID:"), repo.id().unwrap_or("–".into()));
         println!("        {} {}", cli_dimmed("LFS:"), twinkle_pretty_bool(repo.lfs_enabled()));
+        println!("       {} {}", cli_dimmed("Mode:"), if repo.read_only() { "read-only" } else { "read-write" });
+        println!("    {} {}", cli_dimmed("History:"), if repo.git.rev_parse_is_shallow() { "recent" } else { "full" });
+
+        let selection = repo.select();
+
+        if !selection.is_empty() {
+            let selection: Vec<_> = selection.iter().map(|dir| dir.to_string_lossy()).collect();
+            println!("   {} {}", cli_dimmed("Selected:"), selection.join(", "));
+        }
+
+        let aside_dir = twinkle_read_only_aside_dir(&repo);
+
+        if repo.read_only() && aside_dir.exists() {
+            println!("  {} {}", cli_dimmed("Set aside:"), twinkle_pretty_dir(&aside_dir));
+        }
         // println!("       {} {}", cli_dimmed("User:"), repo.user().unwrap_or("default"));
+
+        if let Some(commit) = repo.deletions_paused() {
+            println!();
+            println!("  {}", cli_yellow(&format!("Paused. {commit} deletes too many files")));
+            println!("    {}", cli_dimmed("Run `twinkle confirm-deletions` to sync it, or add --undo to drop it"));
+        }
+
+        let oversize = twinkle_oversize_files(&repo).unwrap_or_default();
+
+        if !oversize.is_empty() {
+            println!();
+            println!("  {}", cli_yellow(&format!("Not syncing {} file(s) over the size limit:", oversize.len())));
+
+            for file in &oversize {
+                println!("    {}", cli_dimmed(&file.to_string()));
+            }
+        }
+
+        if repo.lfs_enabled() && !repo.lfs_lockable().is_empty() {
+            let locks = twinkle_locks(&repo).unwrap_or_default();
+
+            if !locks.is_empty() {
+                println!();
+                println!("  {}", cli_dimmed(&format!("{} locked file(s):", locks.len())));
+
+                for lock in &locks {
+                    println!("    {lock}");
+                }
+            }
+        }
+
         println!();
         println!(" {} {}", cli_dimmed("Last check:"), twinkle_pretty_datetime(repo.last_checked().unwrap_or(0)));
         println!("  {} {}", cli_dimmed("Last sync:"), twinkle_pretty_datetime(repo.last_synced().unwrap_or(0)));
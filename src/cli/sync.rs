@@ -18,6 +18,7 @@ use crate::log;
 
 use crate::twinkle::objects::repository::TwinkleRepository;
 use crate::twinkle::twinkle_sync::twinkle_sync;
+use crate::twinkle::twinkle_trash::twinkle_trash_init;
 // use crate::twinkle::twinkle_clone::twinkle_clone_prepare;
 // use crate::twinkle::twinkle_clone::twinkle_clone_start;
 // use crate::twinkle::twinkle_clone::twinkle_clone_complete;
@@ -50,9 +51,12 @@ impl App {
 
         // TODO: Stop if no user set or let git commit fail?
 
+        twinkle_trash_init(&repo, &self.app_data_home)?;
+        repo.set_forges_file(&self.forges_file)?;
+
         let dir = twinkle_pretty_dir(&repo.path);
         let remote_url = repo.remote_url().ok_or("Missing remote_url")?;
-        let remote = cli_dimmed(&format!("– {}…\n", remote_url.original));
+        let remote = cli_dimmed(&format!("– {}…\n", remote_url.original()));
         let once = false;
 
         log::log(&format!("Syncing {} {}", cli_bold(&dir), remote));
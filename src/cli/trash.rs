@@ -0,0 +1,101 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::path::Path;
+
+use crate::app::App;
+use crate::twinkle::objects::repository::TwinkleRepository;
+use crate::twinkle::twinkle_pretty::twinkle_pretty_dir;
+
+use crate::twinkle::twinkle_trash::{
+    twinkle_trash_empty,
+    twinkle_trash_init,
+    twinkle_trash_list,
+    twinkle_trash_restore,
+};
+
+use super::util::*;
+
+
+impl App {
+    fn cli_command_trash_usage() {
+        println!("Usage: twinkle trash list [path]");
+        println!("                     restore <file|folder> [path]");
+        println!("                     empty [path] [--yes]");
+        println!();
+        println!("Files are relative to the root of the repository, like \"art/cover.psd\".");
+        println!("Restored files are put back where they were, or next to what's there now.");
+        println!();
+    }
+
+
+    pub fn cli_command_trash(&self, args: &[String]) -> Result<(), Box<dyn Error>> {
+        let assume_yes = cli_option_flag(args, "--yes");
+        let args = cli_positional_args(args);
+        let default_operation = "list".to_string();
+        let operation = args.get(2).unwrap_or(&default_operation);
+
+        let (file, path) = match operation.as_str() {
+            "restore" => match args.get(3) {
+                Some(file) => (Some(Path::new(file)), args.get(4)),
+                None => {
+                    Self::cli_command_trash_usage();
+                    return Err("Missing <file>".into());
+                },
+            },
+            _ => (None, args.get(3)),
+        };
+
+        let default_path = ".".to_string();
+        let path = self.cli_prepare_path(Path::new(path.unwrap_or(&default_path)))?;
+        let repo = TwinkleRepository::new(&path);
+
+        let trash_dir = twinkle_trash_init(&repo, &self.app_data_home)?;
+
+        match (operation.as_str(), file) {
+            ("list", _) => {
+                let items = twinkle_trash_list(&repo)?;
+
+                if items.is_empty() {
+                    println!("The trash is empty");
+                    return Ok(());
+                }
+
+                for item in &items {
+                    println!("    {}  {}", cli_dimmed(&item.trashed), item.path.display());
+                }
+
+                println!();
+                println!("{}", cli_dimmed(&format!(
+                    "In {}. Kept for {} days",
+                    twinkle_pretty_dir(&trash_dir),
+                    repo.trash_days(),
+                )));
+            },
+            ("restore", Some(file)) => {
+                for target in twinkle_trash_restore(&repo, file)? {
+                    println!("Restored {}", cli_bold(&target.to_string_lossy()));
+                }
+            },
+            ("empty", _) => {
+                if !assume_yes && !cli_confirm("Remove everything from the trash?")? {
+                    return Ok(());
+                }
+
+                let count = twinkle_trash_empty(&repo)?;
+                println!("Removed {count} file(s) from the trash");
+            },
+            _ => {
+                Self::cli_command_trash_usage();
+                return Err("Unknown operation".into());
+            },
+        }
+
+        Ok(())
+    }
+}
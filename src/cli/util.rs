@@ -7,10 +7,19 @@
 
 use std::error::Error;
 use std::fs;
+use std::io::{ self, Write };
 use std::path::{ Path, PathBuf };
+use std::process::{ Command, Stdio };
 
 use crate::app::App;
 use crate::git::objects::environment::GitEnvironment;
+use crate::git::objects::remote_url::GitRemoteUrl;
+use crate::ssh::keys::fingerprint::Fingerprint;
+use crate::ssh::keys::host_key::HostKey;
+use crate::log;
+use crate::ssh::keys::key_pair::KeyPair;
+use crate::twinkle::twinkle_clone::TwinkleCloneError;
+use crate::twinkle::twinkle_ssh::twinkle_ssh_forges;
 
 
 impl App {
@@ -29,6 +38,141 @@ impl App {
         let path = fs::canonicalize(path)?;
         GitEnvironment::new(&path).rev_parse_show_toplevel()
     }
+
+
+    /// Parses a remote URL and makes local paths absolute. Known forges
+    /// fill in their user: "github.com:hbons/Twinkle"
+    pub fn cli_parse_remote_url(&self, s: &str) -> Result<GitRemoteUrl, Box<dyn Error>> {
+        let url = match s.parse::<GitRemoteUrl>() {
+            Ok(url) => url,
+            Err(e) => twinkle_ssh_forges(&self.forges_file)
+                .with_default_user(s)
+                .ok_or(e)?
+                .parse::<GitRemoteUrl>()?,
+        };
+
+        match url {
+            GitRemoteUrl::Path(path) => Ok(GitRemoteUrl::Path(fs::canonicalize(path)?)),
+            url => Ok(url),
+        }
+    }
+
+
+    pub fn cli_show_public_key(&self, host: &str, key_pair: &KeyPair) {
+        let forges = twinkle_ssh_forges(&self.forges_file);
+        let url = forges.get(host).and_then(|forge| forge.settings_url.as_deref());
+        let url = url.unwrap_or(host);
+
+        println!();
+        println!("First, add this SSH key to {}:", cli_link(url, None));
+        println!("{}", cli_bold(&key_pair.public_key));
+        println!();
+    }
+}
+
+
+/// Arguments that aren't `--options`
+pub fn cli_positional_args(args: &[String]) -> Vec<String> {
+    args.iter()
+        .filter(|arg| !arg.starts_with("--"))
+        .cloned()
+        .collect()
+}
+
+/// "--yes"
+pub fn cli_option_flag(args: &[String], name: &str) -> bool {
+    args.iter().any(|arg| arg == name)
+}
+
+/// "--interval=60" -> "60"
+pub fn cli_option_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .find_map(|arg| arg.strip_prefix(name)?.strip_prefix('='))
+}
+
+
+/// Asks a yes/no question, defaults to no
+pub fn cli_confirm(question: &str) -> Result<bool, Box<dyn Error>> {
+    print!("{question} {} ", cli_dimmed("[y/N]"));
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    Ok(matches!(input.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Reads a line from stdin without showing it, for tokens and passphrases
+pub fn cli_read_secret(prompt: &str) -> Result<String, Box<dyn Error>> {
+    print!("{prompt}");
+    io::stdout().flush()?;
+
+    // Fails when stdin isn't a terminal, like when piped, and then there's nothing to hide
+    let is_hidden = cli_stty("-echo");
+
+    let mut input = String::new();
+    let read = io::stdin().read_line(&mut input);
+
+    if is_hidden {
+        cli_stty("echo");
+        println!(); // The newline wasn't shown either
+    }
+
+    read?;
+    Ok(input.trim_end_matches(['\r', '\n']).to_string())
+}
+
+
+fn cli_stty(setting: &str) -> bool {
+    // Docs: https://man7.org/linux/man-pages/man1/stty.1.html
+
+    Command::new("stty")
+        .arg(setting)
+        .stdin(Stdio::inherit()) // The terminal to change
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+
+pub fn cli_wait_for_enter(message: &str) -> Result<(), Box<dyn Error>> {
+    print!("{message}");
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    Ok(())
+}
+
+
+/// Shows the host key and decides whether to trust it
+pub fn cli_trust_host_key(
+    host_key: &HostKey,
+    trusted_fingerprint: Option<&Fingerprint>,
+    assume_yes: bool,
+) -> Result<bool, Box<dyn Error>>
+{
+    let fingerprint = host_key.fingerprint.as_ref()
+        .ok_or("No host key fingerprint")?;
+
+    println!("Connected to {} {} {}",
+        host_key.host, cli_dimmed("–"), cli_dimmed(&fingerprint.to_string()));
+
+    if let Some(trusted_fingerprint) = trusted_fingerprint {
+        if trusted_fingerprint != fingerprint {
+            log::error(&format!("Host key fingerprint {fingerprint} does not match {trusted_fingerprint}"));
+            return Err(Box::new(TwinkleCloneError::NeedsTrust(host_key.clone())));
+        }
+
+        return Ok(true);
+    }
+
+    if assume_yes {
+        return Ok(true);
+    }
+
+    cli_confirm("Trust this host?")
 }
 
 
@@ -23,4 +23,19 @@ impl GitEnvironment {
             _  => Ok(branch),
         }
     }
+
+
+    /// Also deletes unmerged branches. Remote-tracking branches are like "origin/main"
+    pub fn branch_delete(&self, branch: &GitReference, remote_tracking: bool) -> Result<(), Box<dyn Error>> {
+        let mut args = vec!["--delete", "--force"];
+
+        if remote_tracking {
+            args.push("--remotes");
+        }
+
+        args.extend(["--", branch.as_str()]);
+        self.run("branch", &args)?;
+
+        Ok(())
+    }
 }
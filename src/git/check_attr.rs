@@ -0,0 +1,32 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::path::Path;
+
+use super::objects::environment::GitEnvironment;
+
+
+impl GitEnvironment {
+    // Docs: https://git-scm.com/docs/git-check-attr
+
+    /// The value of `attribute` for `path`, None if it's unspecified
+    pub fn check_attr(&self, attribute: &str, path: &Path) -> Option<String> {
+        let output = self.run("check-attr", &[
+            attribute,
+            "--", // Safety: No more flags coming after this
+            path.to_str()?,
+        ]).ok()?;
+
+        // "path/to/file: filter: lfs"
+        let value = output.stdout.trim().rsplit(": ").next()?;
+
+        match value {
+            "unspecified" | "" => None,
+            value => Some(value.to_string()),
+        }
+    }
+}
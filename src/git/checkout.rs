@@ -25,6 +25,20 @@ impl GitEnvironment {
     }
 
 
+    /// Switches to `branch`, creating or resetting it at `start`.
+    /// Changes to files that are the same in both commits are kept.
+    pub fn checkout_new_branch(&self, branch: &GitReference, start: &str) -> Result<(), Box<dyn Error>> {
+        self.run("checkout", &[
+            "--quiet",
+            "-B",
+            branch,
+            start,
+        ])?;
+
+        Ok(())
+    }
+
+
     pub fn checkout_file(&self, path: &Path, extra_arg: Option<&str>) -> Result<(), Box<dyn Error>> {
         self.run("checkout", &[
             extra_arg.unwrap_or_default(),
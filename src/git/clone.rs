@@ -8,14 +8,26 @@
 use std::error::Error;
 use std::path::Path;
 
-use crate::ssh::objects::url::SshUrl;
 use super::objects::environment::GitEnvironment;
+use super::objects::remote_url::GitRemoteUrl;
 
 
 impl GitEnvironment {
     // Docs: https://git-scm.com/docs/git-clone
 
     pub fn clone(&self, url: &str, directory: Option<&Path>, depth: Option<u32>) -> Result<GitEnvironment, Box<dyn Error>> {
+        self.clone_with_config(url, directory, depth, &[])
+    }
+
+
+    /// Sets config in the new repository, before the initial fetch
+    pub fn clone_with_config(&self,
+        url: &str,
+        directory: Option<&Path>,
+        depth: Option<u32>,
+        config: &[(&str, &str)],
+    ) -> Result<GitEnvironment, Box<dyn Error>>
+    {
         let mut args: Vec<&str> = Vec::new();
 
         let mut depth_str = "--depth=".to_string();
@@ -24,6 +36,15 @@ impl GitEnvironment {
             args.push(&depth_str);
         }
 
+        let config: Vec<String> = config.iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect();
+
+        for option in &config {
+            args.push("--config");
+            args.push(option);
+        }
+
         args.push("--no-checkout");
         args.push("--progress");
         args.push("--"); // Safety: No more flags coming after this
@@ -36,10 +57,10 @@ impl GitEnvironment {
 
         self.run("clone", &args)?;
 
-        let url = url.parse::<SshUrl>()?;
+        let url = url.parse::<GitRemoteUrl>()?;
         let dir_name = match directory {
             Some(d) => d.file_name().ok_or("Could not get name from path")?,
-            None => url.path.file_name().ok_or("Could not get name from url")?,
+            None => url.path().file_name().ok_or("Could not get name from url")?,
         };
 
         let mut git_env = Clone::clone(self);
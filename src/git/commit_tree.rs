@@ -0,0 +1,62 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::fs;
+
+use chrono::{ DateTime, Utc };
+
+use super::config::K_COMMIT_GPG_SIGN;
+use super::objects::environment::GitEnvironment;
+use super::objects::id::GitId;
+use super::objects::user::GitUser;
+
+
+impl GitEnvironment {
+    // Docs: https://git-scm.com/docs/git-commit-tree
+
+    /// Creates a commit without touching the branch, index or files
+    pub fn commit_tree(&self,
+        tree: &GitId,
+        parent: Option<&GitId>,
+        author: &GitUser,
+        timestamp: DateTime<Utc>,
+        message: &str,
+    ) -> Result<GitId, Box<dyn Error>>
+    {
+        let path = ".git/COMMIT_TREE_EDITMSG".to_string();
+        let abs_path = self.working_dir.join(&path);
+        fs::write(&abs_path, message)?; // Use a file to prevent encoding problems
+
+        let mut args = vec![tree.as_str(), "-F", &path];
+
+        if let Some(parent) = parent {
+            args.extend(["-p", parent.as_str()]);
+        }
+
+        // Not implied by the config like with git-commit
+        if self.config_get(K_COMMIT_GPG_SIGN).is_some_and(|output| output.stdout == "true") {
+            args.push("-S");
+        }
+
+        let date = format!("@{} +0000", timestamp.timestamp());
+
+        let env: Vec<(String, String)> = vec![
+            ("GIT_AUTHOR_NAME".into(), author.name().into()),
+            ("GIT_AUTHOR_EMAIL".into(), author.email().into()),
+            ("GIT_AUTHOR_DATE".into(), date.clone()),
+            ("GIT_COMMITTER_NAME".into(), author.name().into()),
+            ("GIT_COMMITTER_EMAIL".into(), author.email().into()),
+            ("GIT_COMMITTER_DATE".into(), date),
+        ];
+
+        let output = self.run_with_env("commit-tree", &args, env);
+        fs::remove_file(abs_path)?;
+
+        Ok(output?.stdout)
+    }
+}
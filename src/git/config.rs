@@ -13,6 +13,7 @@ use super::objects::output::GitOutput;
 
 
 pub const K_CORE_SSH_COMMAND: &str = "core.sshCommand";
+pub const K_CREDENTIAL_HELPER: &str = "credential.helper";
 pub const K_REMOTE_ORIGIN_URL: &str = "remote.origin.url";
 
 pub const K_USER_NAME: &str = "user.name";
@@ -40,6 +41,32 @@ impl GitEnvironment {
     }
 
 
+    pub fn config_unset(&self, name: &str) -> Result<(), Box<dyn Error>> {
+        _ = self.run("config", &["--local", "--unset-all", name]); // Fails if it wasn't set
+        Ok(())
+    }
+
+
+    /// All values of a setting that can be given more than once
+    pub fn config_get_all(&self, name: &str) -> Vec<String> {
+        match self.run("config", &["--local", "--get-all", name]) {
+            Ok(output) => output.stdout.lines().map(String::from).collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    pub fn config_set_all(&self, name: &str, values: &[&str])
+    -> Result<(), Box<dyn Error>> {
+        _ = self.run("config", &["--local", "--unset-all", name]); // Fails if it wasn't set
+
+        for value in values {
+            self.run("config", &["--local", "--add", name, value])?;
+        }
+
+        Ok(())
+    }
+
+
     pub fn config_file_get(
         &self,
         file: &Path,
@@ -60,6 +87,34 @@ impl GitEnvironment {
         self.run("config", &["--file", &file, name, value])
     }
 
+    pub fn config_file_unset(&self,
+        file: &Path,
+        name: &str,
+    ) -> Result<GitOutput, Box<dyn Error>>
+    {
+        let file = file.to_string_lossy().to_string();
+        self.run("config", &["--file", &file, "--unset", name])
+    }
+
+    /// Reads a config file as it is in a commit, like "main:.twinkle/config"
+    pub fn config_blob_get(
+        &self,
+        blob: &str,
+        name: &str,
+    ) -> Option<GitOutput>
+    {
+        self.run("config", &["--blob", blob, name]).ok()
+    }
+
+    pub fn config_file_remove_section(&self,
+        file: &Path,
+        name: &str,
+    ) -> Result<GitOutput, Box<dyn Error>>
+    {
+        let file = file.to_string_lossy().to_string();
+        self.run("config", &["--file", &file, "--remove-section", name])
+    }
+
 
     pub fn config_list(&self) -> Result<GitOutput, Box<dyn Error>> {
         self.run("config", &["--local", "--list"])
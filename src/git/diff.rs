@@ -0,0 +1,34 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::str::FromStr;
+
+use super::objects::change::GitChange;
+use super::objects::environment::GitEnvironment;
+
+
+impl GitEnvironment {
+    // Docs: https://git-scm.com/docs/git-diff
+
+    /// The changes on `theirs` since it split off from `ours`, like what a merge brings in
+    pub fn diff_incoming(&self, ours: &str, theirs: &str) -> Result<Vec<GitChange>, Box<dyn Error>> {
+        let output = self.run("diff", &[
+            "--name-status",
+            "--find-renames=100%", // Only unchanged renames as 'R100' lines, the rest as 'D' and 'A'
+            "--no-color",
+            "--no-ext-diff",
+            &format!("{ours}...{theirs}"),
+            "--",
+        ])?;
+
+        output.stdout.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(GitChange::from_str)
+            .collect()
+    }
+}
@@ -37,4 +37,22 @@ impl GitEnvironment {
 
         Ok(())
     }
+
+
+    /// Fetches all of the history, for clones made with --depth
+    pub fn fetch_unshallow(&self,
+        remote: &GitRemote,
+        branch: &GitReference,
+    ) -> Result<(), Box<dyn Error>>
+    {
+        self.run("fetch", &[
+            "--no-recurse-submodules",
+            "--progress",
+            "--unshallow",
+            remote,
+            branch,
+        ])?;
+
+        Ok(())
+    }
 }
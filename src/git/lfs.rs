@@ -12,6 +12,8 @@ use std::os::unix::fs::PermissionsExt;
 use std::path::{ Path, PathBuf };
 
 use super::objects::environment::GitEnvironment;
+use super::objects::lfs_file::GitLfsFile;
+use super::objects::lfs_lock::GitLfsLock;
 
 
 impl GitEnvironment {
@@ -74,13 +76,51 @@ impl GitEnvironment {
 
 
 impl GitEnvironment {
-    /// Fetching Git LFS objects separately benefits from concurrency
-    pub fn lfs_fetch(&self) -> Result<(), Box<dyn Error>> {
-        self.run("lfs", &["fetch"])?;
+    /// Fetching Git LFS objects separately benefits from concurrency.
+    /// Only fetches files in `include`, if it's not empty.
+    pub fn lfs_fetch(&self, include: &[PathBuf]) -> Result<(), Box<dyn Error>> {
+        let include = lfs_include_arg(include)?;
+
+        let mut args = vec!["fetch"];
+        args.extend(include.as_deref());
+
+        self.run("lfs", &args)?;
+        Ok(())
+    }
+
+
+    /// Also fetches objects from the last `days` of commits
+    pub fn lfs_fetch_recent(&self, days: u32, include: &[PathBuf]) -> Result<(), Box<dyn Error>> {
+        let include = lfs_include_arg(include)?;
+
+        let mut args = vec!["fetch", "--recent"];
+        args.extend(include.as_deref());
+
+        self.run_with_env("lfs", &args, lfs_recent_env(days))?;
+        Ok(())
+    }
+
+
+    /// Deletes local objects that aren't checked out, recent or unpushed
+    pub fn lfs_prune(&self, days: u32) -> Result<(), Box<dyn Error>> {
+        self.run_with_env("lfs", &["prune"], lfs_recent_env(days))?;
         Ok(())
     }
 
 
+    /// The LFS files in HEAD, with their sizes and whether they're downloaded
+    pub fn lfs_ls_files_sizes(&self) -> Result<Vec<GitLfsFile>, Box<dyn Error>> {
+        let output = self.run("lfs", &["ls-files", "--debug"])?;
+
+        let files = output.stdout
+            .split("\n\n")
+            .filter_map(|block| block.parse::<GitLfsFile>().ok())
+            .collect();
+
+        Ok(files)
+    }
+
+
     /// Looks at .gitattributes and committed/staged pointer files
     pub fn lfs_ls_files(&self) -> Result<Vec<PathBuf>, Box<dyn Error>> {
         let output = self.run("lfs", &["ls-files", "--name-only"])?;
@@ -109,6 +149,80 @@ impl GitEnvironment {
     }
 
 
+    /// Puts a pattern, like "*.psd", in .gitattributes under LFS filters.
+    /// Lockable files are checked out read-only until they're locked.
+    pub fn lfs_track_pattern(&self, pattern: &str, lockable: bool) -> Result<(), Box<dyn Error>> {
+        let mut args = vec!["track"];
+
+        if lockable {
+            args.push("--lockable");
+        }
+
+        args.extend(["--", pattern]); // Safety: No more flags coming after this
+
+        self.run("lfs", &args)?;
+
+        Ok(())
+    }
+
+
+    /// Removes a path or pattern from .gitattributes.
+    /// Files already in LFS stay there until they're changed.
+    pub fn lfs_untrack(&self, pattern: &str) -> Result<(), Box<dyn Error>> {
+        self.run("lfs", &[
+            "untrack",
+            "--", // Safety: No more flags coming after this
+            pattern,
+        ])?;
+
+        Ok(())
+    }
+
+
+    /// Docs: https://github.com/git-lfs/git-lfs/blob/main/docs/man/git-lfs-lock.adoc
+    pub fn lfs_lock(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        self.run("lfs", &[
+            "lock",
+            "--", // Safety: No more flags coming after this
+            &path.to_string_lossy(),
+        ])?;
+
+        Ok(())
+    }
+
+
+    /// Forcing also removes other people's locks, if the server allows it
+    pub fn lfs_unlock(&self, path: &Path, force: bool) -> Result<(), Box<dyn Error>> {
+        let mut args = vec!["unlock"];
+
+        if force {
+            args.push("--force");
+        }
+
+        let path = path.to_string_lossy();
+        args.extend(["--", &path]); // Safety: No more flags coming after this
+
+        self.run("lfs", &args)?;
+        Ok(())
+    }
+
+
+    /// All locks on the server. Tries to tell which ones are ours,
+    /// which not all servers support
+    pub fn lfs_locks(&self) -> Result<Vec<GitLfsLock>, Box<dyn Error>> {
+        let output = match self.run("lfs", &["locks", "--verify"]) {
+            Ok(output) => output,
+            Err(_) => self.run("lfs", &["locks"])?,
+        };
+
+        let locks = output.stdout.lines()
+            .filter_map(|line| line.parse::<GitLfsLock>().ok())
+            .collect();
+
+        Ok(locks)
+    }
+
+
     pub fn lfs_version(&self) -> Option<String> {
         self.run("lfs", &["--version"])
             .ok()
@@ -118,3 +232,38 @@ impl GitEnvironment {
             )
     }
 }
+
+
+// Docs: https://github.com/git-lfs/git-lfs/blob/main/docs/man/git-lfs-fetch.adoc#include-and-exclude
+fn lfs_include_arg(include: &[PathBuf]) -> Result<Option<String>, Box<dyn Error>> {
+    if include.is_empty() {
+        return Ok(None);
+    }
+
+    let paths: Vec<&str> = include.iter()
+        .map(|path| path.to_str().ok_or("Path is not valid UTF-8"))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Some(format!("--include={}", paths.join(","))))
+}
+
+
+// Docs: https://github.com/git-lfs/git-lfs/blob/main/docs/man/git-lfs-config.adoc#fetch-settings
+//       https://git-scm.com/docs/git-config#Documentation/git-config.txt-GITCONFIGCOUNT
+fn lfs_recent_env(days: u32) -> Vec<(String, String)> {
+    let config = [
+        ("lfs.fetchrecentrefsdays", days.to_string()),
+        ("lfs.fetchrecentcommitsdays", days.to_string()),
+        ("lfs.fetchrecentremoterefs", "false".to_string()),
+        ("lfs.pruneoffsetdays", "0".to_string()), // Prune right after the window
+    ];
+
+    let mut env = vec![("GIT_CONFIG_COUNT".to_string(), config.len().to_string())];
+
+    for (i, (name, value)) in config.into_iter().enumerate() {
+        env.push((format!("GIT_CONFIG_KEY_{i}"), name.to_string()));
+        env.push((format!("GIT_CONFIG_VALUE_{i}"), value));
+    }
+
+    env
+}
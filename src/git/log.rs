@@ -56,6 +56,47 @@ impl GitEnvironment {
 
         Ok(log)
     }
+
+
+    /// The whole history of HEAD, oldest first. Merged in branches are skipped,
+    /// as their merge commits hold the same changes. Timestamps are commit times.
+    pub fn log_first_parent(&self) -> Result<Vec<GitCommit>, Box<dyn Error>> {
+        let output = self.run("log", &[
+            "--first-parent",
+            "--reverse",
+            "--format=%H%x1f%T%x1f%an <%ae>%x1f%ct%x1f%B%x1e", // Unit and record separators
+            "HEAD",
+        ])?;
+
+        let mut log = Vec::new();
+
+        for record in output.stdout.split('\x1e') {
+            let record = record.trim_start_matches('\n');
+
+            if record.is_empty() {
+                continue;
+            }
+
+            let fields: Vec<&str> = record.splitn(5, '\x1f').collect();
+
+            let [id, tree, author, timestamp, message] = fields[..] else {
+                return Err("Error parsing commit".into());
+            };
+
+            let seconds_from_epoch: i64 = timestamp.parse()?;
+
+            log.push(GitCommit {
+                id: id.to_string(),
+                tree: tree.to_string(),
+                timestamp: DateTime::from_timestamp(seconds_from_epoch, 0).ok_or("Error parsing timestamp")?,
+                author: GitUser::from_str(author)?,
+                message: message.parse::<GitCommitMessage>()?,
+                changes: Vec::new(),
+            });
+        }
+
+        Ok(log)
+    }
 }
 
 
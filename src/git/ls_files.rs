@@ -20,6 +20,18 @@ impl GitEnvironment {
     }
 
 
+    /// Tracked files matching a pattern like "*.png", in any folder
+    pub fn ls_files_matching(&self, pattern: &str) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+        let output = self.run("ls-files", &["--", pattern])?;
+
+        let files = output.stdout.lines()
+            .map(PathBuf::from)
+            .collect();
+
+        Ok(files)
+    }
+
+
     pub fn ls_files_ignored(&self) -> Result<Vec<PathBuf>, Box<dyn Error>>{
         let output = self.run("ls-files", &[
             "--ignored",
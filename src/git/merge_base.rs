@@ -8,6 +8,7 @@
 use std::error::Error;
 
 use super::objects::environment::GitEnvironment;
+use super::objects::id::GitId;
 use super::objects::reference::GitReference;
 
 
@@ -28,4 +29,13 @@ impl GitEnvironment {
             Err(_) => Ok(false), // It's not (commit is probably remote)
         }
     }
+
+
+    /// The best common ancestor of two commits. None when it's not in
+    /// the history that's here, like in clones made with --depth
+    pub fn merge_base_common(&self, commit_a: &str, commit_b: &str) -> Option<GitId> {
+        self.run("merge-base", &[commit_a, commit_b])
+            .ok()
+            .map(|output| output.stdout)
+    }
 }
@@ -17,21 +17,28 @@ pub mod objects {
     pub mod environment;
     pub mod error;
     pub mod file_status;
+    pub mod https_url;
     pub mod ignore_reason;
     pub mod id;
+    pub mod lfs_file;
+    pub mod lfs_lock;
     pub mod merge_status;
     pub mod output;
     pub mod reference;
     pub mod remote;
+    pub mod remote_url;
     pub mod user;
 }
 
 pub mod add;
 pub mod branch;
+pub mod check_attr;
 pub mod checkout;
 pub mod clone;
 pub mod commit;
+pub mod commit_tree;
 pub mod config;
+pub mod diff;
 pub mod fetch;
 pub mod init;
 pub mod lfs;
@@ -41,8 +48,11 @@ pub mod ls_remote;
 pub mod merge;
 pub mod merge_base;
 pub mod push;
+pub mod reset;
 pub mod rev_list;
 pub mod rev_parse;
+pub mod sparse_checkout;
 pub mod status;
 pub mod symbolic_ref;
 pub mod version;
+pub mod write_tree;
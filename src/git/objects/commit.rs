@@ -16,6 +16,7 @@ use super::user::GitUser;
 #[derive(Debug, Default)]
 pub struct GitCommit {
     pub id: GitId,
+    pub tree: GitId,
     pub timestamp: DateTime<Utc>,
     pub author: GitUser,
     pub message: GitCommitMessage,
@@ -0,0 +1,83 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+use std::str;
+
+
+#[derive(Clone, Debug, Default)]
+pub struct HttpsUrl {
+    pub original: String,
+    pub user: Option<String>,
+    pub host: String,
+    pub port: Option<u16>,
+    pub path: PathBuf,
+}
+
+
+impl str::FromStr for HttpsUrl {
+    type Err = Box<dyn Error>;
+
+    /// https://git.example.com/hbons/Twinkle.git
+    /// https://hbons@git.example.com:8443/hbons/Twinkle.git
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let rest = s.strip_prefix("https://").ok_or("No 'https://' found")?;
+        let (authority, path) = rest.split_once('/').ok_or("No '/' found")?;
+
+        let (user, host_and_port) = match authority.split_once('@') {
+            Some((user, host_and_port)) => (Some(user.to_string()), host_and_port),
+            None => (None, authority),
+        };
+
+        let (host, port) = match host_and_port.rsplit_once(':') {
+            Some((host, port)) if !port.ends_with(']') => (host, Some(port.parse::<u16>()?)),
+            _ => (host_and_port, None),
+        };
+
+        if host.is_empty() {
+            return Err("No host found".into());
+        }
+
+        if path.is_empty() {
+            return Err("No path found".into());
+        }
+
+        Ok(HttpsUrl {
+            original: s.to_string(),
+            user,
+            host: host.to_string(),
+            port,
+            path: PathBuf::from(path),
+        })
+    }
+}
+
+
+impl fmt::Display for HttpsUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let user = match &self.user {
+            Some(user) => format!("{user}@"),
+            None => String::new(),
+        };
+
+        write!(f, "https://{}{}/{}", user, self.host_and_port(), self.path.display())
+    }
+}
+
+
+impl HttpsUrl {
+    /// "git.example.com:8443", the form Git uses in credential requests
+    pub fn host_and_port(&self) -> String {
+        match self.port {
+            Some(port) => format!("{}:{}", self.host, port),
+            None => self.host.clone(),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::str;
+use std::path::PathBuf;
+
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GitLfsFile {
+    pub path: PathBuf,
+    pub size: u64,
+    pub downloaded: bool, // In .git/lfs/objects
+}
+
+
+impl str::FromStr for GitLfsFile {
+    type Err = Box<dyn Error>;
+
+    // filepath: videos/intro.mp4
+    //     size: 1048576
+    // checkout: true
+    // download: true
+    //      oid: sha256 4d7a2146…
+    //  version: https://git-lfs.github.com/spec/v1
+    fn from_str(block: &str) -> Result<Self, Self::Err> {
+        let mut path = None;
+        let mut size = None;
+        let mut downloaded = false;
+
+        for line in block.lines() {
+            let Some((name, value)) = line.trim_start().split_once(": ") else {
+                continue;
+            };
+
+            match name {
+                "filepath" => path = Some(PathBuf::from(value)),
+                "size"     => size = value.parse::<u64>().ok(),
+                "download" => downloaded = value == "true",
+                _ => {},
+            }
+        }
+
+        Ok(GitLfsFile {
+            path: path.ok_or("Missing filepath")?,
+            size: size.ok_or("Missing size")?,
+            downloaded,
+        })
+    }
+}
@@ -0,0 +1,49 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::str;
+use std::path::PathBuf;
+
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GitLfsLock {
+    // Docs: https://github.com/git-lfs/git-lfs/blob/main/docs/man/git-lfs-locks.adoc
+
+    pub id: String,
+    pub path: PathBuf,
+    pub owner: String, // Name on the server, like "hbons"
+    pub ours: bool, // Only known with --verify
+}
+
+
+impl str::FromStr for GitLfsLock {
+    type Err = Box<dyn Error>;
+
+    // 'art/cover.psd    hbons   ID:123'
+    // 'O art/cover.psd  hbons   ID:123' (--verify, ours)
+    // '  art/logo.psd   alice   ID:124' (--verify, theirs)
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (ours, line) = match line.strip_prefix("O ") {
+            Some(rest) => (true, rest),
+            None => (false, line.strip_prefix("  ").unwrap_or(line)),
+        };
+
+        let mut parts = line.split('\t');
+
+        let path = parts.next().map(str::trim_end).filter(|path| !path.is_empty()).ok_or("Missing path")?;
+        let owner = parts.next().map(str::trim).filter(|owner| !owner.is_empty()).ok_or("Missing owner")?;
+        let id = parts.next().and_then(|id| id.trim().strip_prefix("ID:")).ok_or("Missing ID")?;
+
+        Ok(GitLfsLock {
+            id: id.to_string(),
+            path: PathBuf::from(path),
+            owner: owner.to_string(),
+            ours,
+        })
+    }
+}
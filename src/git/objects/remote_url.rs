@@ -0,0 +1,122 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::fmt;
+use std::path::{ Path, PathBuf };
+use std::str;
+
+use crate::ssh::objects::url::SshUrl;
+use super::https_url::HttpsUrl;
+
+
+#[derive(Clone, Debug)]
+pub enum GitRemoteUrl {
+    // Docs: https://git-scm.com/docs/git-clone#_git_urls
+
+    /// git@github.com:hbons/Twinkle
+    Ssh(SshUrl),
+    /// https://git.example.com/hbons/Twinkle.git
+    Https(HttpsUrl),
+    /// file:///srv/shared/Twinkle.git
+    File(PathBuf),
+    /// /media/usb/Twinkle.git
+    Path(PathBuf),
+}
+
+
+impl str::FromStr for GitRemoteUrl {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if let Some(path) = s.strip_prefix("file://") {
+            if !path.starts_with('/') {
+                return Err("File URL must have an absolute path".into());
+            }
+
+            return Ok(Self::File(PathBuf::from(path)));
+        }
+
+        if s.starts_with("https://") {
+            return Ok(Self::Https(s.parse::<HttpsUrl>()?));
+        }
+
+        if s.starts_with('/') || s.starts_with("./") || s.starts_with("../") {
+            return Ok(Self::Path(PathBuf::from(s)));
+        }
+
+        Ok(Self::Ssh(s.parse::<SshUrl>()?))
+    }
+}
+
+
+impl fmt::Display for GitRemoteUrl {
+    /// The form that is passed to Git
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ssh(url)   => write!(f, "{}", url.to_string_standard()),
+            Self::Https(url) => write!(f, "{}", url),
+            Self::File(path) => write!(f, "file://{}", path.display()),
+            Self::Path(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+
+impl GitRemoteUrl {
+    /// The URL as the user entered it
+    pub fn original(&self) -> String {
+        match self {
+            Self::Ssh(url)   => url.original.clone(),
+            Self::Https(url) => url.original.clone(),
+            _ => self.to_string(),
+        }
+    }
+
+
+    /// Path to the repository on the remote
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Ssh(url)   => &url.path,
+            Self::Https(url) => &url.path,
+            Self::File(path) | Self::Path(path) => path,
+        }
+    }
+
+
+    pub fn host(&self) -> Option<&str> {
+        match self {
+            Self::Ssh(url)   => Some(&url.host),
+            Self::Https(url) => Some(&url.host),
+            _ => None,
+        }
+    }
+
+
+    pub fn as_ssh(&self) -> Option<&SshUrl> {
+        match self {
+            Self::Ssh(url) => Some(url),
+            _ => None,
+        }
+    }
+
+
+    pub fn as_https(&self) -> Option<&HttpsUrl> {
+        match self {
+            Self::Https(url) => Some(url),
+            _ => None,
+        }
+    }
+
+
+    /// Local remotes don't need keys, host keys or a network
+    pub fn is_local(&self) -> bool {
+        matches!(self, Self::File(_) | Self::Path(_))
+    }
+}
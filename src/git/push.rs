@@ -29,4 +29,19 @@ impl GitEnvironment {
 
         Ok(())
     }
+
+
+    /// Updates all refs, like "<commit>:refs/heads/<branch>", or none of them
+    pub fn push_atomic(&self,
+        remote: &GitRemote,
+        refspecs: &[String],
+    ) -> Result<(), Box<dyn Error>>
+    {
+        let mut args = vec!["--progress", "--atomic", "--", remote.as_str()]; // Safety: No more flags coming after this
+        args.extend(refspecs.iter().map(String::as_str));
+
+        self.run("push", &args)?;
+
+        Ok(())
+    }
 }
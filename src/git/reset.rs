@@ -0,0 +1,78 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::path::PathBuf;
+
+use super::objects::environment::GitEnvironment;
+
+
+impl GitEnvironment {
+    // Docs: https://git-scm.com/docs/git-reset
+
+    /// Discards all changes to tracked files. Untracked files are left alone.
+    pub fn reset_hard(&self) -> Result<(), Box<dyn Error>> {
+        let output = self.run("reset", &[
+            "--hard",
+            "--quiet",
+            "HEAD",
+        ])?;
+
+        match output.exit_code {
+            0 => Ok(()),
+            _ => Err(format!("Reset failed: {}", output.stderr).into()),
+        }
+    }
+
+
+    /// Moves the branch to `commit`, and discards all changes to tracked files
+    pub fn reset_hard_to(&self, commit: &str) -> Result<(), Box<dyn Error>> {
+        let output = self.run("reset", &[
+            "--hard",
+            "--quiet",
+            commit,
+        ])?;
+
+        match output.exit_code {
+            0 => Ok(()),
+            _ => Err(format!("Reset failed: {}", output.stderr).into()),
+        }
+    }
+
+
+    /// Moves the branch to `commit`, leaving the files themselves alone
+    pub fn reset_to(&self, commit: &str) -> Result<(), Box<dyn Error>> {
+        let output = self.run("reset", &[
+            "--mixed",
+            "--quiet",
+            commit,
+        ])?;
+
+        match output.exit_code {
+            0 => Ok(()),
+            _ => Err(format!("Reset failed: {}", output.stderr).into()),
+        }
+    }
+
+
+    /// Unstages `paths`, leaving the files themselves alone
+    pub fn reset_paths(&self, paths: &[PathBuf]) -> Result<(), Box<dyn Error>> {
+        let paths: Vec<&str> = paths.iter()
+            .map(|path| path.to_str().ok_or("Path is not valid UTF-8"))
+            .collect::<Result<_, _>>()?;
+
+        let mut args = vec!["--quiet", "--"]; // Safety: No more flags coming after this
+        args.extend(paths);
+
+        let output = self.run("reset", &args)?;
+
+        match output.exit_code {
+            0 => Ok(()),
+            _ => Err(format!("Reset failed: {}", output.stderr).into()),
+        }
+    }
+}
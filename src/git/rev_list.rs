@@ -6,6 +6,9 @@
 
 
 use std::error::Error;
+
+use chrono::{ DateTime, Utc };
+
 use super::objects::environment::GitEnvironment;
 
 
@@ -18,4 +21,19 @@ impl GitEnvironment {
 
         Ok(count)
     }
+
+
+    /// Commits in the history of HEAD from before `timestamp`
+    pub fn rev_list_count_before(&self, timestamp: DateTime<Utc>) -> Result<u32, Box<dyn Error>> {
+        let output = self.run("rev-list", &[
+            "--count",
+            "--first-parent",
+            &format!("--before=@{}", timestamp.timestamp()),
+            "HEAD",
+        ])?;
+
+        let count = output.stdout.parse::<u32>()?;
+
+        Ok(count)
+    }
 }
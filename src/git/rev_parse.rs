@@ -9,6 +9,7 @@ use std::error::Error;
 use std::path::PathBuf;
 
 use super::objects::environment::GitEnvironment;
+use super::objects::id::GitId;
 
 
 impl GitEnvironment {
@@ -22,6 +23,22 @@ impl GitEnvironment {
     }
 
 
+    /// The object id of a revision, like "main" or "HEAD^{tree}"
+    pub fn rev_parse_verify(&self, rev: &str) -> Result<GitId, Box<dyn Error>> {
+        match self.run("rev-parse", &["--verify", "--quiet", rev]) {
+            Ok(output) => Ok(output.stdout),
+            Err(_) => Err(format!("`{rev}` not found").into()),
+        }
+    }
+
+
+    /// Cloned with --depth, so older history is missing
+    pub fn rev_parse_is_shallow(&self) -> bool {
+        self.run("rev-parse", &["--is-shallow-repository"])
+            .is_ok_and(|output| output.stdout == "true")
+    }
+
+
     pub fn rev_parse_show_toplevel(&self) -> Result<PathBuf, Box<dyn Error>> {
         match self.run("rev-parse", &["--show-toplevel"]) {
             Ok(output) => Ok(PathBuf::from(output.stdout.to_string())),
@@ -0,0 +1,37 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::path::PathBuf;
+
+use super::objects::environment::GitEnvironment;
+
+
+impl GitEnvironment {
+    // Docs: https://git-scm.com/docs/git-sparse-checkout
+
+    /// Checks out only the files in the root and in `dirs`. Files outside of
+    /// them are marked as skipped, rather than deleted.
+    pub fn sparse_checkout_set(&self, dirs: &[PathBuf]) -> Result<(), Box<dyn Error>> {
+        let dirs: Vec<&str> = dirs.iter()
+            .map(|dir| dir.to_str().ok_or("Path is not valid UTF-8"))
+            .collect::<Result<_, _>>()?;
+
+        let mut args = vec!["set", "--cone", "--"]; // Safety: No more flags coming after this
+        args.extend(dirs);
+
+        self.run("sparse-checkout", &args)?;
+        Ok(())
+    }
+
+
+    /// Checks out everything again
+    pub fn sparse_checkout_disable(&self) -> Result<(), Box<dyn Error>> {
+        self.run("sparse-checkout", &["disable"])?;
+        Ok(())
+    }
+}
@@ -23,6 +23,12 @@ impl GitEnvironment {
     }
 
 
+    /// Lists the files in untracked dirs, instead of the dirs
+    pub fn status_all(&self) -> Result<Vec<GitChange>, Box<dyn Error>> {
+        self.get_changes("--untracked-files=all")
+    }
+
+
     fn get_changes(&self, extra_arg: &str) -> Result<Vec<GitChange>, Box<dyn Error>> {
         let output = &self.run("status", &[
             "--no-renames",
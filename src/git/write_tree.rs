@@ -0,0 +1,22 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+
+use super::objects::environment::GitEnvironment;
+use super::objects::id::GitId;
+
+
+impl GitEnvironment {
+    // Docs: https://git-scm.com/docs/git-write-tree
+
+    /// Stores what's staged as a tree
+    pub fn write_tree(&self) -> Result<GitId, Box<dyn Error>> {
+        let output = self.run("write-tree", &[])?;
+        Ok(output.stdout)
+    }
+}
@@ -16,8 +16,11 @@ pub fn log(message: &str) {
     println!("{}", message);
 }
 
+// Logs go to stderr, so stdout stays clean for output that's read by
+// other programs, like the credential helper's answers to Git
+
 pub fn info(message: &str) {
-    println!("{}", format_line(message));
+    eprintln!("{}", format_line(message));
 }
 
 
@@ -31,7 +34,7 @@ pub fn debug_struct(s: &impl Debug) {
 
 pub fn debug_base(message: &str) {
     if let Ok("1") = env::var("DEBUG").as_deref() {
-        println!("\x1b[2m{}\x1b[0m", message);
+        eprintln!("\x1b[2m{}\x1b[0m", message);
     }
 }
 
@@ -19,6 +19,7 @@ pub mod gui;
 pub mod log;
 pub mod ssh;
 pub mod twinkle;
+pub mod util;
 
 mod tests;
 
@@ -30,6 +31,7 @@ use std::process::exit;
 use crate::app::{ app_deps, app_version };
 use crate::app::{ App, app_runs_as_root };
 use crate::gui::Gui;
+use crate::twinkle::twinkle_clone::TwinkleCloneError;
 
 
 fn main() -> Result<(), Box<dyn Error>> {
@@ -52,6 +54,14 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     match app.cli_parse_args(&args) {
         Ok(_)  => exit(0),
-        Err(e) => log::error_and_exit(&e.to_string())
+        Err(e) => {
+            // Lets scripts tell network, trust and auth problems apart
+            if let Some(clone_error) = e.downcast_ref::<TwinkleCloneError>() {
+                log::error(&e.to_string());
+                exit(clone_error.to_exit_code().into());
+            }
+
+            log::error_and_exit(&e.to_string())
+        }
     };
 }
@@ -0,0 +1,171 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::env;
+use std::error::Error;
+use std::path::{ Path, PathBuf };
+use std::process::{ self, Command, Stdio };
+
+use crate::log;
+
+use super::keys::key_pair::KeyPair;
+
+
+#[derive(Debug)]
+pub struct SshAgent {
+    pub auth_sock: PathBuf,
+    pid: Option<String>, // Only set for agents we started ourselves
+}
+
+
+impl SshAgent {
+    /// The agent of the user's session
+    pub fn from_env() -> Option<SshAgent> {
+        let auth_sock = env::var_os("SSH_AUTH_SOCK")?;
+
+        Some(SshAgent {
+            auth_sock: PathBuf::from(auth_sock),
+            pid: None,
+        })
+    }
+
+
+    /// Starts a new agent listening on `auth_sock`, stopped again when dropped
+    pub fn start(auth_sock: &Path) -> Result<SshAgent, Box<dyn Error>> {
+        // Docs: https://man.openbsd.org/ssh-agent
+
+        let output = Command::new("ssh-agent")
+            .arg("-s") // Bourne shell output
+            .arg("-a").arg(auth_sock)
+            .stdin(Stdio::null())
+            .output()?;
+
+        if !output.status.success() {
+            return Err(format!("Could not start ssh-agent: {}",
+                String::from_utf8_lossy(&output.stderr).trim()).into());
+        }
+
+        // "SSH_AGENT_PID=1234; export SSH_AGENT_PID;"
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let pid = stdout
+            .split(';')
+            .find_map(|s| s.trim().strip_prefix("SSH_AGENT_PID="))
+            .ok_or("Missing SSH_AGENT_PID")?;
+
+        Ok(SshAgent {
+            auth_sock: auth_sock.to_path_buf(),
+            pid: Some(pid.to_string()),
+        })
+    }
+
+
+    /// Public keys held by the agent: "ssh-ed25519 AAAAC3NzaC1lZDI1N… comment"
+    pub fn list(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        let output = self.ssh_add()
+            .arg("-L")
+            .stdin(Stdio::null())
+            .output()?;
+
+        match output.status.code() {
+            Some(0) => Ok(String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(|line| line.trim().to_string())
+                .collect()),
+            Some(1) => Ok(Vec::new()), // The agent has no identities
+            _ => Err(format!("Could not list ssh-agent keys: {}",
+                String::from_utf8_lossy(&output.stderr).trim()).into()),
+        }
+    }
+
+
+    pub fn contains(&self, key_pair: &KeyPair) -> bool {
+        let Some(key_body) = key_pair.public_key.split_whitespace().nth(1) else {
+            return false;
+        };
+
+        self.list()
+            .unwrap_or_default()
+            .iter()
+            .any(|line| line.split_whitespace().nth(1) == Some(key_body))
+    }
+
+
+    /// Asks for the passphrase on the terminal if the key needs one
+    pub fn add(&self, key_pair: &KeyPair) -> Result<(), Box<dyn Error>> {
+        let status = self.ssh_add()
+            .arg(&key_pair.private_key_path)
+            .status()?;
+
+        if !status.success() {
+            return Err(format!("Could not add `{}` to ssh-agent",
+                key_pair.private_key_path.to_string_lossy()).into());
+        }
+
+        log::debug(&format!("Agent | Added `{}`", key_pair.private_key_path.to_string_lossy()));
+        Ok(())
+    }
+
+
+    pub fn remove(&self, key_pair: &KeyPair) -> Result<(), Box<dyn Error>> {
+        let output = self.ssh_add()
+            .arg("-d")
+            .arg(&key_pair.public_key_path)
+            .stdin(Stdio::null())
+            .output()?;
+
+        if !output.status.success() {
+            return Err(format!("Could not remove `{}` from ssh-agent",
+                key_pair.private_key_path.to_string_lossy()).into());
+        }
+
+        log::debug(&format!("Agent | Removed `{}`", key_pair.private_key_path.to_string_lossy()));
+        Ok(())
+    }
+
+
+    /// Removes the key once this process is gone, however it exits. The watcher
+    /// ignores SIGINT and SIGHUP so it outlives Ctrl+C in a terminal.
+    pub fn remove_on_exit(&self, key_pair: &KeyPair) -> Result<(), Box<dyn Error>> {
+        let script = format!(
+            "trap '' INT HUP; while kill -0 {pid} 2>/dev/null; do sleep 2; done; ssh-add -d \"$0\"",
+            pid = process::id(),
+        );
+
+        Command::new("sh")
+            .arg("-c").arg(script)
+            .arg(&key_pair.public_key_path)
+            .env("SSH_AUTH_SOCK", &self.auth_sock)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        Ok(())
+    }
+
+
+    /// Docs: https://man.openbsd.org/ssh-add
+    fn ssh_add(&self) -> Command {
+        let mut command = Command::new("ssh-add");
+        command.env("SSH_AUTH_SOCK", &self.auth_sock);
+        command
+    }
+}
+
+
+impl Drop for SshAgent {
+    fn drop(&mut self) {
+        if let Some(pid) = &self.pid {
+            _ = Command::new("ssh-agent")
+                .arg("-k")
+                .env("SSH_AGENT_PID", pid)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status();
+        }
+    }
+}
@@ -6,13 +6,16 @@
 
 
 use std::error::Error;
-use std::fs::{ create_dir_all, read_to_string };
+use std::fs::{ OpenOptions, Permissions, create_dir_all, read_to_string, remove_file, set_permissions };
 use std::io::Write;
-use std::path::Path;
-use std::process::{ Command, Stdio };
+use std::os::unix::fs::{ OpenOptionsExt, PermissionsExt };
+use std::path::{ Path, PathBuf };
+use std::process::{ self, Command, Stdio };
+use std::sync::atomic::{ AtomicUsize, Ordering };
 
 use crate::log;
 
+use super::keys::certificate::Certificate;
 use super::keys::fingerprint::Fingerprint;
 use super::keys::host_key::HostKey;
 use super::keys::key_pair::KeyPair;
@@ -25,13 +28,18 @@ pub fn ssh_keygen(
     key_path: &Path,
     key_type: KeyType,
     key_size: Option<KeySize>,
+    passphrase: Option<&str>,
 ) -> Result<KeyPair, Box<dyn Error>>
 {
+    if key_type.is_security_key() {
+        return Err(format!("Can't generate `{key_type}` keys, they need the security key present. Add one to ssh-agent instead").into());
+    }
 
     let keys_dir = key_path.parent().ok_or("Could not find parent directory")?;
 
     if !keys_dir.exists() {
         create_dir_all(keys_dir)?;
+        set_permissions(keys_dir, Permissions::from_mode(0o700))?;
     }
 
     let key_size = match key_size {
@@ -43,16 +51,27 @@ pub fn ssh_keygen(
         "-q", // Quiet
         "-t", &key_type.to_string(), // Key type
         "-b", &key_size.to_string(), // Key size in bits
-        "-P", "", // No passphrase
         "-C", "Twinkle", // Key comment
         "-f", key_path.to_str().ok_or("Invalid key path")?, // File name
     ];
 
     log::debug(&format!("ssh-keygen {}", args.join(" ")));
 
-    let ssh_keygen = Command::new("ssh-keygen")
-        .args(args)
-        .output();
+    let mut command = Command::new("ssh-keygen");
+    command.args(args).stdin(Stdio::null());
+
+    // Passphrases never go on the command line, where other users can see them.
+    // ssh-keygen asks for it instead, so the key is encrypted from the start
+    let askpass = match passphrase.filter(|p| !p.is_empty()) {
+        Some(passphrase) => Some(Askpass::new(keys_dir, &mut command, "", passphrase)?),
+        None => {
+            command.arg("-N").arg(""); // No passphrase
+            None
+        },
+    };
+
+    let ssh_keygen = command.output();
+    drop(askpass);
 
     match ssh_keygen {
         Ok(output) => {
@@ -74,10 +93,12 @@ pub fn ssh_keygen(
                 key_type,
                 private_key: read_to_string(key_path)?.trim().to_string(),
                 private_key_path: key_path.to_path_buf(),
-                passphrase: None,
+                passphrase: passphrase.map(|p| p.to_string()),
 
                 public_key:  read_to_string(&pubkey_path)?.trim().to_string(),
                 public_key_path: pubkey_path.to_path_buf(),
+
+                certificate: None,
             };
 
             Ok(key_pair)
@@ -88,6 +109,23 @@ pub fn ssh_keygen(
 
 
 pub fn ssh_keygen_fingerprint(host_key: &HostKey) -> Result<Fingerprint, Box<dyn Error>> {
+    fingerprint_for(&host_key.to_string())
+}
+
+
+pub fn ssh_keygen_fingerprint_key_pair(key_pair: &KeyPair) -> Result<Fingerprint, Box<dyn Error>> {
+    ssh_keygen_fingerprint_public_key(&key_pair.public_key)
+}
+
+
+/// "ssh-ed25519 AAAAC3NzaC1lZDI1N… Twinkle"
+pub fn ssh_keygen_fingerprint_public_key(public_key: &str) -> Result<Fingerprint, Box<dyn Error>> {
+    fingerprint_for(public_key)
+}
+
+
+/// Accepts both known_hosts lines and public keys
+fn fingerprint_for(key: &str) -> Result<Fingerprint, Box<dyn Error>> {
     // Docs: https://man.openbsd.org/ssh-keygen#l
 
     let mut child = Command::new("ssh-keygen")
@@ -102,7 +140,7 @@ pub fn ssh_keygen_fingerprint(host_key: &HostKey) -> Result<Fingerprint, Box<dyn
 
     {
         let stdin = child.stdin.as_mut().ok_or("Could not open stdin")?;
-        stdin.write_all(host_key.to_string().as_bytes())?;
+        stdin.write_all(key.as_bytes())?;
     }
 
     let output = child.wait_with_output()?;
@@ -117,3 +155,139 @@ pub fn ssh_keygen_fingerprint(host_key: &HostKey) -> Result<Fingerprint, Box<dyn
             String::from_utf8_lossy(&output.stderr)).into())
     }
 }
+
+
+/// Whether the private key needs a passphrase
+pub fn ssh_keygen_is_encrypted(key_pair: &KeyPair) -> bool {
+    // Docs: https://man.openbsd.org/ssh-keygen#y
+
+    if key_pair.is_agent_identity() {
+        return false;
+    }
+
+    let ssh_keygen = Command::new("ssh-keygen")
+        .arg("-y") // Print the public key
+        .arg("-P").arg("") // Try an empty passphrase
+        .arg("-f").arg(&key_pair.private_key_path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+
+    !matches!(ssh_keygen, Ok(status) if status.success())
+}
+
+
+/// Adds, changes or removes (empty `new`) the passphrase of a private key
+pub fn ssh_keygen_change_passphrase(
+    key_pair: &KeyPair,
+    old: &str,
+    new: &str,
+) -> Result<(), Box<dyn Error>>
+{
+    change_passphrase(&key_pair.private_key_path, old, new)?;
+
+    log::debug(&format!("Keys | Changed passphrase of `{}`", key_pair.private_key_path.to_string_lossy()));
+    Ok(())
+}
+
+
+/// Answers ssh-keygen's prompts from the environment, which only the same user can read
+const ASKPASS_SCRIPT: &str = r#"#!/bin/sh
+case "$1" in
+    *old*) printf '%s\n' "$TWINKLE_ASKPASS_OLD" ;;
+    *) printf '%s\n' "$TWINKLE_ASKPASS_NEW" ;;
+esac
+"#;
+
+
+/// An `SSH_ASKPASS` script for one ssh-keygen run, removed again when dropped
+struct Askpass {
+    path: PathBuf,
+}
+
+
+impl Askpass {
+    /// Docs: https://man.openbsd.org/ssh#SSH_ASKPASS_REQUIRE
+    fn new(keys_dir: &Path, command: &mut Command, old: &str, new: &str) -> Result<Askpass, Box<dyn Error>> {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        // Unique per run, so key operations at the same time don't replace each other's script
+        let path = keys_dir.join(format!(".askpass-{}-{}",
+            process::id(), COUNT.fetch_add(1, Ordering::Relaxed)));
+
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o700)
+            .open(&path)?;
+
+        let askpass = Askpass { path }; // Removes the script if writing fails too
+        file.write_all(ASKPASS_SCRIPT.as_bytes())?;
+
+        command
+            .env("SSH_ASKPASS", &askpass.path)
+            .env("SSH_ASKPASS_REQUIRE", "force") // Even with a terminal
+            .env("TWINKLE_ASKPASS_OLD", old)
+            .env("TWINKLE_ASKPASS_NEW", new);
+
+        Ok(askpass)
+    }
+}
+
+
+impl Drop for Askpass {
+    fn drop(&mut self) {
+        _ = remove_file(&self.path);
+    }
+}
+
+
+fn change_passphrase(key_path: &Path, old: &str, new: &str) -> Result<(), Box<dyn Error>> {
+    // Docs: https://man.openbsd.org/ssh-keygen#p
+
+    let keys_dir = key_path.parent().ok_or("Could not find parent directory")?;
+
+    let mut command = Command::new("ssh-keygen");
+    command
+        .arg("-q") // Quiet
+        .arg("-p") // Change passphrase, asking for the old and new ones
+        .arg("-f").arg(key_path)
+        .stdin(Stdio::null());
+
+    let askpass = Askpass::new(keys_dir, &mut command, old, new)?;
+    let output = command.output();
+    drop(askpass);
+
+    let output = output?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("Could not change passphrase: {}",
+            String::from_utf8_lossy(&output.stderr).trim()).into())
+    }
+}
+
+
+/// Reads a certificate's key, principals and validity
+pub fn ssh_keygen_certificate(path: &Path) -> Result<Certificate, Box<dyn Error>> {
+    // Docs: https://man.openbsd.org/ssh-keygen#L
+
+    let output = Command::new("ssh-keygen")
+        .arg("-L") // Print certificate contents
+        .arg("-f").arg(path)
+        .stdin(Stdio::null())
+        .output()?;
+
+    if !output.status.success() {
+        return Err(format!("Could not read certificate `{}`: {}",
+            path.to_string_lossy(),
+            String::from_utf8_lossy(&output.stderr).trim()).into());
+    }
+
+    let mut certificate: Certificate = String::from_utf8_lossy(&output.stdout).parse()?;
+    certificate.path = path.to_path_buf();
+
+    Ok(certificate)
+}
@@ -0,0 +1,142 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::path::{ Path, PathBuf };
+use std::str;
+
+use chrono::{ DateTime, Local, NaiveDateTime, TimeZone, Utc };
+
+use super::fingerprint::Fingerprint;
+
+
+/// An OpenSSH user certificate, signed by a certificate authority
+#[derive(Clone, Debug, Default)]
+pub struct Certificate {
+    pub path: PathBuf, // "github.com.ed25519.key-cert.pub"
+    pub key_id: String,
+    pub fingerprint: Option<Fingerprint>, // Of the certified public key
+    pub principals: Vec<String>,
+    pub valid_after: Option<DateTime<Utc>>, // None means since forever
+    pub valid_before: Option<DateTime<Utc>>, // None means forever
+}
+
+
+impl str::FromStr for Certificate {
+    type Err = Box<dyn Error>;
+
+    /// The output of `ssh-keygen -L`:
+    ///
+    ///     Public key: ED25519-CERT SHA256:WKBLjQEjFyuiSOywWLw+oUlym7YkPnybJlWBWMVoNZM
+    ///     Key ID: "alice@example.org"
+    ///     Valid: from 2026-10-19T08:06:00 to 2026-10-19T09:07:46
+    ///     Principals:
+    ///             git
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut certificate = Certificate::default();
+        let mut has_validity = false;
+        let mut in_principals = false;
+
+        for line in s.lines() {
+            let line = line.trim();
+
+            // "Field: value", or "Field:" followed by indented values
+            let field = line.split_once(": ").or_else(|| line.strip_suffix(':').map(|field| (field, "")));
+
+            if let Some((field, value)) = field {
+                in_principals = field == "Principals";
+
+                match field {
+                    "Public key" => {
+                        certificate.fingerprint = value.split_whitespace().nth(1).and_then(|s| s.parse().ok());
+                    },
+                    "Key ID" => certificate.key_id = value.trim_matches('"').to_string(),
+                    "Valid" => {
+                        (certificate.valid_after, certificate.valid_before) = parse_validity(value)?;
+                        has_validity = true;
+                    },
+                    _ => {},
+                }
+            } else if in_principals && !line.is_empty() && line != "(none)" {
+                certificate.principals.push(line.to_string());
+            }
+        }
+
+        if !has_validity {
+            return Err("Not a certificate".into());
+        }
+
+        Ok(certificate)
+    }
+}
+
+
+/// Valid after and valid before, None means no limit
+type Validity = (Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+
+/// "forever", "from X to Y", "after X" or "before Y", in local time
+fn parse_validity(s: &str) -> Result<Validity, Box<dyn Error>> {
+    let words: Vec<&str> = s.split_whitespace().collect();
+
+    match words.as_slice() {
+        ["forever"] => Ok((None, None)),
+        ["from", after, "to", before] => Ok((Some(parse_time(after)?), Some(parse_time(before)?))),
+        ["after", after] => Ok((Some(parse_time(after)?), None)),
+        ["before", before] => Ok((None, Some(parse_time(before)?))),
+        _ => Err(format!("Unknown validity `{s}`").into()),
+    }
+}
+
+
+fn parse_time(s: &str) -> Result<DateTime<Utc>, Box<dyn Error>> {
+    let time = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S")?;
+
+    let time = Local.from_local_datetime(&time)
+        .earliest()
+        .ok_or_else(|| format!("Invalid local time `{s}`"))?;
+
+    Ok(time.with_timezone(&Utc))
+}
+
+
+impl Certificate {
+    /// Where OpenSSH looks for the certificate of a private key
+    pub fn path_for(private_key_path: &Path) -> PathBuf {
+        let mut path = private_key_path.as_os_str().to_owned();
+        path.push("-cert.pub");
+
+        PathBuf::from(path)
+    }
+
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.valid_before.is_some_and(|before| now >= before)
+    }
+
+
+    pub fn is_valid(&self, now: DateTime<Utc>) -> bool {
+        !self.is_expired(now) && self.valid_after.is_none_or(|after| now >= after)
+    }
+
+
+    /// Less than a quarter of the validity window left, and at most a week.
+    /// Short-lived certificates only warn close to the end.
+    pub fn expires_soon(&self, now: DateTime<Utc>) -> bool {
+        let Some(before) = self.valid_before else {
+            return false;
+        };
+
+        let lifetime = match self.valid_after {
+            Some(after) => before - after,
+            None => chrono::Duration::weeks(4),
+        };
+
+        let warning = (lifetime / 4).min(chrono::Duration::weeks(1));
+        now >= before - warning
+    }
+}
@@ -7,20 +7,21 @@
 
 use std::error::Error;
 use std::fmt;
-use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
 
-use super::known_hosts::prelude::*;
 use super::fingerprint::Fingerprint;
+use super::known_hosts_file::{ KnownHostsEntry, KnownHostsFile };
 use super::key_type::KeyType;
-use super::super::keyscan::ssh_keyscan;
+use super::super::keyscan::{ ssh_keyscan, ssh_keyscan_all };
+use super::super::objects::forge::KnownForge;
 use super::super::objects::url::SshUrl;
 
 
 #[derive(Clone, Debug, Default)]
 pub struct HostKey {
     pub host: String,
+    pub port: Option<u16>, // None for the default port
     pub is_trusted: bool, // TODO: TrustedHostKey and UntrustedHostKey types
     pub key_type: KeyType,
     pub public_key: String, // TODO: PublicKey type
@@ -29,43 +30,58 @@ pub struct HostKey {
 
 
 impl HostKey {
-    pub fn for_known_host(url: &SshUrl, key_type: KeyType) -> Result<HostKey, Box<dyn Error>> {
-        if key_type == KeyType::ED25519 {
-            match url.host.as_str() { // TODO: Use .parse and an enum so we cover all cases
-                "bitbucket.org"     => Ok(ssh_hostkey_bitbucket()),
-                "codeberg.org"      => Ok(ssh_hostkey_codeberg()),
-                "gitee.com"         => Ok(ssh_hostkey_gitee()),
-                "github.com"        => Ok(ssh_hostkey_github()),
-                "gitlab.com"        => Ok(ssh_hostkey_gitlab()),
-                "gitlab.gnome.org"  => Ok(ssh_hostkey_gnome()),
-                "git.sr.ht"         => Ok(ssh_hostkey_sourcehut()),
-                "git.savannah.org"  => Ok(ssh_hostkey_savannah()),
-                "invent.kde.org"    => Ok(ssh_hostkey_kde()),
-                "ssh.dev.azure.com" => Ok(ssh_hostkey_devops()),
-                "git.code.sf.net"   => Ok(ssh_hostkey_sourceforge()),
-                _ => ssh_keyscan(url.host.as_str(), url.port, KeyType::ED25519),
+    /// The forge's pinned key, or a freshly scanned one
+    pub fn for_known_host(url: &SshUrl, key_type: KeyType, forge: Option<&KnownForge>) -> Result<HostKey, Box<dyn Error>> {
+        match forge.and_then(|forge| forge.host_key(key_type, url.port)) {
+            Some(host_key) => Ok(host_key),
+            None => ssh_keyscan(url.host.as_str(), url.port, key_type),
+        }
+    }
+
+
+    /// Finds the first key type the host supports, see `KeyType::preferred`.
+    /// Hosts without pinned keys are scanned once for all key types.
+    pub fn negotiate(url: &SshUrl, forge: Option<&KnownForge>) -> Result<HostKey, Box<dyn Error>> {
+        for key_type in KeyType::preferred() {
+            if let Some(host_key) = forge.and_then(|forge| forge.host_key(key_type, url.port)) {
+                return Ok(host_key);
             }
-         } else {
-            ssh_keyscan(url.host.as_str(), url.port, key_type)
-         }
+        }
+
+        let host_keys = ssh_keyscan_all(url.host.as_str(), url.port)
+            .map_err(|e| format!("No supported host key type found for {}: {e}", url.host))?;
+
+        KeyType::preferred()
+            .into_iter()
+            .find_map(|key_type| host_keys.iter().find(|host_key| host_key.key_type == key_type))
+            .cloned()
+            .ok_or_else(|| format!("No supported host key type found for {}", url.host).into())
     }
 
 
-    /// "github.com ssh-ed25519 AAAAC3NzaC1lZDI1N…"
+    /// The first key in a known_hosts file: "github.com ssh-ed25519 AAAAC3NzaC1lZDI1N…".
+    /// Use `KnownHostsFile` for files with several hosts or keys.
     pub fn from_file(path: &Path) -> Result<HostKey, Box<dyn Error>> {
-        let content = fs::read_to_string(path)?;
-        let mut parts = content.split_whitespace();
+        let file = KnownHostsFile::from_file(path)?;
+
+        let entry = file.entries.iter()
+            .find(|entry| entry.marker.is_none())
+            .ok_or("No host key")?;
 
-        let host        = parts.next().ok_or("Missing host")?.to_string();
-        let key_type    = parts.next().ok_or("Missing key type")?.to_string();
-        let key_type    = key_type.trim_start_matches("ssh-").parse::<KeyType>()?;
-        let public_key  = parts.next().ok_or("Missing public key")?.trim().to_string();
+        let pattern = entry.patterns.first().ok_or("Missing host")?;
+
+        // "[git.example.org]:2222"
+        let (host, port) = match pattern.strip_prefix('[').and_then(|s| s.split_once("]:")) {
+            Some((host, port)) => (host.to_string(), Some(port.parse::<u16>()?)),
+            None => (pattern.clone(), None),
+        };
 
         let host_key = HostKey {
             host,
+            port,
             is_trusted: true, // If it's local we trust it
-            key_type,
-            public_key,
+            key_type: entry.key_type,
+            public_key: entry.public_key.clone(),
             fingerprint: None
         };
 
@@ -83,9 +99,6 @@ impl HostKey {
 
 impl fmt::Display for HostKey {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.key_type {
-            KeyType::ECDSA => write!(f, "{} ecdsa-sha2-nistp256 {}", self.host, self.public_key),
-            _              => write!(f, "{} ssh-{} {}",              self.host, self.key_type, self.public_key),
-        }
+        write!(f, "{}", KnownHostsEntry::from(self))
     }
 }
@@ -9,7 +9,11 @@ use std::error::Error;
 use std::fs;
 use std::path::PathBuf;
 
+use crate::log;
+
+use super::certificate::Certificate;
 use super::key_type::KeyType;
+use super::super::keygen::ssh_keygen_certificate;
 
 
 #[derive(Clone, Debug, Default)]
@@ -22,6 +26,8 @@ pub struct KeyPair {
 
     pub public_key_path: PathBuf,
     pub public_key: String,
+
+    pub certificate: Option<Certificate>, // Loaded from "<private key>-cert.pub"
 }
 
 
@@ -43,8 +49,32 @@ impl KeyPair {
 
             public_key_path: public_key_path.clone(),
             public_key: public_key.trim().to_string(),
+
+            certificate: None,
+        };
+
+        let certificate_path = Certificate::path_for(private_key_path);
+
+        let key_pair = match certificate_path.exists() {
+            true => match ssh_keygen_certificate(&certificate_path) {
+                Ok(certificate) => KeyPair { certificate: Some(certificate), ..key_pair },
+                Err(e) => {
+                    log::warning(&e.to_string());
+                    key_pair
+                },
+            },
+            false => key_pair,
         };
 
         Ok(key_pair)
     }
 }
+
+
+impl KeyPair {
+    /// Agent identities only have their public key on disk, the private key
+    /// stays in ssh-agent. SSH and Git signing both accept that as an IdentityFile.
+    pub fn is_agent_identity(&self) -> bool {
+        !self.private_key.starts_with("-----BEGIN")
+    }
+}
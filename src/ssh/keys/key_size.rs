@@ -23,9 +23,11 @@ pub enum KeySize {
 impl KeySize {
     pub fn default(key_type: KeyType) -> KeySize {
         match key_type {
-            KeyType::ED25519 => KeySize::Bits256,
-            KeyType::RSA     => KeySize::Bits4096,
-            KeyType::ECDSA   => KeySize::Bits256,
+            KeyType::ED25519   => KeySize::Bits256,
+            KeyType::RSA       => KeySize::Bits4096,
+            KeyType::ECDSA     => KeySize::Bits256,
+            KeyType::ED25519SK => KeySize::Bits256,
+            KeyType::ECDSASK   => KeySize::Bits256,
         }
     }
 }
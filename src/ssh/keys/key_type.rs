@@ -14,15 +14,19 @@ pub enum KeyType {
     #[default] ED25519,
     RSA,
     ECDSA,
+    ED25519SK, // FIDO2 security keys
+    ECDSASK,
 }
 
 
 impl fmt::Display for KeyType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", match self {
-            KeyType::ECDSA   => "ecdsa-sha2-nistp256",
-            KeyType::ED25519 => "ed25519",
-            KeyType::RSA     => "rsa",
+            KeyType::ECDSA     => "ecdsa-sha2-nistp256",
+            KeyType::ED25519   => "ed25519",
+            KeyType::RSA       => "rsa",
+            KeyType::ED25519SK => "ed25519-sk",
+            KeyType::ECDSASK   => "ecdsa-sk",
         })
     }
 }
@@ -36,6 +40,13 @@ impl str::FromStr for KeyType {
             "ecdsa-sha2-nistp256" => Ok(KeyType::ECDSA),
             "ed25519"             => Ok(KeyType::ED25519),
             "rsa"                 => Ok(KeyType::RSA),
+            "ed25519-sk"          => Ok(KeyType::ED25519SK),
+            "ecdsa-sk"            => Ok(KeyType::ECDSASK),
+
+            // Public keys name security keys in full
+            "sk-ssh-ed25519@openssh.com"         => Ok(KeyType::ED25519SK),
+            "sk-ecdsa-sha2-nistp256@openssh.com" => Ok(KeyType::ECDSASK),
+
             _ => Err("Invalid key type".into()),
         }
     }
@@ -43,6 +54,46 @@ impl str::FromStr for KeyType {
 
 
 impl KeyType {
+    /// Order in which key types are tried when a host is new
+    pub fn preferred() -> [KeyType; 3] {
+        [KeyType::ED25519, KeyType::ECDSA, KeyType::RSA]
+    }
+
+
+    pub fn all() -> [KeyType; 5] {
+        [KeyType::ED25519, KeyType::ECDSA, KeyType::RSA, KeyType::ED25519SK, KeyType::ECDSASK]
+    }
+
+
+    /// Security keys need the hardware to generate, so they're only used when
+    /// brought in through ssh-agent
+    pub fn is_security_key(&self) -> bool {
+        matches!(self, KeyType::ED25519SK | KeyType::ECDSASK)
+    }
+
+
+    /// Servers don't have security keys, an "ed25519-sk" user key goes with an "ed25519" host key
+    pub fn to_host_key_type(&self) -> KeyType {
+        match self {
+            KeyType::ED25519SK => KeyType::ED25519,
+            KeyType::ECDSASK   => KeyType::ECDSA,
+            _ => *self,
+        }
+    }
+
+
+    /// As used in public keys and known_hosts: "ssh-ed25519", "ecdsa-sha2-nistp256",
+    /// "sk-ssh-ed25519@openssh.com"
+    pub fn to_public_key_prefix(&self) -> String {
+        match self {
+            KeyType::ECDSA     => self.to_string(),
+            KeyType::ED25519SK => "sk-ssh-ed25519@openssh.com".into(),
+            KeyType::ECDSASK   => "sk-ecdsa-sha2-nistp256@openssh.com".into(),
+            _                  => format!("ssh-{self}"),
+        }
+    }
+
+
     // "ssh-ed25519 AAAAC3NzaC1lZDI1N… Twinkle"
     pub fn from_public_key(s: &str) -> Result<Self, String> {
         let key_type = s.split_whitespace().next().ok_or("Invalid public key string")?;
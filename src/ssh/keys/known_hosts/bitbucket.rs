@@ -16,6 +16,7 @@ pub fn ssh_hostkey_bitbucket() -> HostKey {
 
     HostKey {
         host: "bitbucket.org".into(),
+        port: None,
         is_trusted: true,
         key_type: KeyType::ED25519,
         public_key: "AAAAC3NzaC1lZDI1NTE5AAAAIIazEu89wgQZ4bqs3d63QSMzYVa0MuJ2e2gKTKqu+UUO".into(),
@@ -17,6 +17,7 @@ pub fn ssh_hostkey_codeberg() -> HostKey {
 
     HostKey {
         host: "codeberg.org".into(),
+        port: None,
         is_trusted: true,
         key_type: KeyType::ED25519,
         public_key: "AAAAC3NzaC1lZDI1NTE5AAAAIIVIC02vnjFyL+I4RHfvIGNtOgJMe769VTF1VR4EB3ZB".into(),
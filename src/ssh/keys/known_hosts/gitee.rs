@@ -16,6 +16,7 @@ pub fn ssh_hostkey_gitee() -> HostKey {
 
     HostKey {
         host: "gitee.com".into(),
+        port: None,
         is_trusted: true,
         key_type: KeyType::ED25519,
         public_key: "AAAAC3NzaC1lZDI1NTE5AAAAIEKxHSJ7084RmkJ4YdEi5tngynE8aZe2uEoVVsB/OvYN".into(),
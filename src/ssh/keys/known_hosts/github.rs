@@ -16,6 +16,7 @@ pub fn ssh_hostkey_github() -> HostKey {
 
     HostKey {
         host: "github.com".into(),
+        port: None,
         is_trusted: true,
         key_type: KeyType::ED25519,
         public_key: "AAAAC3NzaC1lZDI1NTE5AAAAIOMqqnkVzrm0SdG6UOoqKLsabgH5C9okWi0dh2l9GKJl".into(),
@@ -16,6 +16,7 @@ pub fn ssh_hostkey_gitlab() -> HostKey {
 
     HostKey {
         host: "gitlab.com".into(),
+        port: None,
         is_trusted: true,
         key_type: KeyType::ED25519,
         public_key: "AAAAC3NzaC1lZDI1NTE5AAAAIAfuCHKVTjquxvt6CM6tdG4SLp1Btn/nOeHHE5UOzRdf".into(),
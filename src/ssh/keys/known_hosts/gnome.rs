@@ -17,6 +17,7 @@ pub fn ssh_hostkey_gnome() -> HostKey {
 
     HostKey {
         host: "gitlab.gnome.org".into(),
+        port: None,
         is_trusted: true, // TODO: TrustedHostKey type
         key_type: KeyType::ED25519,
         public_key: "AAAAC3NzaC1lZDI1NTE5AAAAIHG6b3deoYMPwKEu9Sj+y6MBHYYUKQiAnta/go3aNv7R".into(),
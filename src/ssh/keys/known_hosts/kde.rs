@@ -16,6 +16,7 @@ pub fn ssh_hostkey_kde() -> HostKey {
 
     HostKey {
         host: "invent.kde.org".into(),
+        port: None,
         is_trusted: true,
         key_type: KeyType::ED25519,
         public_key: "AAAAC3NzaC1lZDI1NTE5AAAAIOMtd90DMLrtdCiapQK43JjwKk+U8egSXQU15fOJba1n".into(),
@@ -5,7 +5,6 @@
 //   under the terms of the GNU General Public License v3 or any later version.
 
 
-// TODO: Use types?
 pub use super::bitbucket::ssh_hostkey_bitbucket;
 pub use super::codeberg::ssh_hostkey_codeberg;
 pub use super::devops::ssh_hostkey_devops;
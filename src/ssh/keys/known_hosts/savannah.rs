@@ -16,6 +16,7 @@ pub fn ssh_hostkey_savannah() -> HostKey {
 
     HostKey {
         host: "git.savannah.gnu.org".into(),
+        port: None,
         is_trusted: true,
         key_type: KeyType::ED25519,
         public_key: "AAAAC3NzaC1lZDI1NTE5AAAAIMnMLHxGS/b6Su98mL/J58FkpEJY/X1mONqhPBuFX5sJ".into(),
@@ -16,6 +16,7 @@ pub fn ssh_hostkey_sourceforge() -> HostKey {
 
     HostKey {
         host: "git.code.sf.net".into(),
+        port: None,
         is_trusted: true,
         key_type: KeyType::ED25519,
         public_key: "AAAAC3NzaC1lZDI1NTE5AAAAIGObtXLh/mZom0pXjE5Mu211O+JvtzolqdNKVA+XJ466".into(),
@@ -16,6 +16,7 @@ pub fn ssh_hostkey_sourcehut() -> HostKey {
 
     HostKey {
         host: "git.sr.ht".into(),
+        port: None,
         is_trusted: true,
         key_type: KeyType::ED25519,
         public_key: "AAAAC3NzaC1lZDI1NTE5AAAAIMZvRd4EtM7R+IHVMWmDkVU3VLQTSwQDSAvW0t2Tkj60".into(),
@@ -0,0 +1,282 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use hmac::{ Hmac, KeyInit, Mac };
+use sha1::Sha1;
+
+use crate::log;
+
+use super::host_key::HostKey;
+use super::key_type::KeyType;
+use super::super::keyscan::SSH_DEFAULT_PORT;
+use super::super::util::ssh_util_pattern_match;
+
+
+/// The lines of an OpenSSH known_hosts file. Lines with key types we
+/// don't support are skipped.
+#[derive(Clone, Debug, Default)]
+pub struct KnownHostsFile {
+    pub entries: Vec<KnownHostsEntry>,
+}
+
+/// "@cert-authority *.example.org,!git.example.org ssh-ed25519 AAAAC3NzaC1lZDI1N… comment"
+#[derive(Clone, Debug, PartialEq)]
+pub struct KnownHostsEntry {
+    pub marker: Option<KnownHostsMarker>,
+    pub patterns: Vec<String>, // "github.com", "[git.example.org]:2222", "*.example.org" or hashed "|1|salt|hash"
+    pub key_type: KeyType,
+    pub public_key: String,
+    pub comment: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KnownHostsMarker {
+    CertAuthority,
+    Revoked,
+}
+
+
+impl str::FromStr for KnownHostsFile {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Docs: https://man.openbsd.org/sshd#SSH_KNOWN_HOSTS_FILE_FORMAT
+        let mut entries = Vec::new();
+
+        for line in s.lines() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line.parse::<KnownHostsEntry>() {
+                Ok(entry) => entries.push(entry),
+                Err(e) => log::debug(&format!("Keys | Skipping known_hosts line: {e}")),
+            }
+        }
+
+        Ok(KnownHostsFile { entries })
+    }
+}
+
+
+impl str::FromStr for KnownHostsEntry {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace().peekable();
+
+        let marker = match parts.peek() {
+            Some(&"@cert-authority") => Some(KnownHostsMarker::CertAuthority),
+            Some(&"@revoked") => Some(KnownHostsMarker::Revoked),
+            Some(marker) if marker.starts_with('@') => return Err(format!("Unknown marker `{marker}`").into()),
+            _ => None,
+        };
+
+        if marker.is_some() {
+            parts.next();
+        }
+
+        let patterns   = parts.next().ok_or("Missing host")?;
+        let key_type   = parts.next().ok_or("Missing key type")?;
+        let public_key = parts.next().ok_or("Missing public key")?;
+        let comment    = parts.collect::<Vec<_>>().join(" ");
+
+        Ok(KnownHostsEntry {
+            marker,
+            patterns: patterns.split(',').map(String::from).collect(),
+            key_type: KeyType::from_public_key(key_type)?,
+            public_key: public_key.to_string(),
+            comment: (!comment.is_empty()).then_some(comment),
+        })
+    }
+}
+
+
+impl fmt::Display for KnownHostsFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            writeln!(f, "{entry}")?;
+        }
+
+        Ok(())
+    }
+}
+
+
+impl fmt::Display for KnownHostsEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.marker {
+            Some(KnownHostsMarker::CertAuthority) => write!(f, "@cert-authority ")?,
+            Some(KnownHostsMarker::Revoked) => write!(f, "@revoked ")?,
+            None => {},
+        }
+
+        write!(f, "{} {} {}",
+            self.patterns.join(","),
+            self.key_type.to_public_key_prefix(),
+            self.public_key)?;
+
+        if let Some(comment) = &self.comment {
+            write!(f, " {comment}")?;
+        }
+
+        Ok(())
+    }
+}
+
+
+impl KnownHostsFile {
+    pub fn from_file(path: &Path) -> Result<KnownHostsFile, Box<dyn Error>> {
+        fs::read_to_string(path)?.parse()
+    }
+
+
+    pub fn write(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(path, self.to_string())?;
+        Ok(())
+    }
+
+
+    /// "github.com" on the default port, "[git.example.org]:2222" otherwise
+    pub fn host_name(host: &str, port: Option<u16>) -> String {
+        match port {
+            Some(port) if port != SSH_DEFAULT_PORT => format!("[{host}]:{port}"),
+            _ => host.to_string(),
+        }
+    }
+
+
+    /// The usable keys for a host, in file order. Revoked keys are left out.
+    pub fn host_keys(&self, host: &str, port: Option<u16>) -> Vec<HostKey> {
+        let name = Self::host_name(host, port);
+
+        self.entries.iter()
+            .filter(|entry| entry.marker.is_none() && entry.matches(&name))
+            .filter(|entry| !self.is_revoked(&entry.public_key))
+            .map(|entry| HostKey {
+                host: host.to_string(),
+                port,
+                is_trusted: true,
+                key_type: entry.key_type,
+                public_key: entry.public_key.clone(),
+                fingerprint: None,
+            })
+            .collect()
+    }
+
+
+    pub fn host_key(&self, host: &str, port: Option<u16>, key_type: KeyType) -> Option<HostKey> {
+        self.host_keys(host, port)
+            .into_iter()
+            .find(|host_key| host_key.key_type == key_type)
+    }
+
+
+    /// Keys of certificate authorities that may sign host keys for a host
+    pub fn cert_authorities(&self, host: &str, port: Option<u16>) -> Vec<&KnownHostsEntry> {
+        let name = Self::host_name(host, port);
+
+        self.entries.iter()
+            .filter(|entry| entry.marker == Some(KnownHostsMarker::CertAuthority) && entry.matches(&name))
+            .collect()
+    }
+
+
+    /// Revocations apply to every host
+    pub fn is_revoked(&self, public_key: &str) -> bool {
+        self.entries.iter().any(|entry|
+            entry.marker == Some(KnownHostsMarker::Revoked) && entry.public_key == public_key
+        )
+    }
+
+
+    /// Replaces the host's key of the same type, other keys are kept
+    pub fn set(&mut self, host_key: &HostKey) {
+        self.remove(&host_key.host, host_key.port, host_key.key_type);
+        self.entries.push(KnownHostsEntry::from(host_key));
+    }
+
+
+    /// Removes the plain entries written for a host, wildcards and hashed entries are left alone
+    pub fn remove(&mut self, host: &str, port: Option<u16>, key_type: KeyType) {
+        let name = Self::host_name(host, port);
+
+        self.entries.retain(|entry|
+            entry.marker.is_some() ||
+            entry.key_type != key_type ||
+            entry.patterns != [name.clone()]
+        );
+    }
+}
+
+
+impl From<&HostKey> for KnownHostsEntry {
+    fn from(host_key: &HostKey) -> Self {
+        KnownHostsEntry {
+            marker: None,
+            patterns: vec![KnownHostsFile::host_name(&host_key.host, host_key.port)],
+            key_type: host_key.key_type,
+            public_key: host_key.public_key.clone(),
+            comment: None,
+        }
+    }
+}
+
+
+impl KnownHostsEntry {
+    /// A negated pattern that matches excludes the host, even if other patterns match
+    pub fn matches(&self, name: &str) -> bool {
+        let mut is_match = false;
+
+        for pattern in &self.patterns {
+            match pattern.strip_prefix('!') {
+                Some(pattern) if Self::pattern_matches(pattern, name) => return false,
+                Some(_) => {},
+                None => is_match |= Self::pattern_matches(pattern, name),
+            }
+        }
+
+        is_match
+    }
+
+
+    fn pattern_matches(pattern: &str, name: &str) -> bool {
+        match pattern.strip_prefix("|1|") {
+            Some(hashed) => {
+                let Some((salt, _)) = hashed.split_once('|') else { return false };
+                let Ok(salt) = BASE64.decode(salt) else { return false };
+
+                Self::hash_host(name, &salt) == pattern
+            },
+            None => ssh_util_pattern_match(pattern, name),
+        }
+    }
+
+
+    /// "|1|salt|hash", the HMAC-SHA1 of the host name keyed with the salt,
+    /// as written by `ssh-keygen -H` and HashKnownHosts
+    pub fn hash_host(name: &str, salt: &[u8]) -> String {
+        let Ok(mut mac) = Hmac::<Sha1>::new_from_slice(salt) else {
+            return String::new(); // HMAC takes keys of any length
+        };
+
+        mac.update(name.as_bytes());
+        let hash = mac.finalize().into_bytes();
+
+        format!("|1|{}|{}", BASE64.encode(salt), BASE64.encode(hash))
+    }
+}
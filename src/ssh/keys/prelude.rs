@@ -5,8 +5,10 @@
 //   under the terms of the GNU General Public License v3 or any later version.
 
 
+pub use super::certificate::Certificate;
 pub use super::fingerprint::Fingerprint;
 pub use super::host_key::HostKey;
 pub use super::key_pair::KeyPair;
 pub use super::key_size::KeySize;
 pub use super::key_type::KeyType;
+pub use super::known_hosts_file::{ KnownHostsEntry, KnownHostsFile, KnownHostsMarker };
@@ -12,6 +12,7 @@ use crate::log;
 
 use super::keygen::ssh_keygen_fingerprint;
 use super::keys::host_key::HostKey;
+use super::keys::known_hosts_file::KnownHostsFile;
 use super::keys::key_type::KeyType;
 
 
@@ -25,54 +26,77 @@ pub fn ssh_keyscan(
     key_type: KeyType,
 ) -> Result<HostKey, Box<dyn Error>>
 {
-    let args = [
+    scan(host, port, Some(key_type))?
+        .into_iter()
+        .find(|host_key| host_key.key_type == key_type)
+        .ok_or_else(|| "No key line".into())
+}
+
+
+/// All keys the host offers, of the types we support
+pub fn ssh_keyscan_all(host: &str, port: Option<u16>) -> Result<Vec<HostKey>, Box<dyn Error>> {
+    let host_keys = scan(host, port, None)?;
+
+    if host_keys.is_empty() {
+        return Err("No key lines".into());
+    }
+
+    Ok(host_keys)
+}
+
+
+fn scan(
+    host: &str,
+    port: Option<u16>,
+    key_type: Option<KeyType>,
+) -> Result<Vec<HostKey>, Box<dyn Error>>
+{
+    let port_arg = port.unwrap_or(SSH_DEFAULT_PORT).to_string();
+    let mut args = vec![
         // "-q", // Skip banner comments. Too new: added in OpenSSH 9.8 (July 2024)
-        "-p", &port.unwrap_or(SSH_DEFAULT_PORT).to_string(), // Port
-        "-t", &key_type.to_string(), // Key type
-        host,
+        "-p", &port_arg, // Port
     ];
 
+    let key_type_arg = key_type.map(|key_type| key_type.to_string());
+
+    if let Some(key_type) = &key_type_arg {
+        args.extend(["-t", key_type]); // Key type
+    }
+
+    args.push(host);
+
     log::debug(&format!("ssh-keyscan {}", args.join(" ")));
 
-    let ssh_keyscan = Command::new("ssh-keyscan")
-        .args(args)
-        .output();
+    let output = Command::new("ssh-keyscan")
+        .args(&args)
+        .output()
+        .map_err(|e| format!("Could not run ssh-keyscan: {e}"))?;
 
-    match ssh_keyscan {
-        Ok(output) => {
-            if !output.status.success() {
-                let code = output.status
-                    .code()
-                    .unwrap_or_default();
+    if !output.status.success() {
+        let code = output.status
+            .code()
+            .unwrap_or_default();
 
-                let stderr = String::from_utf8_lossy(&output.stderr)
-                    .trim()
-                    .to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr)
+            .trim()
+            .to_string();
 
-                return Err(format!("ssh-keyscan exited with error {code}: {stderr}").into());
-            }
+        return Err(format!("ssh-keyscan exited with error {code}: {stderr}").into());
+    }
 
-            // "github.com ssh-ed25519 AAAAC…"
-            let first_key_line = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .filter(|l| !l.starts_with('#')) // Skip banner comments, same as -q
-                .collect::<Vec<_>>()
-                .into_iter()
-                .next()
-                .ok_or("No key line")?
-                .to_string();
-
-            let public_key = first_key_line
-                .split_whitespace()
-                .nth(2)
-                .ok_or("No key body")?
-                .to_string();
+    // "github.com ssh-ed25519 AAAAC…", banner comments are skipped like with -q
+    let file: KnownHostsFile = String::from_utf8_lossy(&output.stdout).parse()?;
 
+    let host_keys = file.entries
+        .into_iter()
+        .filter(|entry| entry.marker.is_none())
+        .map(|entry| {
             let mut host_key = HostKey {
                 host: host.to_string(),
+                port,
                 is_trusted: false,
-                key_type,
-                public_key,
+                key_type: entry.key_type,
+                public_key: entry.public_key,
                 fingerprint: None,
             };
 
@@ -80,8 +104,9 @@ pub fn ssh_keyscan(
                 host_key.fingerprint = Some(fingerprint);
             }
 
-            Ok(host_key)
-        },
-        Err(e) => Err(format!("Could not run ssh-keyscan: {e}").into())
-    }
+            host_key
+        })
+        .collect();
+
+    Ok(host_keys)
 }
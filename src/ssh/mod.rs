@@ -21,19 +21,24 @@ pub mod keys {
         pub mod sourcehut;
     }
 
+    pub mod certificate;
     pub mod fingerprint;
     pub mod host_key;
     pub mod key_pair;
     pub mod key_size;
     pub mod key_type;
+    pub mod known_hosts_file;
     pub mod prelude;
 }
 
 pub mod objects {
     pub mod config;
+    pub mod config_file;
+    pub mod forge;
     pub mod url;
 }
 
+pub mod agent;
 pub mod keygen;
 pub mod keyscan;
 pub mod util;
@@ -16,11 +16,16 @@ pub struct SshConfig {
     // Docs: https://man.openbsd.org/ssh_config
 
     pub BatchMode: bool, // password prompts and host key confirmation requests will be disabled
+    pub CertificateFile: Option<PathBuf>, // file from which the user's certificate is read. A corresponding private key must be provided separately
     pub ConnectionAttempts: u32, // number of tries (one per second) to make before exiting. The argument must be an integer. This may be useful in scripts if the connection sometimes fails. The default is 1.
     pub ConnectTimeout: Duration, // instead of using the default system TCP timeout.
+    pub HostKeyAlias: Option<String>, // alias that should be used instead of the real host name when looking up or saving the host key in the host key database files
+    pub HostName: Option<String>, // real host name to log into. Default is the name given on the command line
     pub IdentitiesOnly: bool, //  only use the configured authentication identity and certificate files (either the default files, or those explicitly configured in the ssh_config files or passed on the ssh(1) command-line), even if ssh-agent(1) or a PKCS11Provider or SecurityKeyProvider offers more identities
     pub IdentityFile: Option<PathBuf>, // Specifies a file from which the user's ECDSA, authenticator-hosted ECDSA, Ed25519, authenticator-hosted Ed25519 or RSA authentication identity is read
     pub PasswordAuthentication: bool, //  whether to use password authenticatio
+    pub Port: Option<u16>, // port number to connect on the remote host. The default is 22
+    pub ProxyJump: Option<String>, // one or more jump proxies as either [user@]host[:port] or an ssh URI. Multiple proxies may be separated by comma characters and will be visited sequentially
     pub ServerAliveCountMax: u32, // number of ServerAliveInterval after which to disconnect
     pub ServerAliveInterval: Duration, // Sets a timeout interval in seconds after which if no data has been received from the server, ssh(1) will send a message through the encrypted channel to request a response from the server.
     pub StrictHostKeyChecking: bool, //  never automatically add host keys to the ~/.ssh/known_hosts file, and refuses to connect to hosts whose host key has changed
@@ -35,11 +40,16 @@ impl Default for SshConfig {
 
         SshConfig {
             BatchMode: true,
+            CertificateFile: None,
             ConnectionAttempts: 2,
             ConnectTimeout: Duration::from_secs(4),
+            HostKeyAlias: None,
+            HostName: None,
             IdentitiesOnly: false,
             IdentityFile: None,
             PasswordAuthentication: false,
+            Port: None,
+            ProxyJump: None,
             ServerAliveCountMax: 2,
             ServerAliveInterval: Duration::from_secs(4),
             StrictHostKeyChecking: true,
@@ -62,6 +72,36 @@ impl fmt::Display for SshConfig {
             format!("-o StrictHostKeyChecking={}", format_bool(self.StrictHostKeyChecking)),
         ];
 
+        if let Some(v) = &self.CertificateFile {
+            options.push(
+                format!("-o CertificateFile={}", v.to_string_lossy())
+            );
+        }
+
+        if let Some(v) = &self.HostKeyAlias {
+            options.push(
+                format!("-o HostKeyAlias={v}")
+            );
+        }
+
+        if let Some(v) = &self.HostName {
+            options.push(
+                format!("-o HostName={v}")
+            );
+        }
+
+        if let Some(v) = &self.Port {
+            options.push(
+                format!("-o Port={v}")
+            );
+        }
+
+        if let Some(v) = &self.ProxyJump {
+            options.push(
+                format!("-o ProxyJump={v}")
+            );
+        }
+
         if let Some(v) = &self.IdentityFile {
             options.push(
                 format!("-o IdentityFile={}", v.to_string_lossy())
@@ -80,6 +120,20 @@ impl fmt::Display for SshConfig {
 }
 
 
+impl SshConfig {
+    /// Takes the options that describe how to reach a host from `host`
+    pub fn with_host_options(self, host: &SshConfig) -> SshConfig {
+        SshConfig {
+            HostKeyAlias: host.HostKeyAlias.clone().or(self.HostKeyAlias),
+            HostName: host.HostName.clone().or(self.HostName),
+            Port: host.Port.or(self.Port),
+            ProxyJump: host.ProxyJump.clone().or(self.ProxyJump),
+            ..self
+        }
+    }
+}
+
+
 fn format_bool(value: bool) -> &'static str {
     if value { "yes" } else { "no" }
 }
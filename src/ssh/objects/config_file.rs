@@ -0,0 +1,206 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str;
+
+use super::config::SshConfig;
+use super::super::util::ssh_util_pattern_match;
+
+
+/// The parts of an OpenSSH client config file (~/.ssh/config) we can use.
+/// Match blocks and Include directives are skipped.
+#[derive(Clone, Debug, Default)]
+pub struct SshConfigFile {
+    pub hosts: Vec<SshConfigHost>,
+}
+
+/// Host github.com *.example.org !internal.example.org
+///     HostName ssh.github.com
+///     Port 443
+#[derive(Clone, Debug, Default)]
+pub struct SshConfigHost {
+    pub patterns: Vec<String>,
+    pub options: Vec<(String, String)>,
+}
+
+
+impl str::FromStr for SshConfigFile {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Docs: https://man.openbsd.org/ssh_config
+        let mut hosts: Vec<SshConfigHost> = Vec::new();
+        let mut in_match = false;
+
+        for line in s.lines() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            // "Keyword value", "Keyword=value" or "Keyword = value"
+            let (keyword, value) = line
+                .split_once(|c: char| c.is_whitespace() || c == '=')
+                .ok_or_else(|| format!("Missing value in `{line}`"))?;
+
+            let value = value.trim_start_matches(|c: char| c.is_whitespace() || c == '=').trim();
+            let value = value.trim_matches('"');
+
+            match keyword.to_lowercase().as_str() {
+                "host" => {
+                    hosts.push(SshConfigHost {
+                        patterns: value.split_whitespace().map(String::from).collect(),
+                        options: Vec::new(),
+                    });
+
+                    in_match = false;
+                },
+                "match" => in_match = true,
+                "include" => {},
+                _ => {
+                    // Options before the first Host block apply to all hosts
+                    if hosts.is_empty() {
+                        hosts.push(SshConfigHost {
+                            patterns: vec!["*".into()],
+                            options: Vec::new(),
+                        });
+                    }
+
+                    if !in_match {
+                        let host = hosts.last_mut().ok_or("No Host block")?;
+                        host.options.push((keyword.to_string(), value.to_string()));
+                    }
+                },
+            }
+        }
+
+        Ok(SshConfigFile { hosts })
+    }
+}
+
+
+impl fmt::Display for SshConfigFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let blocks: Vec<String> = self.hosts.iter().map(|host| host.to_string()).collect();
+        write!(f, "{}", blocks.join("\n"))
+    }
+}
+
+
+impl fmt::Display for SshConfigHost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Host {}", self.patterns.join(" "))?;
+
+        for (keyword, value) in &self.options {
+            writeln!(f, "    {keyword} {value}")?;
+        }
+
+        Ok(())
+    }
+}
+
+
+impl SshConfigFile {
+    pub fn from_file(path: &Path) -> Result<SshConfigFile, Box<dyn Error>> {
+        fs::read_to_string(path)?.parse()
+    }
+
+
+    /// The value for `keyword` that applies to `host`. Like ssh, the first
+    /// matching block that sets it wins.
+    pub fn get(&self, host: &str, keyword: &str) -> Option<&str> {
+        self.hosts.iter()
+            .filter(|block| block.matches(host))
+            .find_map(|block| block.get(keyword))
+    }
+
+
+    /// The options we support for `host`, merged from all matching blocks
+    pub fn to_config(&self, host: &str) -> Result<SshConfig, Box<dyn Error>> {
+        let port = match self.get(host, "Port") {
+            Some(port) => Some(port.parse::<u16>().map_err(|_| format!("Not a valid Port: `{port}`"))?),
+            None => None,
+        };
+
+        let config = SshConfig {
+            HostKeyAlias: self.get(host, "HostKeyAlias").map(String::from),
+            HostName: self.get(host, "HostName").map(|name| name.replace("%h", host)),
+            Port: port,
+            ProxyJump: self.get(host, "ProxyJump")
+                .filter(|jump| !jump.eq_ignore_ascii_case("none"))
+                .map(String::from),
+            ..Default::default()
+        };
+
+        // Values end up in a command line that's split on whitespace
+        for value in [&config.HostKeyAlias, &config.HostName, &config.ProxyJump].into_iter().flatten() {
+            if value.contains(char::is_whitespace) {
+                return Err(format!("Unsupported value with spaces: `{value}`").into());
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+
+impl SshConfigHost {
+    /// Applies `config`'s host options to a single block for `host`
+    pub fn from_config(host: &str, config: &SshConfig) -> SshConfigHost {
+        let mut options = Vec::new();
+
+        if let Some(v) = &config.HostName {
+            options.push(("HostName".into(), v.clone()));
+        }
+
+        if let Some(v) = &config.Port {
+            options.push(("Port".into(), v.to_string()));
+        }
+
+        if let Some(v) = &config.ProxyJump {
+            options.push(("ProxyJump".into(), v.clone()));
+        }
+
+        if let Some(v) = &config.HostKeyAlias {
+            options.push(("HostKeyAlias".into(), v.clone()));
+        }
+
+        SshConfigHost {
+            patterns: vec![host.to_string()],
+            options,
+        }
+    }
+
+
+    pub fn get(&self, keyword: &str) -> Option<&str> {
+        self.options.iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(keyword))
+            .map(|(_, v)| v.as_str())
+    }
+
+
+    /// A negated pattern that matches excludes the host, even if other patterns match
+    pub fn matches(&self, host: &str) -> bool {
+        let mut is_match = false;
+
+        for pattern in &self.patterns {
+            match pattern.strip_prefix('!') {
+                Some(pattern) if ssh_util_pattern_match(pattern, host) => return false,
+                Some(_) => {},
+                None => is_match |= ssh_util_pattern_match(pattern, host),
+            }
+        }
+
+        is_match
+    }
+}
+
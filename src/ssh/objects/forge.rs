@@ -0,0 +1,228 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::str;
+
+use crate::log;
+use crate::util::util_parse_size;
+
+use super::super::keys::known_hosts::prelude::*;
+use super::super::keys::host_key::HostKey;
+use super::super::keys::key_type::KeyType;
+
+
+/// A Git host we know how to talk to without asking the user
+#[derive(Clone, Debug, Default)]
+pub struct KnownForge {
+    pub host: String,
+    pub host_keys: Vec<HostKey>, // Pinned, at most one per key type
+    pub settings_url: Option<String>, // Where users add their SSH keys
+    pub default_user: Option<String>, // None if users log in with their own name
+    pub test_exit_codes: Vec<i32>, // Exit codes of `ssh -T` that still mean we're authenticated
+    pub max_file_size: Option<u64>, // In bytes, for files not stored with LFS
+    pub lfs_max_file_size: Option<u64>,
+}
+
+
+/// The built-in forges, with the ones from a forges file on top:
+///
+///     Forge git.example.org
+///         HostKey ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIOMqqnkVzrm0SdG6UOoqKLsabgH5C9okWi0dh2l9GKJl
+///         SettingsURL https://git.example.org/-/user_settings/ssh_keys
+///         User git
+///         TestExitCode 1
+///         MaxFileSize 100m
+///         LFSMaxFileSize 2g
+#[derive(Clone, Debug, Default)]
+pub struct KnownForges {
+    pub forges: Vec<KnownForge>,
+}
+
+
+impl KnownForge {
+    fn builtin(
+        host: &str,
+        host_key: Option<HostKey>,
+        settings_url: Option<&str>,
+        default_user: Option<&str>,
+    ) -> KnownForge
+    {
+        KnownForge {
+            host: host.to_string(),
+            host_keys: host_key.into_iter().collect(),
+            settings_url: settings_url.map(String::from),
+            default_user: default_user.map(String::from),
+            test_exit_codes: Vec::new(),
+            max_file_size: None,
+            lfs_max_file_size: None,
+        }
+    }
+
+
+    /// The pinned key of `key_type`, for any port the host is reached on
+    pub fn host_key(&self, key_type: KeyType, port: Option<u16>) -> Option<HostKey> {
+        self.host_keys.iter()
+            .find(|host_key| host_key.key_type == key_type)
+            .map(|host_key| HostKey {
+                host: self.host.clone(),
+                port,
+                is_trusted: true,
+                ..host_key.clone()
+            })
+    }
+
+
+    pub fn is_test_success(&self, exit_code: i32) -> bool {
+        exit_code == 0 || self.test_exit_codes.contains(&exit_code)
+    }
+}
+
+
+impl str::FromStr for KnownForges {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut forges: Vec<KnownForge> = Vec::new();
+
+        for line in s.lines() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (keyword, value) = line
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| format!("Missing value in `{line}`"))?;
+
+            let value = value.trim();
+
+            if keyword.eq_ignore_ascii_case("Forge") {
+                forges.push(KnownForge {
+                    host: value.to_string(),
+                    ..Default::default()
+                });
+
+                continue;
+            }
+
+            let forge = forges.last_mut().ok_or_else(|| format!("`{keyword}` before the first Forge"))?;
+
+            match keyword.to_lowercase().as_str() {
+                "hostkey" => {
+                    let public_key = value.split_whitespace().nth(1).ok_or_else(|| format!("Invalid host key `{value}`"))?;
+
+                    forge.host_keys.retain(|host_key| host_key.public_key != public_key);
+                    forge.host_keys.push(HostKey {
+                        host: forge.host.clone(),
+                        is_trusted: true,
+                        key_type: KeyType::from_public_key(value)?,
+                        public_key: public_key.to_string(),
+                        ..Default::default()
+                    });
+                },
+                "settingsurl"    => forge.settings_url = Some(value.to_string()),
+                "user"           => forge.default_user = Some(value.to_string()),
+                "testexitcode"   => forge.test_exit_codes.push(value.parse().map_err(|_| format!("Not an exit code: `{value}`"))?),
+                "maxfilesize"    => forge.max_file_size = Some(util_parse_size(value)?),
+                "lfsmaxfilesize" => forge.lfs_max_file_size = Some(util_parse_size(value)?),
+                _ => return Err(format!("Unknown keyword `{keyword}`").into()),
+            }
+        }
+
+        Ok(KnownForges { forges })
+    }
+}
+
+
+impl KnownForges {
+    pub fn builtin() -> KnownForges {
+        let github = KnownForge {
+            test_exit_codes: vec![1], // GitHub has no shell, even when authenticated
+            max_file_size: Some(100 * 1024 * 1024), // Pushes with larger files are rejected
+            lfs_max_file_size: Some(2 * 1024 * 1024 * 1024), // On the Free and Pro plans
+            ..KnownForge::builtin("github.com", Some(ssh_hostkey_github()), Some("https://github.com/settings/keys"), Some("git"))
+        };
+
+        let forges = vec![
+            KnownForge::builtin("bitbucket.org", Some(ssh_hostkey_bitbucket()), Some("https://bitbucket.org/account/settings/ssh-keys/"), Some("git")),
+            KnownForge::builtin("codeberg.org", Some(ssh_hostkey_codeberg()), Some("https://codeberg.org/user/settings/keys"), Some("git")),
+            KnownForge::builtin("git.code.sf.net", Some(ssh_hostkey_sourceforge()), Some("https://sourceforge.net/auth/shell_services"), None),
+            KnownForge::builtin("git.launchpad.net", None, Some("https://launchpad.net/~/+editsshkeys"), None),
+            KnownForge::builtin("git.savannah.gnu.org", Some(ssh_hostkey_savannah()), Some("https://savannah.gnu.org/my/admin/editsshkeys.php"), None),
+            KnownForge::builtin("git.sr.ht", Some(ssh_hostkey_sourcehut()), Some("https://meta.sr.ht/keys/ssh-keys"), Some("git")),
+            KnownForge::builtin("gitee.com", Some(ssh_hostkey_gitee()), Some("https://gitee.com/profile/sshkeys"), Some("git")),
+            github,
+            KnownForge::builtin("gitlab.com", Some(ssh_hostkey_gitlab()), Some("https://gitlab.com/-/user_settings/ssh_keys"), Some("git")),
+            KnownForge::builtin("gitlab.gnome.org", Some(ssh_hostkey_gnome()), Some("https://gitlab.gnome.org/-/user_settings/ssh_keys"), Some("git")),
+            KnownForge::builtin("invent.kde.org", Some(ssh_hostkey_kde()), Some("https://invent.kde.org/-/user_settings/ssh_keys"), Some("git")),
+            KnownForge::builtin("ssh.dev.azure.com", Some(ssh_hostkey_devops()), None, Some("git")), // No universal settings link
+        ];
+
+        KnownForges { forges }
+    }
+
+
+    pub fn from_file(path: &Path) -> Result<KnownForges, Box<dyn Error>> {
+        fs::read_to_string(path)?.parse()
+    }
+
+
+    /// The built-in forges and the ones in `path`, if it exists.
+    /// Forges in the file replace built-in ones for the same host.
+    pub fn load(path: &Path) -> KnownForges {
+        let mut known_forges = Self::builtin();
+
+        if !path.exists() {
+            return known_forges;
+        }
+
+        match Self::from_file(path) {
+            Ok(file) => {
+                for forge in file.forges {
+                    known_forges.forges.retain(|known| known.host != forge.host);
+                    known_forges.forges.push(forge);
+                }
+            },
+            Err(e) => log::warning(&format!("Could not read `{}`: {e}", path.to_string_lossy())),
+        }
+
+        known_forges
+    }
+
+
+    pub fn get(&self, host: &str) -> Option<&KnownForge> {
+        self.forges.iter().find(|forge| forge.host.eq_ignore_ascii_case(host))
+    }
+
+
+    /// Adds the forge's default user to URLs that leave it out:
+    /// "github.com:hbons/Twinkle" -> "git@github.com:hbons/Twinkle"
+    pub fn with_default_user(&self, url: &str) -> Option<String> {
+        let (scheme, rest) = match url.strip_prefix("ssh://") {
+            Some(rest) => ("ssh://", rest),
+            None => ("", url),
+        };
+
+        let host_end = match scheme {
+            "ssh://" => rest.find(['/', ':'])?,
+            _ => rest.find(':')?,
+        };
+
+        let host = &rest[..host_end];
+
+        if host.is_empty() || host.contains(['@', '/']) {
+            return None;
+        }
+
+        let user = self.get(host)?.default_user.as_ref()?;
+        Some(format!("{scheme}{user}@{rest}"))
+    }
+}
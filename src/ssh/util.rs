@@ -6,17 +6,29 @@
 
 
 use std::error::Error;
+use std::fmt;
 use std::process::Command;
 
 use crate::log;
 
+use super::keygen::ssh_keygen_fingerprint;
+use super::keys::fingerprint::Fingerprint;
 use super::keys::host_key::HostKey;
 use super::keys::key_pair::KeyPair;
 use super::objects::config::SshConfig;
+use super::objects::forge::KnownForge;
 use super::objects::url::SshUrl;
 
 
-pub fn ssh_util_test_connection(url: &SshUrl, host_key: &HostKey, key_pair: Option<&KeyPair>) -> Result<(), Box<dyn Error>> {
+/// `host_config` holds the options needed to reach the host, like a ProxyJump
+pub fn ssh_util_test_connection(
+    url: &SshUrl,
+    host_key: &HostKey,
+    key_pair: Option<&KeyPair>,
+    host_config: &SshConfig,
+    forge: Option<&KnownForge>,
+) -> Result<(), Box<dyn Error>>
+{
     let mut config = SshConfig::default();
 
     if let Some(key_pair) = key_pair {
@@ -25,6 +37,7 @@ pub fn ssh_util_test_connection(url: &SshUrl, host_key: &HostKey, key_pair: Opti
         let hostkey_path = hostkey_path.join(hostkey_name);
 
         config = SshConfig {
+            CertificateFile: key_pair.certificate.as_ref().map(|certificate| certificate.path.clone()),
             IdentitiesOnly: true,
             IdentityFile: Some(key_pair.private_key_path.clone()),
             UserKnownHostsFile: Some(hostkey_path),
@@ -32,7 +45,14 @@ pub fn ssh_util_test_connection(url: &SshUrl, host_key: &HostKey, key_pair: Opti
         };
     }
 
-    let args = format!("-T {} {}@{}", config, url.user, url.host);
+    let config = config.with_host_options(host_config);
+
+    let port = match url.port {
+        Some(port) => format!("-p {port} "),
+        None => String::new(),
+    };
+
+    let args = format!("-T {}{} {}@{}", port, config, url.user, url.host);
     log::debug(&format!("ssh {}", args));
     let args: Vec<&str> = args.split_whitespace().collect();
 
@@ -40,12 +60,88 @@ pub fn ssh_util_test_connection(url: &SshUrl, host_key: &HostKey, key_pair: Opti
         .args(args)
         .output()?;
 
-    log::debug(format!("{}", String::from_utf8_lossy(&ssh.stderr)).trim());
+    let stderr = String::from_utf8_lossy(&ssh.stderr);
+    log::debug(stderr.trim());
+
+    if ssh_util_is_host_key_changed(&stderr) {
+        return Err(Box::new(SshHostKeyChanged {
+            host: url.host.clone(),
+            old: host_key.fingerprint.clone().or_else(|| ssh_keygen_fingerprint(host_key).ok()),
+            new: ssh_util_offered_fingerprint(&stderr),
+        }));
+    }
 
     match ssh.status.code() {
         Some(0) => Ok(()),
-        Some(1) if url.host == "github.com" => Ok(()), // GitHub is special
+        Some(n) if forge.is_some_and(|forge| forge.is_test_success(n)) => Ok(()), // Forges without a shell
         Some(n) => Err(format!("ssh exited with code {n}").into()),
         None => Err("Could not run ssh".into()),
     }
 }
+
+
+/// @@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@
+/// @    WARNING: REMOTE HOST IDENTIFICATION HAS CHANGED!     @
+/// @@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@
+pub fn ssh_util_is_host_key_changed(output: &str) -> bool {
+    output.contains("REMOTE HOST IDENTIFICATION HAS CHANGED")
+}
+
+
+/// The fingerprint ssh prints after "The fingerprint for the ED25519 key sent by the remote host is"
+pub fn ssh_util_offered_fingerprint(output: &str) -> Option<Fingerprint> {
+    output
+        .split_whitespace()
+        .find_map(|word| word.trim_end_matches('.').parse::<Fingerprint>().ok())
+}
+
+
+/// Patterns as used in ssh_config and known_hosts. They can contain
+/// `*` (zero or more characters) and `?` (exactly one).
+pub fn ssh_util_pattern_match(pattern: &str, s: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let s: Vec<char> = s.to_lowercase().chars().collect();
+
+    let (mut p, mut i) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while i < s.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == s[i]) {
+            p += 1;
+            i += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, i));
+            p += 1;
+        } else if let Some((star_p, star_i)) = star {
+            p = star_p + 1;
+            i = star_i + 1;
+            star = Some((star_p, star_i + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+
+#[derive(Debug)]
+pub struct SshHostKeyChanged {
+    pub host: String,
+    pub old: Option<Fingerprint>,
+    pub new: Option<Fingerprint>,
+}
+
+impl fmt::Display for SshHostKeyChanged {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format = |fingerprint: &Option<Fingerprint>| match fingerprint {
+            Some(fingerprint) => fingerprint.to_string(),
+            None => "unknown".to_string(),
+        };
+
+        write!(f, "Host key for {} has changed from {} to {}",
+            self.host, format(&self.old), format(&self.new))
+    }
+}
+
+impl Error for SshHostKeyChanged {}
@@ -0,0 +1,63 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::fs;
+use std::path::Path;
+
+use crate::app::App;
+use crate::cli::util::cli_trust_host_key;
+use crate::ssh::keys::fingerprint::Fingerprint;
+use crate::ssh::keys::host_key::HostKey;
+use crate::twinkle::twinkle_clone::TwinkleCloneError;
+
+
+fn exit_code(result: Result<impl Sized, Box<dyn std::error::Error>>) -> Option<u8> {
+    result.err()?.downcast_ref::<TwinkleCloneError>().map(|e| e.to_exit_code())
+}
+
+
+#[test]
+fn test_cli_clone_exit_codes() {
+    let path = Path::new("/tmp/twinkle_tests_cli_clone");
+
+    if path.exists() {
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    fs::create_dir_all(path.join("clones")).unwrap();
+
+    let mut app = App {
+        app_keys_dir: path.join("keys"),
+        forges_file: path.join("forges"),
+        ..App::default()
+    };
+
+    // Nothing listens on port 1
+    let args: Vec<String> = [
+        "twinkle", "clone", "--yes", "ssh://git@127.0.0.1:1/repo.git",
+        &path.join("clones").to_string_lossy(),
+    ].iter().map(|s| s.to_string()).collect();
+
+    let result = app.cli_command_clone(&args);
+    assert_eq!(result.as_ref().unwrap_err().to_string(), "Could not connect to 127.0.0.1");
+    assert_eq!(exit_code(result), Some(1));
+
+    // Another host key than the one expected
+    let fingerprint = "SHA256:+DiY3wvvV6TuJJhbpZisF/zLDA0zPMSvHdkr4UvCOqU".parse::<Fingerprint>().unwrap();
+    let other = "SHA256:uNiVztksCsDhcc0u9e8BujQXVUpKZIDTMczCvj3tD2s".parse::<Fingerprint>().unwrap();
+
+    let host_key = HostKey {
+        host: "git.example.org".into(),
+        fingerprint: Some(fingerprint.clone()),
+        ..Default::default()
+    };
+
+    assert!(cli_trust_host_key(&host_key, Some(&fingerprint), false).unwrap());
+    assert_eq!(exit_code(cli_trust_host_key(&host_key, Some(&other), false)), Some(2));
+
+    _ = fs::remove_dir_all(path);
+}
@@ -0,0 +1,33 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use crate::cli::util::{ cli_option_flag, cli_option_value, cli_positional_args };
+
+
+#[test]
+fn test_cli_options() {
+    let args: Vec<String> = [
+        "twinkle", "clone", "--yes", "git@github.com:hbons/Twinkle",
+        "--trust-fingerprint=SHA256:+DiY3wvvV6TuJJhbpZisF/zLDA0zPMSvHdkr4UvCOqU", "/tmp",
+    ].iter().map(|s| s.to_string()).collect();
+
+    assert!(cli_option_flag(&args, "--yes"));
+    assert!(!cli_option_flag(&args, "--full"));
+
+    assert_eq!(
+        cli_option_value(&args, "--trust-fingerprint"),
+        Some("SHA256:+DiY3wvvV6TuJJhbpZisF/zLDA0zPMSvHdkr4UvCOqU")
+    );
+
+    assert_eq!(cli_option_value(&args, "--trust"), None);
+    assert_eq!(cli_option_value(&args, "--yes"), None);
+
+    assert_eq!(
+        cli_positional_args(&args),
+        vec!["twinkle", "clone", "git@github.com:hbons/Twinkle", "/tmp"]
+    );
+}
@@ -0,0 +1,35 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::git::objects::lfs_file::GitLfsFile;
+
+
+#[test]
+fn test_git_object_lfs_file_from_str() {
+    let file = GitLfsFile::from_str(concat!(
+        "filepath: videos/my intro.mp4\n",
+        "    size: 1048576\n",
+        "checkout: true\n",
+        "download: false\n",
+        "     oid: sha256 4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\n",
+        " version: https://git-lfs.github.com/spec/v1",
+    )).unwrap();
+
+    assert_eq!(file.path, PathBuf::from("videos/my intro.mp4"));
+    assert_eq!(file.size, 1048576);
+    assert!(!file.downloaded);
+
+    let file = GitLfsFile::from_str("filepath: cover.psd\n    size: 0\ndownload: true").unwrap();
+    assert!(file.downloaded);
+
+    assert!(GitLfsFile::from_str("filepath: cover.psd").is_err());
+    assert!(GitLfsFile::from_str("    size: 12").is_err());
+    assert!(GitLfsFile::from_str("").is_err());
+}
@@ -0,0 +1,33 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::git::objects::lfs_lock::GitLfsLock;
+
+
+#[test]
+fn test_git_object_lfs_lock_from_str() {
+    let lock = GitLfsLock::from_str("art/my cover.psd  \thbons\tID:123").unwrap();
+    assert_eq!(lock.path, PathBuf::from("art/my cover.psd"));
+    assert_eq!(lock.owner, "hbons");
+    assert_eq!(lock.id, "123");
+    assert!(!lock.ours);
+
+    let lock = GitLfsLock::from_str("O art/cover.psd\thbons\tID:123").unwrap();
+    assert_eq!(lock.path, PathBuf::from("art/cover.psd"));
+    assert!(lock.ours);
+
+    let lock = GitLfsLock::from_str("  art/logo.psd \talice\tID:124").unwrap();
+    assert_eq!(lock.path, PathBuf::from("art/logo.psd"));
+    assert!(!lock.ours);
+
+    assert!(GitLfsLock::from_str("art/cover.psd\thbons").is_err());
+    assert!(GitLfsLock::from_str("art/cover.psd\t\tID:123").is_err());
+    assert!(GitLfsLock::from_str("").is_err());
+}
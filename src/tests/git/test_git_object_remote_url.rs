@@ -0,0 +1,59 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::path::Path;
+use crate::git::objects::remote_url::GitRemoteUrl;
+
+
+#[test]
+fn test_git_object_remote_url_from_str() {
+    let url = "git@github.com:hbons/Twinkle".parse::<GitRemoteUrl>().unwrap();
+    assert!(matches!(url, GitRemoteUrl::Ssh(_)));
+    assert!(!url.is_local());
+    assert_eq!(url.host(), Some("github.com"));
+
+    let url = "https://git.example.com/hbons/Twinkle.git".parse::<GitRemoteUrl>().unwrap();
+    assert!(matches!(url, GitRemoteUrl::Https(_)));
+    assert!(!url.is_local());
+    assert_eq!(url.host(), Some("git.example.com"));
+    assert_eq!(url.path(), Path::new("hbons/Twinkle.git"));
+
+    let url = "file:///srv/shared/Twinkle.git".parse::<GitRemoteUrl>().unwrap();
+    assert!(matches!(url, GitRemoteUrl::File(_)));
+    assert!(url.is_local());
+    assert_eq!(url.host(), None);
+    assert_eq!(url.path(), Path::new("/srv/shared/Twinkle.git"));
+
+    let url = "/media/usb/Twinkle.git".parse::<GitRemoteUrl>().unwrap();
+    assert!(matches!(url, GitRemoteUrl::Path(_)));
+    assert!(url.is_local());
+
+    let url = "../Twinkle.git".parse::<GitRemoteUrl>().unwrap();
+    assert!(matches!(url, GitRemoteUrl::Path(_)));
+
+    assert!("https://git.example.com".parse::<GitRemoteUrl>().is_err());
+    assert!("file://relative/Twinkle.git".parse::<GitRemoteUrl>().is_err());
+    assert!("github.com/hbons/Twinkle".parse::<GitRemoteUrl>().is_err());
+}
+
+
+#[test]
+fn test_git_object_remote_url_to_string() {
+    let url = "git@github.com:hbons/Twinkle".parse::<GitRemoteUrl>().unwrap();
+    assert_eq!(url.to_string(), "ssh://git@github.com/hbons/Twinkle");
+    assert_eq!(url.original(), "git@github.com:hbons/Twinkle");
+
+    let url = "https://hbons@git.example.com:8443/hbons/Twinkle.git".parse::<GitRemoteUrl>().unwrap();
+    assert_eq!(url.to_string(), "https://hbons@git.example.com:8443/hbons/Twinkle.git");
+    assert_eq!(url.as_https().unwrap().host_and_port(), "git.example.com:8443");
+
+    let url = "file:///srv/shared/Twinkle.git".parse::<GitRemoteUrl>().unwrap();
+    assert_eq!(url.to_string(), "file:///srv/shared/Twinkle.git");
+
+    let url = "/media/usb/Twinkle.git".parse::<GitRemoteUrl>().unwrap();
+    assert_eq!(url.to_string(), "/media/usb/Twinkle.git");
+}
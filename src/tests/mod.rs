@@ -8,14 +8,26 @@
 #![allow(clippy::field_reassign_with_default)]
 #![allow(clippy::unwrap_used)]
 
+#[cfg(test)]
+mod cli {
+    mod test_cli_clone;
+    mod test_cli_util;
+}
+
 #[cfg(test)]
 mod ssh {
+    mod test_ssh_agent;
     mod test_ssh_keygen;
     mod test_ssh_keyscan;
     mod test_ssh_known_hosts;
+    mod test_ssh_known_hosts_file;
     mod test_ssh_object_hostkey;
+    mod test_ssh_object_certificate;
+    mod test_ssh_object_config_file;
+    mod test_ssh_object_forge;
     mod test_ssh_object_keytype;
     mod test_ssh_object_url;
+    mod test_ssh_util;
     mod test_ssh_version;
 }
 
@@ -25,22 +37,47 @@ mod git {
     mod test_git_object_commit_message;
     mod test_git_object_environment;
     mod test_git_object_file_status;
+    mod test_git_object_lfs_file;
+    mod test_git_object_lfs_lock;
     mod test_git_object_merge_status;
+    mod test_git_object_remote_url;
     mod test_git_object_user;
 }
 
 #[cfg(test)]
 mod twinkle {
+    mod fixtures;
+    mod test_twinkle_channel;
+    mod test_twinkle_clone;
+    mod test_twinkle_compact;
+    mod test_twinkle_credentials;
     mod test_twinkle_default_dir_name;
     mod test_twinkle_default;
+    mod test_twinkle_deletions;
+    mod test_twinkle_history;
     mod test_twinkle_init;
+    mod test_twinkle_keys;
+    mod test_twinkle_lan;
+    mod test_twinkle_lfs;
+    mod test_twinkle_limits;
+    mod test_twinkle_lock;
     mod test_twinkle_object_repository;
     mod test_twinkle_pretty_bool;
     mod test_twinkle_pretty_commit_message;
     mod test_twinkle_pretty_datetime;
     mod test_twinkle_pretty_dir;
+    mod test_twinkle_pretty_size;
+    mod test_twinkle_read_only;
+    mod test_twinkle_relay;
     mod test_twinkle_resolve_paths;
+    mod test_twinkle_select;
     mod test_twinkle_unique_dir;
-    mod test_twinkle_settings_url_for_host;
+    mod test_twinkle_ssh;
+    mod test_twinkle_trash;
     mod test_twinkle_sync;
 }
+
+#[cfg(test)]
+mod util {
+    mod test_util;
+}
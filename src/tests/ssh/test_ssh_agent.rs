@@ -0,0 +1,41 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::fs;
+use std::path::Path;
+
+use crate::ssh::agent::SshAgent;
+use crate::ssh::keygen::ssh_keygen;
+use crate::ssh::keys::key_type::KeyType;
+
+
+#[test]
+fn test_ssh_agent() {
+    let path = Path::new("/tmp/twinkle_tests_agent");
+
+    if path.exists() {
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    fs::create_dir_all(path).unwrap();
+
+    let agent = SshAgent::start(&path.join("agent.sock")).unwrap();
+    assert!(agent.list().unwrap().is_empty());
+
+    let key_pair = ssh_keygen(&path.join("test.key"), KeyType::ED25519, None, None).unwrap();
+    assert!(!agent.contains(&key_pair));
+
+    agent.add(&key_pair).unwrap();
+    assert!(agent.contains(&key_pair));
+    assert_eq!(agent.list().unwrap().len(), 1);
+
+    agent.remove(&key_pair).unwrap();
+    assert!(!agent.contains(&key_pair));
+
+    drop(agent);
+    _ = fs::remove_dir_all(path);
+}
@@ -9,7 +9,7 @@ use std::path::Path;
 use std::thread;
 use std::time::Duration;
 
-use crate::ssh::keygen::{ ssh_keygen, ssh_keygen_fingerprint };
+use crate::ssh::keygen::{ ssh_keygen, ssh_keygen_change_passphrase, ssh_keygen_fingerprint, ssh_keygen_is_encrypted };
 use crate::ssh::keys::key_size::KeySize;
 use crate::ssh::keys::key_type::KeyType;
 use crate::ssh::keys::host_key::HostKey;
@@ -24,7 +24,7 @@ fn test_ssh_keygen() {
         fs::remove_dir_all(keys_dir).unwrap();
     }
 
-    let key_pair = ssh_keygen(&key_path, KeyType::ED25519, Some(KeySize::Bits256)).unwrap();
+    let key_pair = ssh_keygen(&key_path, KeyType::ED25519, Some(KeySize::Bits256), None).unwrap();
 
     assert_eq!(key_pair.key_type, KeyType::ED25519);
     assert!(key_pair.private_key_path.exists());
@@ -49,4 +49,52 @@ fn test_ssh_keygen_fingerprint() {
     let fingerprint = ssh_keygen_fingerprint(&host_key).unwrap();
     assert!(fingerprint.to_string().starts_with("SHA256:"));
     assert_eq!(fingerprint.to_string().len(), 50);
+
+    let host_key = HostKey {
+        key_type: KeyType::ED25519SK,
+        public_key: "AAAAGnNrLXNzaC1lZDI1NTE5QG9wZW5zc2guY29tAAAAIAABAgMEBQYHCAkKCwwNDg8QERITFBUWFxgZGhscHR4fAAAABHNzaDo=".into(),
+        ..Default::default()
+    };
+
+    let fingerprint = ssh_keygen_fingerprint(&host_key).unwrap();
+    assert_eq!(fingerprint.to_string(), "SHA256:/p0CbeE3dk2SyW1OXXsThGc12ezDVD8eGw2/vtztDfk");
+}
+
+
+#[test]
+fn test_ssh_keygen_security_key() {
+    let key_path = Path::new("/tmp/twinkle_tests_keygen_sk/test.key");
+    assert!(ssh_keygen(key_path, KeyType::ED25519SK, None, None).is_err()); // Needs the hardware
+    assert!(!key_path.exists());
+}
+
+
+#[test]
+fn test_ssh_keygen_passphrase() {
+    let keys_dir = Path::new("/tmp/twinkle_tests_keygen_passphrase");
+    let key_path = keys_dir.join("test.key");
+
+    if keys_dir.exists() {
+        fs::remove_dir_all(keys_dir).unwrap();
+    }
+
+    let key_pair = ssh_keygen(&key_path, KeyType::ED25519, None, Some("correct horse")).unwrap();
+    assert_eq!(key_pair.passphrase.as_deref(), Some("correct horse"));
+    assert!(ssh_keygen_is_encrypted(&key_pair));
+
+    assert!(ssh_keygen_change_passphrase(&key_pair, "wrong", "").is_err());
+    assert!(ssh_keygen_is_encrypted(&key_pair));
+
+    ssh_keygen_change_passphrase(&key_pair, "correct horse", "").unwrap();
+    assert!(!ssh_keygen_is_encrypted(&key_pair));
+
+    ssh_keygen_change_passphrase(&key_pair, "", "battery staple").unwrap();
+    assert!(ssh_keygen_is_encrypted(&key_pair));
+
+    let askpass_left = fs::read_dir(keys_dir).unwrap()
+        .any(|entry| entry.unwrap().file_name().to_string_lossy().starts_with(".askpass"));
+
+    assert!(!askpass_left);
+
+    _ = fs::remove_dir_all(keys_dir);
 }
@@ -0,0 +1,138 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::ssh::keys::host_key::HostKey;
+use crate::ssh::keys::key_type::KeyType;
+use crate::ssh::keys::known_hosts_file::{ KnownHostsEntry, KnownHostsFile, KnownHostsMarker };
+
+
+const ED25519: &str = "AAAAC3NzaC1lZDI1NTE5AAAAIOMqqnkVzrm0SdG6UOoqKLsabgH5C9okWi0dh2l9GKJl";
+const ECDSA: &str = "AAAAE2VjZHNhLXNoYTItbmlzdHAyNTYAAAAIbmlzdHAyNTYAAABBBEmKSENjQEezOmxkZMy7opKgwFB9nkt5YRrYMjNuG5N87uRgg6CLrbo5wAdT/y6v0mKV0U2w0WZ2YB/++Tpockg=";
+const REVOKED: &str = "AAAAC3NzaC1lZDI1NTE5AAAAIAfuCHKVTjquxvt6CM6tdG4SLp1Btn/nOeHHE5UOzRdf";
+
+
+fn known_hosts() -> String {
+    format!("\
+# Comment
+github.com,140.82.121.4 ssh-ed25519 {ED25519} github
+github.com ecdsa-sha2-nistp256 {ECDSA}
+[git.example.org]:2222 ssh-ed25519 {ECDSA_AS_ED25519}
+*.example.org,!secret.example.org ssh-ed25519 {ED25519}
+@cert-authority *.example.org ssh-ed25519 {ED25519} CA
+@revoked * ssh-ed25519 {REVOKED}
+gitlab.com ssh-ed25519 {REVOKED}
+github.com ssh-dss AAAAB3NzaC1kc3MAAACBAP1
+",
+    ECDSA_AS_ED25519 = "AAAAC3NzaC1lZDI1NTE5AAAAIPortPortPortPortPortPortPortPortPortPortPort")
+}
+
+
+#[test]
+fn test_ssh_known_hosts_file_parse() {
+    let file: KnownHostsFile = known_hosts().parse().unwrap();
+    assert_eq!(file.entries.len(), 7); // ssh-dss is skipped
+
+    assert_eq!(file.entries[0].patterns, vec!["github.com", "140.82.121.4"]);
+    assert_eq!(file.entries[0].comment.as_deref(), Some("github"));
+    assert_eq!(file.entries[4].marker, Some(KnownHostsMarker::CertAuthority));
+    assert_eq!(file.entries[5].marker, Some(KnownHostsMarker::Revoked));
+
+    // Multiple keys per host
+    let host_keys = file.host_keys("github.com", None);
+    let key_types: Vec<KeyType> = host_keys.iter().map(|host_key| host_key.key_type).collect();
+    assert_eq!(key_types, vec![KeyType::ED25519, KeyType::ECDSA]);
+    assert_eq!(file.host_key("github.com", Some(22), KeyType::ECDSA).unwrap().public_key, ECDSA);
+
+    // Ports
+    assert!(file.host_keys("git.example.org", Some(2222))[0].public_key.contains("Port"));
+    assert_eq!(file.host_keys("git.example.org", None)[0].public_key, ED25519); // Wildcard
+    assert!(file.host_keys("secret.example.org", None).is_empty());
+
+    // Markers
+    assert_eq!(file.cert_authorities("www.example.org", None).len(), 1);
+    assert!(file.is_revoked(REVOKED));
+    assert!(file.host_keys("gitlab.com", None).is_empty());
+}
+
+
+#[test]
+fn test_ssh_known_hosts_file_write() {
+    let mut file: KnownHostsFile = known_hosts().parse().unwrap();
+    let parsed: KnownHostsFile = file.to_string().parse().unwrap();
+    assert_eq!(parsed.entries, file.entries);
+
+    let host_key = HostKey {
+        host: "git.example.org".into(),
+        port: Some(2222),
+        key_type: KeyType::ED25519,
+        public_key: ED25519.into(),
+        ..Default::default()
+    };
+
+    file.set(&host_key);
+    assert_eq!(file.host_keys("git.example.org", Some(2222)).len(), 1);
+    assert_eq!(file.host_key("git.example.org", Some(2222), KeyType::ED25519).unwrap().public_key, ED25519);
+    assert!(file.to_string().contains(&format!("\n[git.example.org]:2222 ssh-ed25519 {ED25519}\n")));
+
+    file.remove("git.example.org", Some(2222), KeyType::ED25519);
+    assert_eq!(file.host_keys("git.example.org", Some(2222)).len(), 0);
+    assert_eq!(file.cert_authorities("git.example.org", None).len(), 1); // Markers are kept
+}
+
+
+#[test]
+fn test_ssh_known_hosts_file_hashed() {
+    let path = Path::new("/tmp/twinkle_tests_known_hosts_hashed");
+
+    if path.exists() {
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    fs::create_dir_all(path).unwrap();
+    let known_hosts = path.join("known_hosts");
+    fs::write(&known_hosts, format!("github.com ssh-ed25519 {ED25519}\n[git.example.org]:2222 ecdsa-sha2-nistp256 {ECDSA}\n")).unwrap();
+
+    let status = Command::new("ssh-keygen")
+        .arg("-H")
+        .arg("-f").arg(&known_hosts)
+        .output()
+        .unwrap()
+        .status;
+
+    assert!(status.success());
+
+    let file = KnownHostsFile::from_file(&known_hosts).unwrap();
+    assert!(file.entries.iter().all(|entry| entry.patterns[0].starts_with("|1|")));
+
+    assert_eq!(file.host_keys("github.com", None)[0].public_key, ED25519);
+    assert_eq!(file.host_keys("git.example.org", Some(2222))[0].key_type, KeyType::ECDSA);
+    assert!(file.host_keys("git.example.org", None).is_empty());
+    assert!(file.host_keys("gitlab.com", None).is_empty());
+
+    let hashed = KnownHostsEntry::hash_host("github.com", &[7; 20]);
+    assert!(hashed.starts_with("|1|BwcHBwcHBwcHBwcHBwcHBwcHBwc=|"));
+
+    fs::remove_dir_all(path).unwrap();
+}
+
+
+#[test]
+fn test_ssh_known_hosts_hostkey_from_file() {
+    let path = Path::new("/tmp/twinkle_tests_known_hosts_from_file");
+    fs::write(path, format!("@revoked * ssh-ed25519 {REVOKED}\n[git.example.org]:2222 ssh-ed25519 {ED25519}\n")).unwrap();
+
+    let host_key = HostKey::from_file(path).unwrap();
+    assert_eq!(host_key.host, "git.example.org");
+    assert_eq!(host_key.port, Some(2222));
+    assert_eq!(host_key.to_string(), format!("[git.example.org]:2222 ssh-ed25519 {ED25519}"));
+
+    fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,87 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::path::Path;
+
+use chrono::{ Duration, TimeZone, Utc };
+
+use crate::ssh::keys::certificate::Certificate;
+
+
+const SSH_KEYGEN_L: &str = "\
+user-cert.pub:
+        Type: ssh-ed25519-cert-v01@openssh.com user certificate
+        Public key: ED25519-CERT SHA256:WKBLjQEjFyuiSOywWLw+oUlym7YkPnybJlWBWMVoNZM
+        Signing CA: ED25519 SHA256:bDAfV3W6zfXaoeeLMCF3r2wpIkWDkngG9JJiYNElUxc (using ssh-ed25519)
+        Key ID: \"alice@corp\"
+        Serial: 0
+        Valid: from 2026-10-19T08:00:00 to 2026-10-20T08:00:00
+        Principals: 
+                git
+                alice
+        Critical Options: (none)
+        Extensions: 
+                permit-pty
+";
+
+
+#[test]
+fn test_ssh_certificate_parse() {
+    let certificate: Certificate = SSH_KEYGEN_L.parse().unwrap();
+
+    assert_eq!(certificate.key_id, "alice@corp");
+    assert_eq!(certificate.principals, vec!["git", "alice"]);
+    assert_eq!(certificate.fingerprint.unwrap().to_string(), "SHA256:WKBLjQEjFyuiSOywWLw+oUlym7YkPnybJlWBWMVoNZM");
+
+    let after = certificate.valid_after.unwrap();
+    let before = certificate.valid_before.unwrap();
+    assert_eq!(before - after, Duration::days(1));
+
+    let forever: Certificate = SSH_KEYGEN_L.replace("from 2026-10-19T08:00:00 to 2026-10-20T08:00:00", "forever").parse().unwrap();
+    assert!(forever.valid_after.is_none() && forever.valid_before.is_none());
+
+    let after: Certificate = SSH_KEYGEN_L.replace("from 2026-10-19T08:00:00 to 2026-10-20T08:00:00", "after 2026-10-19T08:00:00").parse().unwrap();
+    assert!(after.valid_after.is_some() && after.valid_before.is_none());
+
+    assert!("Not a certificate".parse::<Certificate>().is_err());
+}
+
+
+#[test]
+fn test_ssh_certificate_validity() {
+    let after = Utc.with_ymd_and_hms(2026, 10, 19, 8, 0, 0).unwrap();
+
+    let certificate = Certificate {
+        valid_after: Some(after),
+        valid_before: Some(after + Duration::hours(8)),
+        ..Default::default()
+    };
+
+    assert!(!certificate.is_valid(after - Duration::minutes(1)));
+    assert!(certificate.is_valid(after + Duration::hours(1)));
+    assert!(!certificate.expires_soon(after + Duration::hours(5)));
+    assert!(certificate.expires_soon(after + Duration::hours(7))); // Last two hours
+    assert!(certificate.is_expired(after + Duration::hours(8)));
+
+    // Long-lived certificates warn a week before
+    let certificate = Certificate {
+        valid_after: Some(after),
+        valid_before: Some(after + Duration::days(365)),
+        ..Default::default()
+    };
+
+    assert!(!certificate.expires_soon(after + Duration::days(357)));
+    assert!(certificate.expires_soon(after + Duration::days(359)));
+
+    assert!(!Certificate::default().expires_soon(after));
+    assert!(!Certificate::default().is_expired(after));
+
+    assert_eq!(
+        Certificate::path_for(Path::new("/keys/github.com.ed25519.key")),
+        Path::new("/keys/github.com.ed25519.key-cert.pub"),
+    );
+}
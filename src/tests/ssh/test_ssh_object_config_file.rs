@@ -0,0 +1,104 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use crate::ssh::objects::config::SshConfig;
+use crate::ssh::objects::config_file::{ SshConfigFile, SshConfigHost };
+
+
+const SSH_CONFIG: &str = "\
+# Personal settings
+ServerAliveInterval 30
+
+Host github.com
+    HostName ssh.github.com
+    Port 443
+
+Match host *.internal exec \"true\"
+    User nobody
+
+Host git.*.example.org !git.public.example.org
+    ProxyJump=bastion@jump.example.org:2222
+    HostKeyAlias git-internal
+
+Host *
+    Port 22
+    ProxyJump none
+";
+
+
+#[test]
+fn test_ssh_config_file_parse() {
+    let file: SshConfigFile = SSH_CONFIG.parse().unwrap();
+    assert_eq!(file.hosts.len(), 4);
+
+    assert_eq!(file.hosts[0].patterns, vec!["*"]);
+    assert_eq!(file.hosts[1].get("hostname"), Some("ssh.github.com"));
+    assert_eq!(file.get("github.com", "Port"), Some("443"));
+    assert_eq!(file.get("gitlab.com", "Port"), Some("22"));
+    assert_eq!(file.get("anything.internal", "User"), None); // Match blocks are skipped
+
+    assert!(file.hosts[2].matches("git.corp.example.org"));
+    assert!(!file.hosts[2].matches("git.public.example.org"));
+    assert!(!file.hosts[2].matches("www.example.org"));
+}
+
+
+#[test]
+fn test_ssh_config_file_to_config() {
+    let file: SshConfigFile = SSH_CONFIG.parse().unwrap();
+
+    let config = file.to_config("github.com").unwrap();
+    assert_eq!(config.HostName.as_deref(), Some("ssh.github.com"));
+    assert_eq!(config.Port, Some(443));
+    assert_eq!(config.ProxyJump, None);
+
+    let config = file.to_config("git.corp.example.org").unwrap();
+    assert_eq!(config.ProxyJump.as_deref(), Some("bastion@jump.example.org:2222"));
+    assert_eq!(config.HostKeyAlias.as_deref(), Some("git-internal"));
+
+    let file: SshConfigFile = "Host bad\n    Port http\n".parse().unwrap();
+    assert!(file.to_config("bad").is_err());
+}
+
+
+#[test]
+fn test_ssh_config_file_roundtrip() {
+    let config = SshConfig {
+        HostName: Some("10.0.0.5".into()),
+        Port: Some(2222),
+        ProxyJump: Some("jump.example.org".into()),
+        ..Default::default()
+    };
+
+    let block = SshConfigHost::from_config("git.example.org", &config);
+    let s = block.to_string();
+
+    assert_eq!(s, "Host git.example.org\n    HostName 10.0.0.5\n    Port 2222\n    ProxyJump jump.example.org\n");
+
+    let file: SshConfigFile = s.parse().unwrap();
+    let parsed = file.to_config("git.example.org").unwrap();
+
+    assert_eq!(parsed.HostName, config.HostName);
+    assert_eq!(parsed.Port, config.Port);
+    assert_eq!(parsed.ProxyJump, config.ProxyJump);
+}
+
+
+#[test]
+fn test_ssh_config_host_options() {
+    let host = SshConfig {
+        Port: Some(2222),
+        ProxyJump: Some("jump.example.org".into()),
+        ..Default::default()
+    };
+
+    let s = SshConfig::default().with_host_options(&host).to_string();
+
+    assert!(s.contains("-o Port=2222"));
+    assert!(s.contains("-o ProxyJump=jump.example.org"));
+    assert!(!s.contains("HostName"));
+}
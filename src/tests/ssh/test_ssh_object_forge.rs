@@ -0,0 +1,115 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::fs;
+use std::path::Path;
+
+use crate::ssh::keys::key_type::KeyType;
+use crate::ssh::objects::forge::KnownForges;
+
+
+const FORGES: &str = "\
+# Our own forges
+Forge git.example.org
+    HostKey ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIOMqqnkVzrm0SdG6UOoqKLsabgH5C9okWi0dh2l9GKJl
+    HostKey ecdsa-sha2-nistp256 AAAAE2VjZHNhLXNoYTItbmlzdHAyNTYAAAAIbmlzdHAyNTYAAABBBEmKSENjQEezOmxkZMy7opKgwFB9nkt5YRrYMjNuG5N87uRgg6CLrbo5wAdT/y6v0mKV0U2w0WZ2YB/++Tpockg=
+    SettingsURL https://git.example.org/-/user_settings/ssh_keys
+    User git
+    TestExitCode 1
+    MaxFileSize 50m
+
+Forge github.com
+    User github
+";
+
+
+#[test]
+fn test_ssh_forges_builtin() {
+    let forges = KnownForges::builtin();
+
+    let github = forges.get("github.com").unwrap();
+    assert_eq!(github.settings_url.as_deref(), Some("https://github.com/settings/keys"));
+    assert_eq!(github.default_user.as_deref(), Some("git"));
+    assert!(github.is_test_success(1));
+    assert_eq!(github.max_file_size, Some(100 * 1024 * 1024));
+    assert!(!forges.get("gitlab.com").unwrap().is_test_success(1));
+
+    // Pinned keys and settings URL used to disagree on this one
+    let savannah = forges.get("git.savannah.gnu.org").unwrap();
+    assert!(savannah.settings_url.is_some());
+    assert!(savannah.host_key(KeyType::ED25519, None).is_some());
+    assert!(savannah.default_user.is_none());
+
+    let host_key = github.host_key(KeyType::ED25519, Some(443)).unwrap();
+    assert_eq!(host_key.port, Some(443));
+    assert!(host_key.is_trusted);
+    assert!(github.host_key(KeyType::RSA, None).is_none());
+
+    assert!(forges.get("git.launchpad.net").unwrap().host_keys.is_empty());
+    assert!(forges.get("asdfgasdfg").is_none());
+}
+
+
+#[test]
+fn test_ssh_forges_parse() {
+    let file: KnownForges = FORGES.parse().unwrap();
+    assert_eq!(file.forges.len(), 2);
+
+    let forge = &file.forges[0];
+    assert_eq!(forge.host, "git.example.org");
+    assert_eq!(forge.host_keys.len(), 2);
+    assert_eq!(forge.host_key(KeyType::ECDSA, None).unwrap().host, "git.example.org");
+    assert_eq!(forge.default_user.as_deref(), Some("git"));
+    assert_eq!(forge.test_exit_codes, vec![1]);
+    assert_eq!(forge.max_file_size, Some(50 * 1024 * 1024));
+    assert!(forge.lfs_max_file_size.is_none());
+
+    assert!("User git".parse::<KnownForges>().is_err()); // Outside of a Forge
+    assert!("Forge a\n    Colour blue".parse::<KnownForges>().is_err());
+    assert!("Forge a\n    HostKey ssh-dss AAAA".parse::<KnownForges>().is_err());
+    assert!("Forge a\n    MaxFileSize lots".parse::<KnownForges>().is_err());
+}
+
+
+#[test]
+fn test_ssh_forges_load() {
+    let path = Path::new("/tmp/twinkle_tests_forges");
+    let builtin = KnownForges::builtin().forges.len();
+
+    _ = fs::remove_file(path);
+    assert_eq!(KnownForges::load(path).forges.len(), builtin);
+
+    fs::write(path, FORGES).unwrap();
+    let forges = KnownForges::load(path);
+
+    assert_eq!(forges.forges.len(), builtin + 1);
+    assert!(forges.get("git.example.org").is_some());
+
+    // Replaces the built-in one
+    let github = forges.get("github.com").unwrap();
+    assert_eq!(github.default_user.as_deref(), Some("github"));
+    assert!(github.host_keys.is_empty());
+
+    fs::write(path, "Nonsense").unwrap();
+    assert_eq!(KnownForges::load(path).forges.len(), builtin);
+
+    _ = fs::remove_file(path);
+}
+
+
+#[test]
+fn test_ssh_forges_default_user() {
+    let forges = KnownForges::builtin();
+
+    assert_eq!(forges.with_default_user("github.com:hbons/Twinkle").as_deref(), Some("git@github.com:hbons/Twinkle"));
+    assert_eq!(forges.with_default_user("ssh://gitlab.com/hbons/Twinkle").as_deref(), Some("ssh://git@gitlab.com/hbons/Twinkle"));
+    assert_eq!(forges.with_default_user("ssh://gitlab.com:22/hbons/Twinkle").as_deref(), Some("ssh://git@gitlab.com:22/hbons/Twinkle"));
+
+    assert!(forges.with_default_user("git.savannah.gnu.org:/srv/git/emacs.git").is_none()); // Users log in with their own name
+    assert!(forges.with_default_user("git.example.org:hbons/Twinkle").is_none());
+    assert!(forges.with_default_user("./github.com:hbons").is_none());
+}
@@ -10,22 +10,37 @@ use std::path::Path;
 
 use crate::ssh::keys::host_key::HostKey;
 use crate::ssh::keys::key_type::KeyType;
+use crate::ssh::objects::forge::KnownForges;
 use crate::ssh::objects::url::SshUrl;
 
 
 #[test]
 fn test_ssh_hostkey_for_host() {
     let url = "git@github.com:hbons/Twinkle".parse::<SshUrl>().unwrap();
-    let host_key = HostKey::for_known_host(&url, KeyType::ED25519).unwrap();
+    let host_key = HostKey::for_known_host(&url, KeyType::ED25519, KnownForges::builtin().get("github.com")).unwrap();
 
     assert_eq!(host_key.key_type, KeyType::ED25519);
 }
 
 
+#[test]
+fn test_ssh_hostkey_negotiate() {
+    // Pinned, so no network needed
+    let url = "git@github.com:hbons/Twinkle".parse::<SshUrl>().unwrap();
+    let host_key = HostKey::negotiate(&url, KnownForges::builtin().get("github.com")).unwrap();
+
+    assert_eq!(host_key.key_type, KeyType::ED25519);
+    assert!(host_key.is_trusted);
+
+    let url = "ssh://git@localhost:1/hbons/Twinkle".parse::<SshUrl>().unwrap();
+    assert!(HostKey::negotiate(&url, None).is_err());
+}
+
+
 #[test]
 fn test_ssh_hostkey_to_string() {
     let url = "git@github.com:hbons/Twinkle".parse::<SshUrl>().unwrap();
-    let mut host_key = HostKey::for_known_host(&url, KeyType::ED25519).unwrap();
+    let mut host_key = HostKey::for_known_host(&url, KeyType::ED25519, KnownForges::builtin().get("github.com")).unwrap();
 
     assert!(host_key.to_string().starts_with("github.com ssh-ed25519 "));
     host_key.key_type = KeyType::ECDSA;
@@ -36,7 +51,7 @@ fn test_ssh_hostkey_to_string() {
 #[test]
 fn test_ssh_hostkey_to_file_name() {
     let url = "git@github.com:hbons/Twinkle".parse::<SshUrl>().unwrap();
-    let host_key = HostKey::for_known_host(&url, KeyType::ED25519).unwrap();
+    let host_key = HostKey::for_known_host(&url, KeyType::ED25519, KnownForges::builtin().get("github.com")).unwrap();
 
     assert_eq!(host_key.to_file_name(), Path::new("github.com.ed25519.key.host"));
 }
@@ -45,7 +60,7 @@ fn test_ssh_hostkey_to_file_name() {
 #[test]
 fn test_ssh_hostkey_from_file() {
     let url = "git@github.com:hbons/Twinkle".parse::<SshUrl>().unwrap();
-    let host_key = HostKey::for_known_host(&url, KeyType::ED25519).unwrap();
+    let host_key = HostKey::for_known_host(&url, KeyType::ED25519, KnownForges::builtin().get("github.com")).unwrap();
 
     let key_path = Path::new("./src/tests/.tmp/github.com.ed25519.key.host");
     let _ = fs::create_dir_all(key_path.parent().unwrap());
@@ -13,4 +13,32 @@ fn test_ssh_keytype_fmt() {
     assert_eq!(KeyType::ECDSA.to_string(), "ecdsa-sha2-nistp256");
     assert_eq!(KeyType::ED25519.to_string(), "ed25519");
     assert_eq!(KeyType::RSA.to_string(), "rsa");
+    assert_eq!(KeyType::ED25519SK.to_string(), "ed25519-sk");
+    assert_eq!(KeyType::ECDSASK.to_string(), "ecdsa-sk");
+}
+
+
+#[test]
+fn test_ssh_keytype_security_keys() {
+    assert_eq!("ed25519-sk".parse::<KeyType>().unwrap(), KeyType::ED25519SK);
+    assert_eq!("sk-ecdsa-sha2-nistp256@openssh.com".parse::<KeyType>().unwrap(), KeyType::ECDSASK);
+
+    assert_eq!(KeyType::from_public_key("sk-ssh-ed25519@openssh.com AAAAGnNrLXNzaC1l… alice").unwrap(), KeyType::ED25519SK);
+    assert_eq!(KeyType::ED25519SK.to_public_key_prefix(), "sk-ssh-ed25519@openssh.com");
+    assert_eq!(KeyType::ECDSASK.to_public_key_prefix(), "sk-ecdsa-sha2-nistp256@openssh.com");
+
+    assert_eq!(KeyType::ED25519SK.to_host_key_type(), KeyType::ED25519);
+    assert_eq!(KeyType::ECDSASK.to_host_key_type(), KeyType::ECDSA);
+    assert_eq!(KeyType::RSA.to_host_key_type(), KeyType::RSA);
+
+    assert!(KeyType::ECDSASK.is_security_key());
+    assert!(!KeyType::ECDSA.is_security_key());
+    assert!(!KeyType::preferred().iter().any(|key_type| key_type.is_security_key()));
+}
+
+
+#[test]
+fn test_ssh_keytype_preferred() {
+    assert_eq!(KeyType::preferred(), [KeyType::ED25519, KeyType::ECDSA, KeyType::RSA]);
+    assert_eq!(KeyType::preferred()[0], KeyType::default());
 }
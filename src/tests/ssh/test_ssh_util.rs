@@ -0,0 +1,53 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use crate::ssh::keys::fingerprint::Fingerprint;
+use crate::ssh::util::{ SshHostKeyChanged, ssh_util_is_host_key_changed, ssh_util_offered_fingerprint };
+
+
+const HOST_KEY_CHANGED: &str = "\
+@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@
+@    WARNING: REMOTE HOST IDENTIFICATION HAS CHANGED!     @
+@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@
+IT IS POSSIBLE THAT SOMEONE IS DOING SOMETHING NASTY!
+Someone could be eavesdropping on you right now (man-in-the-middle attack)!
+It is also possible that a host key has just been changed.
+The fingerprint for the ED25519 key sent by the remote host is
+SHA256:uNiVztksCsDhcc0u9e8BujQXVUpKZIDTMczCvj3tD2s.
+Please contact your system administrator.
+Add correct host key in /home/hbons/.config/twinkle/keys/github.com.ed25519.key.host to get rid of this message.
+Offending ED25519 key in /home/hbons/.config/twinkle/keys/github.com.ed25519.key.host:1
+Host key for github.com has changed and you have requested strict checking.
+Host key verification failed.";
+
+
+#[test]
+fn test_ssh_util_host_key_changed() {
+    assert!(ssh_util_is_host_key_changed(HOST_KEY_CHANGED));
+    assert!(!ssh_util_is_host_key_changed("Host key verification failed."));
+    assert!(!ssh_util_is_host_key_changed("git@github.com: Permission denied (publickey)."));
+
+    let fingerprint = ssh_util_offered_fingerprint(HOST_KEY_CHANGED).unwrap();
+    assert_eq!(fingerprint.to_string(), "SHA256:uNiVztksCsDhcc0u9e8BujQXVUpKZIDTMczCvj3tD2s");
+
+    assert!(ssh_util_offered_fingerprint("Host key verification failed.").is_none());
+}
+
+
+#[test]
+fn test_ssh_util_host_key_changed_fmt() {
+    let change = SshHostKeyChanged {
+        host: "github.com".into(),
+        old: Some("SHA256:+DiY3wvvV6TuJJhbpZisF/zLDA0zPMSvHdkr4UvCOqU".parse::<Fingerprint>().unwrap()),
+        new: None,
+    };
+
+    assert_eq!(
+        change.to_string(),
+        "Host key for github.com has changed from SHA256:+DiY3wvvV6TuJJhbpZisF/zLDA0zPMSvHdkr4UvCOqU to unknown"
+    );
+}
@@ -0,0 +1,131 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use crate::git::objects::environment::GitEnvironment;
+use crate::git::objects::remote_url::GitRemoteUrl;
+
+use crate::twinkle::objects::repository::TwinkleRepository;
+use crate::twinkle::twinkle_clone::{ twinkle_clone_complete, twinkle_clone_start };
+use crate::twinkle::twinkle_init::twinkle_init;
+
+
+// Each test gets its own folder in /tmp, with a bare "remote.git" to sync
+// through, a "seed" that set it up, and clones of it next to them.
+
+
+/// Where the test called `name` keeps its files
+pub fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(format!("/tmp/twinkle_tests_{name}"))
+}
+
+
+pub fn fixture_remote_url(name: &str) -> GitRemoteUrl {
+    format!("file://{}/remote.git", fixture_path(name).display())
+        .parse::<GitRemoteUrl>().unwrap()
+}
+
+
+/// Starts over with an empty remote, and a repository set up for it in "seed".
+/// Nothing is pushed yet.
+pub fn fixture_seed(name: &str) -> TwinkleRepository {
+    let path = fixture_path(name);
+
+    if path.exists() {
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    fs::create_dir_all(&path).unwrap();
+
+    GitEnvironment::new(&path)
+        .run("init", &["--bare", "--quiet", "--initial-branch=main", "remote.git"])
+        .unwrap();
+
+    fixture_init(name, "seed")
+}
+
+
+/// Another repository set up in `dir` for the same remote, with its own ID
+pub fn fixture_init(name: &str, dir: &str) -> TwinkleRepository {
+    let path = fixture_path(name).join(dir);
+    fs::create_dir_all(&path).unwrap();
+
+    twinkle_init(&path, &fixture_remote_url(name), None).unwrap()
+}
+
+
+/// A seed with `files` committed and pushed, and a clone of it in "clones"
+pub fn fixture_seed_and_clone(name: &str, files: &[(&str, &str)]) -> (TwinkleRepository, TwinkleRepository) {
+    let seed = fixture_seed(name);
+
+    for (file, content) in files {
+        fixture_commit_file(&seed, file, content);
+    }
+
+    fixture_push(&seed);
+
+    let clone = fixture_clone(name, "clones", Some(1));
+    (seed, clone)
+}
+
+
+/// Clones into `dir`, with LFS off as git-lfs may not be installed
+pub fn fixture_clone(name: &str, dir: &str, depth: Option<u32>) -> TwinkleRepository {
+    let path = fixture_path(name).join(dir);
+    fs::create_dir_all(&path).unwrap();
+
+    let mut repo = twinkle_clone_start(&fixture_remote_url(name), None, &path, depth).unwrap();
+    twinkle_clone_complete(&mut repo, None).unwrap();
+    repo.set_lfs_enabled(false).unwrap();
+
+    repo
+}
+
+
+pub fn fixture_push(repo: &TwinkleRepository) {
+    repo.git.run("push", &["--no-verify", "--quiet", "--set-upstream", "origin", "main"]).unwrap(); // Skip the LFS hook
+}
+
+
+pub fn fixture_commit_file(repo: &TwinkleRepository, file: &str, content: &str) {
+    fixture_write(repo, file, content);
+    repo.git.commit(repo.user(), &format!("~ \"{file}\"")).unwrap();
+}
+
+
+/// Like `fixture_commit_file`, but made at `timestamp`
+pub fn fixture_commit_file_at(repo: &TwinkleRepository, file: &str, content: &str, timestamp: i64) {
+    fixture_write(repo, file, content);
+
+    let user = repo.user().unwrap();
+    let date = format!("@{timestamp} +0000");
+
+    let env = vec![
+        ("GIT_AUTHOR_NAME".into(), user.name().into()),
+        ("GIT_AUTHOR_EMAIL".into(), user.email().into()),
+        ("GIT_AUTHOR_DATE".into(), date.clone()),
+        ("GIT_COMMITTER_NAME".into(), user.name().into()),
+        ("GIT_COMMITTER_EMAIL".into(), user.email().into()),
+        ("GIT_COMMITTER_DATE".into(), date),
+    ];
+
+    repo.git.run_with_env("commit", &["--quiet", "-m", &format!("~ \"{file}\"")], env).unwrap();
+}
+
+
+fn fixture_write(repo: &TwinkleRepository, file: &str, content: &str) {
+    let abs_path = repo.abs_path(Path::new(file));
+
+    if let Some(parent) = abs_path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+
+    fs::write(abs_path, content).unwrap();
+    repo.git.add(Path::new(file)).unwrap();
+}
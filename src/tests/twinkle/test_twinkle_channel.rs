@@ -0,0 +1,91 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::fs;
+use std::net::TcpListener;
+use std::thread;
+use std::time::{ Duration, Instant };
+
+use tungstenite::{ accept, Message };
+
+use crate::twinkle::objects::repository_notify::TwinkleNotifyMessage;
+use crate::twinkle::twinkle_channel::{ twinkle_channel_announce, twinkle_channel_listen, twinkle_channel_receive };
+
+use super::fixtures::{ fixture_path, fixture_seed };
+
+
+const CHANNEL: &str = "5d41402abc4b2a76b9719d911017c592ae1aec3d5c6e1d3bd0d5a3a6e1f1e8a0";
+
+
+#[test]
+fn test_twinkle_channel_message() {
+    let message = format!("announce {CHANNEL} 1b2c3d");
+
+    assert_eq!(message.parse::<TwinkleNotifyMessage>().unwrap(), TwinkleNotifyMessage::Announce(CHANNEL.into(), "1b2c3d".into()));
+    assert_eq!(TwinkleNotifyMessage::Announce(CHANNEL.into(), "1b2c3d".into()).to_string(), message);
+    assert_eq!(format!("subscribe {CHANNEL}").parse::<TwinkleNotifyMessage>().unwrap(), TwinkleNotifyMessage::Subscribe(CHANNEL.into()));
+    assert_eq!(format!("unsubscribe {CHANNEL}").parse::<TwinkleNotifyMessage>().unwrap(), TwinkleNotifyMessage::Unsubscribe(CHANNEL.into()));
+
+    assert!("subscribe my-photos".parse::<TwinkleNotifyMessage>().is_err()); // Not hashed
+    assert!(format!("subscribe {CHANNEL} extra").parse::<TwinkleNotifyMessage>().is_err());
+    assert!(format!("announce {CHANNEL}").parse::<TwinkleNotifyMessage>().is_err());
+    assert!(format!("announce {CHANNEL} 1b2c 3d").parse::<TwinkleNotifyMessage>().is_err());
+    assert!(format!("publish {CHANNEL} 1b2c3d").parse::<TwinkleNotifyMessage>().is_err());
+}
+
+
+#[test]
+fn test_twinkle_channel() {
+    let repo = fixture_seed("channel");
+    let channel = repo.as_notify_channel_name().unwrap();
+    let head = repo.current_head().unwrap();
+
+    // Only commits we don't have count
+    let unknown = "0".repeat(40);
+    assert!(twinkle_channel_receive(&repo, &channel, &format!("announce {channel} {unknown}")));
+    assert!(!twinkle_channel_receive(&repo, &channel, &format!("announce {channel} {head}")));
+    assert!(!twinkle_channel_receive(&repo, &channel, &format!("announce {CHANNEL} {unknown}")));
+    assert!(!twinkle_channel_receive(&repo, &channel, &format!("subscribe {channel}")));
+    assert!(!twinkle_channel_receive(&repo, &channel, "Nonsense"));
+
+    // A stand-in server
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    repo.set_notify_url(&format!("ws://{}", listener.local_addr().unwrap())).unwrap();
+
+    let repo_c = repo.clone();
+    thread::spawn(move || { _ = twinkle_channel_listen(&repo_c, false); });
+
+    let mut subscriber = accept(listener.accept().unwrap().0).unwrap();
+    let subscribe = subscriber.read().unwrap().into_text().unwrap();
+    assert_eq!(subscribe.parse::<TwinkleNotifyMessage>().unwrap(), TwinkleNotifyMessage::Subscribe(channel.clone()));
+
+    assert!(!repo.has_remote_changes());
+    subscriber.send(Message::text(format!("announce {channel} {unknown}"))).unwrap();
+
+    let start = Instant::now();
+
+    while !repo.has_remote_changes() {
+        assert!(start.elapsed() < Duration::from_secs(10));
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    // Announcing after a push
+    let repo_c = repo.clone();
+    let announce = thread::spawn(move || twinkle_channel_announce(&repo_c).is_ok());
+
+    let mut announcer = accept(listener.accept().unwrap().0).unwrap();
+    let message = announcer.read().unwrap().into_text().unwrap();
+    assert_eq!(message.parse::<TwinkleNotifyMessage>().unwrap(), TwinkleNotifyMessage::Announce(channel, head));
+
+    while announcer.read().is_ok() {}
+    assert!(announce.join().unwrap());
+
+    repo.set_notify_url("https://notify.example.org").unwrap();
+    assert!(twinkle_channel_announce(&repo).is_err());
+
+    _ = fs::remove_dir_all(fixture_path("channel"));
+}
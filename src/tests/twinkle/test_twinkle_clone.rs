@@ -0,0 +1,52 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::fs;
+use std::path::Path;
+
+use crate::git::objects::environment::GitEnvironment;
+use crate::git::objects::remote_url::GitRemoteUrl;
+
+use crate::twinkle::defaults::common::COMMON_FIRST_FILE;
+use crate::twinkle::twinkle_clone::{ twinkle_clone_complete, twinkle_clone_prepare_keys, twinkle_clone_start };
+use crate::twinkle::twinkle_init::twinkle_init;
+
+
+#[test]
+fn test_twinkle_clone_local() {
+    let path = Path::new("/tmp/twinkle_tests_clone");
+
+    if path.exists() {
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    fs::create_dir_all(path.join("seed")).unwrap();
+    fs::create_dir_all(path.join("clones")).unwrap();
+
+    GitEnvironment::new(path)
+        .run("init", &["--bare", "--quiet", "--initial-branch=main", "remote.git"])
+        .unwrap();
+
+    let remote_url = "file:///tmp/twinkle_tests_clone/remote.git"
+        .parse::<GitRemoteUrl>().unwrap();
+
+    let seed = twinkle_init(&path.join("seed"), &remote_url, None).unwrap();
+    seed.git.run("push", &["--no-verify", "--quiet", "origin", "main"]).unwrap(); // Skip the LFS hook
+
+
+    let key_pair = twinkle_clone_prepare_keys(&remote_url, &path.join("keys"), &path.join("forges"));
+    assert!(key_pair.unwrap().is_none());
+    assert!(!path.join("keys").exists());
+
+    let mut repo = twinkle_clone_start(&remote_url, None, &path.join("clones"), Some(1)).unwrap();
+    twinkle_clone_complete(&mut repo, None).unwrap();
+
+    assert_eq!(repo.git.working_dir, path.join("clones/remote"));
+    assert!(repo.git.working_dir.join(COMMON_FIRST_FILE).exists());
+    assert_eq!(repo.id(), seed.id());
+    assert_eq!(repo.remote_url().unwrap().to_string(), remote_url.to_string());
+}
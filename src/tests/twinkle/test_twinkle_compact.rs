@@ -0,0 +1,246 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::fs;
+use std::path::Path;
+
+use chrono::{ DateTime, Duration, Utc };
+
+use crate::git::objects::commit::GitCommit;
+use crate::git::objects::environment::GitEnvironment;
+
+use crate::twinkle::defaults::common::{ COMMON_CONFIG_FILE, COMMON_FIRST_FILE };
+use crate::twinkle::defaults::config::{ K_NEXT_BRANCH, K_NEXT_COMMIT, key };
+use crate::twinkle::objects::repository::TwinkleRepository;
+use crate::twinkle::twinkle_sync::{ twinkle_has_unpushed_commits, twinkle_sync_down };
+
+use crate::twinkle::twinkle_compact::{
+    twinkle_compact,
+    twinkle_compact_branch_name,
+    twinkle_compact_follow,
+    twinkle_compact_generation,
+    twinkle_compact_next,
+    twinkle_compact_snapshots,
+};
+
+use super::fixtures::{
+    fixture_clone,
+    fixture_commit_file_at,
+    fixture_init,
+    fixture_path,
+    fixture_push,
+    fixture_seed,
+};
+
+
+const DAY: i64 = 24 * 60 * 60;
+
+
+fn commit_at(tree: &str, timestamp: i64) -> GitCommit {
+    GitCommit {
+        tree: tree.into(),
+        timestamp: DateTime::from_timestamp(timestamp, 0).unwrap(),
+        ..Default::default()
+    }
+}
+
+
+#[test]
+fn test_twinkle_compact_snapshots() {
+    let cutoff = DateTime::from_timestamp(100 * DAY, 0).unwrap();
+
+    let history = vec![
+        commit_at("a", 10 * DAY),
+        commit_at("b", 11 * DAY),
+        commit_at("c", 12 * DAY),
+        commit_at("d", 31 * DAY), // Next period
+        commit_at("e", 45 * DAY),
+        commit_at("f", 120 * DAY), // Clock was ahead
+        commit_at("g", 62 * DAY),
+        commit_at("h", 101 * DAY), // Recent
+        commit_at("i", 102 * DAY),
+    ];
+
+    let (snapshots, count) = twinkle_compact_snapshots(&history, cutoff, 30);
+    assert_eq!(count, 7);
+
+    let trees: Vec<&str> = snapshots.iter().map(|snapshot| snapshot.tree.as_str()).collect();
+    let counts: Vec<usize> = snapshots.iter().map(|snapshot| snapshot.count).collect();
+
+    assert_eq!(trees, vec!["c", "e", "f", "g"]);
+    assert_eq!(counts, vec![3, 2, 1, 1]);
+    assert_eq!(snapshots[0].from, history[0].timestamp);
+    assert_eq!(snapshots[0].to, history[2].timestamp);
+
+    // Nothing old
+    let (snapshots, count) = twinkle_compact_snapshots(&history[7..], cutoff, 30);
+    assert!(snapshots.is_empty());
+    assert_eq!(count, 0);
+
+    // Everything old
+    let (snapshots, count) = twinkle_compact_snapshots(&history[..3], cutoff, 0);
+    assert_eq!(snapshots.len(), 3); // Periods of at least a day
+    assert_eq!(count, 3);
+}
+
+
+#[test]
+fn test_twinkle_compact_branch_name() {
+    assert_eq!(twinkle_compact_branch_name("main", 1), "main-2");
+    assert_eq!(twinkle_compact_branch_name("main-2", 2), "main-3");
+    assert_eq!(twinkle_compact_branch_name("release-1", 1), "release-1-2");
+    assert_eq!(twinkle_compact_branch_name("main-7", 2), "main-7-3");
+}
+
+
+fn read(repo: &TwinkleRepository, file: &str) -> String {
+    fs::read_to_string(repo.abs_path(Path::new(file))).unwrap()
+}
+
+
+#[test]
+fn test_twinkle_compact() {
+    let path = fixture_path("compact");
+    let remote = GitEnvironment::new(&path.join("remote.git"));
+
+    let seed = fixture_seed("compact");
+    seed.set_compact_days(90).unwrap();
+    seed.set_compact_period(30).unwrap();
+
+    // A year old, in two periods, then two recent changes
+    let period = 30 * DAY;
+    let old = (Utc::now() - Duration::days(400)).timestamp().div_euclid(period) * period;
+
+    fixture_commit_file_at(&seed, "notes.md", "1", old + DAY);
+    fixture_commit_file_at(&seed, "notes.md", "2", old + 2 * DAY);
+    fixture_commit_file_at(&seed, "todo.md", "1", old + 3 * DAY);
+    fixture_commit_file_at(&seed, "notes.md", "3", old + period + DAY);
+    fixture_commit_file_at(&seed, "todo.md", "2", old + period + 2 * DAY);
+
+    let recent = (Utc::now() - Duration::days(5)).timestamp();
+    fixture_commit_file_at(&seed, "notes.md", "4", recent);
+    fixture_commit_file_at(&seed, "todo.md", "3", recent + DAY);
+
+    fixture_push(&seed);
+    _ = fs::remove_file(seed.abs_path(Path::new(".git/hooks/pre-push"))); // Needs git-lfs
+
+    // Cloned before compacting
+    let mut other = fixture_clone("compact", "clones", Some(1));
+    let other_head = other.current_head().unwrap();
+    fs::write(other.abs_path(Path::new("todo.md")), "Mine").unwrap();
+    fs::write(other.abs_path(Path::new("new.md")), "Mine").unwrap();
+
+    let behind = fixture_clone("compact", "others", Some(1));
+    let mut full = fixture_clone("compact", "full", None);
+
+    // Refuses with changes that aren't synced yet
+    fs::write(seed.abs_path(Path::new("draft.md")), "Draft").unwrap();
+    assert!(twinkle_compact(&seed).is_err());
+    fs::remove_file(seed.abs_path(Path::new("draft.md"))).unwrap();
+
+    let old_history = seed.git.log_first_parent().unwrap();
+    assert_eq!(old_history.len(), 8);
+
+    assert_eq!(twinkle_compact(&seed).unwrap(), Some("main-2".into()));
+    assert_eq!(seed.branch().unwrap(), "main-2");
+    assert_eq!(twinkle_compact_generation(&seed), 2);
+
+    // "Set up Twinkle" is newer, but comes before old commits
+    let history = seed.git.log_first_parent().unwrap();
+    let titles: Vec<&str> = history.iter().map(|commit| commit.message.title.as_str()).collect();
+
+    assert_eq!(history.len(), 6);
+    assert!(titles[0].starts_with("Compacted 1 changes"));
+    assert!(titles[1].starts_with("Compacted 3 changes"));
+    assert!(titles[2].starts_with("Compacted 2 changes"));
+    assert_eq!(titles[3..], ["~ \"notes.md\"", "~ \"todo.md\"", "Start generation 2"]);
+
+    assert_eq!(history[2].tree, old_history[5].tree);
+    assert_eq!(history[4].tree, old_history[7].tree);
+    assert_eq!(history[4].timestamp, old_history[7].timestamp);
+    assert_eq!(history[4].author.email(), old_history[7].author.email());
+
+    assert_eq!(read(&seed, "notes.md"), "4");
+    assert_eq!(read(&seed, "todo.md"), "3");
+    assert!(seed.git.status_all().unwrap().is_empty());
+    assert!(seed.git.rev_parse_verify("main").is_err()); // Old history can be cleaned up
+
+    // Both branches are on the remote, the old one points to the new one
+    assert_eq!(remote.rev_parse_verify("main-2").unwrap(), seed.current_head().unwrap());
+
+    let config = format!("main:{COMMON_CONFIG_FILE}");
+    assert_eq!(remote.config_blob_get(&config, &key(K_NEXT_BRANCH)).unwrap().stdout, "main-2");
+    assert_eq!(remote.config_blob_get(&config, &key(K_NEXT_COMMIT)).unwrap().stdout, seed.current_head().unwrap());
+
+    // Nothing left to compact
+    assert_eq!(twinkle_compact(&seed).unwrap(), None);
+
+    // Moves over on the next fetch, and keeps the changes that aren't synced yet
+    twinkle_sync_down(&mut other).unwrap();
+
+    assert_eq!(other.branch().unwrap(), "main-2");
+    assert_eq!(other.current_head().unwrap(), seed.current_head().unwrap());
+    assert_eq!(other.remote(&"main-2".into()), "origin");
+    assert!(other.git.rev_parse_verify(&other_head).is_ok());
+    assert!(other.git.rev_parse_verify("main").is_err());
+    assert!(!twinkle_has_unpushed_commits(&other));
+
+    assert_eq!(read(&other, "todo.md"), "Mine");
+    assert_eq!(read(&other, "new.md"), "Mine");
+    assert!(twinkle_compact_next(&other).is_none());
+
+    // Tracks only the new branch, unless all of them were tracked already
+    let fetch_key = "remote.origin.fetch";
+    assert_eq!(other.git.config_get_all(fetch_key), ["+refs/heads/main-2:refs/remotes/origin/main-2"]);
+
+    twinkle_sync_down(&mut full).unwrap();
+    assert_eq!(full.branch().unwrap(), "main-2");
+    assert_eq!(full.git.config_get_all(fetch_key), ["+refs/heads/*:refs/remotes/origin/*"]);
+
+    // Committed before moving over, but not pushed
+    fixture_commit_file_at(&behind, "later.md", "Later", Utc::now().timestamp());
+
+    behind.git.fetch(&"origin".into(), &"main".into()).unwrap();
+    behind.git.run("merge", &["--quiet", "--no-edit", "FETCH_HEAD"]).unwrap(); // Without signing
+    twinkle_compact_follow(&behind).unwrap();
+
+    assert_eq!(behind.branch().unwrap(), "main-2");
+    assert!(behind.has_local_changes());
+    assert!(twinkle_has_unpushed_commits(&behind));
+    assert_eq!(behind.git.rev_parse_verify("HEAD^").unwrap(), seed.current_head().unwrap());
+    assert_eq!(read(&behind, "later.md"), "Later");
+    assert!(behind.git.status().unwrap().is_empty());
+
+    behind.git.run("push", &["--no-verify", "--quiet", "origin", "main-2"]).unwrap();
+
+    let mut seed = seed;
+    twinkle_sync_down(&mut seed).unwrap();
+    assert_eq!(read(&seed, "later.md"), "Later");
+
+    // New clones start on the new branch
+    fs::remove_dir_all(path.join("others")).unwrap();
+
+    let mut new = fixture_clone("compact", "others", Some(1));
+    assert_eq!(new.branch().unwrap(), "main-2");
+
+    twinkle_sync_down(&mut new).unwrap();
+    assert_eq!(new.current_head().unwrap(), seed.current_head().unwrap());
+    assert_eq!(read(&new, COMMON_FIRST_FILE), read(&seed, COMMON_FIRST_FILE));
+
+    // Never moves to another repository's branch
+    let unrelated = fixture_init("compact", "unrelated");
+    unrelated.git.run("push", &["--no-verify", "--quiet", "origin", "main:unrelated"]).unwrap();
+
+    let config_file = Path::new(COMMON_CONFIG_FILE);
+    new.git.config_file_set(config_file, &key(K_NEXT_BRANCH), "unrelated").unwrap();
+    new.git.config_file_set(config_file, &key(K_NEXT_COMMIT), &unrelated.current_head().unwrap()).unwrap();
+
+    assert!(twinkle_compact_follow(&new).is_err());
+    assert_eq!(new.branch().unwrap(), "main-2");
+
+    _ = fs::remove_dir_all(path);
+}
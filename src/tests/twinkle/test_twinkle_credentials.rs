@@ -0,0 +1,65 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+use crate::git::objects::https_url::HttpsUrl;
+use crate::twinkle::twinkle_credentials::*;
+
+
+#[test]
+fn test_twinkle_credentials() {
+    let file = Path::new("/tmp/twinkle_tests_credentials/credentials");
+    _ = fs::remove_dir_all(file.parent().unwrap());
+
+    assert!(twinkle_credential_get(file, "git.example.com").is_none());
+
+    let credential = TwinkleCredential {
+        host: "git.example.com:8443".into(),
+        username: "hbons".into(),
+        token: "glpat-secret".into(),
+    };
+
+    twinkle_credential_set(file, &credential).unwrap();
+
+    let mode = fs::metadata(file).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    let stored = twinkle_credential_get(file, "git.example.com:8443").unwrap();
+    assert_eq!(stored.username, "hbons");
+    assert_eq!(stored.token, "glpat-secret");
+    assert!(twinkle_credential_get(file, "git.example.com").is_none());
+
+
+    let response = twinkle_credential_fill(file, "protocol=https\nhost=git.example.com:8443\n");
+    assert_eq!(response.unwrap(), "username=hbons\npassword=glpat-secret\n");
+
+    let response = twinkle_credential_fill(file, "protocol=http\nhost=git.example.com:8443\n");
+    assert!(response.is_none());
+
+    let response = twinkle_credential_fill(file, "protocol=https\nhost=example.com\n");
+    assert!(response.is_none());
+
+
+    twinkle_credential_delete(file, "git.example.com:8443").unwrap();
+    assert!(twinkle_credential_get(file, "git.example.com:8443").is_none());
+}
+
+
+#[test]
+fn test_twinkle_credential_error() {
+    let url = "https://git.example.com/hbons/Twinkle.git".parse::<HttpsUrl>().unwrap();
+
+    let error = twinkle_credential_error(&url,
+        "fatal: could not read Username for 'https://git.example.com': terminal prompts disabled".into());
+    assert!(error.to_string().contains("twinkle credential set https://git.example.com"));
+
+    let error = twinkle_credential_error(&url, "fatal: repository not found".into());
+    assert_eq!(error.to_string(), "fatal: repository not found");
+}
@@ -5,7 +5,7 @@
 //   under the terms of the GNU General Public License v3 or any later version.
 
 
-use crate::ssh::objects::url::SshUrl;
+use crate::git::objects::remote_url::GitRemoteUrl;
 
 use crate::twinkle::defaults::info::twinkle_default_info_attributes;
 use crate::twinkle::defaults::info::twinkle_default_info_exclude;
@@ -36,9 +36,9 @@ fn test_twinkle_default_attribute_rules() {
 
 #[test]
 fn test_twinkle_default_welcome() {
-    let url = "git@github.com:hbons/Twinkle".parse::<SshUrl>().unwrap();
+    let url = "git@github.com:hbons/Twinkle".parse::<GitRemoteUrl>().unwrap();
     let message = init_welcome(&url);
 
-    assert!(message.contains(url.original.as_str()));
-    assert!(message.len() > url.original.len());
+    assert!(message.contains(url.original().as_str()));
+    assert!(message.len() > url.original().len());
 }
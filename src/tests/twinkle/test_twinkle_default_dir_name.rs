@@ -7,20 +7,26 @@
 
 use std::path::Path;
 
-use crate::ssh::objects::url::SshUrl;
+use crate::git::objects::remote_url::GitRemoteUrl;
 use crate::twinkle::twinkle_util::twinkle_default_dir_name;
 
 
 #[test]
 fn test_twinkle_default_dir_name() {
-    let url = "ssh://git@github.com/hbons/Twinkle".parse::<SshUrl>().unwrap();
+    let url = "ssh://git@github.com/hbons/Twinkle".parse::<GitRemoteUrl>().unwrap();
     let result = twinkle_default_dir_name(&url);
 
     assert_eq!(result.unwrap(), Path::new("Twinkle").to_path_buf());
 
 
-    let url = "ssh://git@github.com/hbons".parse::<SshUrl>().unwrap();
+    let url = "ssh://git@github.com/hbons".parse::<GitRemoteUrl>().unwrap();
     let result = twinkle_default_dir_name(&url);
 
     assert_eq!(result.unwrap(), Path::new("hbons").to_path_buf());
+
+
+    let url = "file:///srv/shared/Twinkle.git".parse::<GitRemoteUrl>().unwrap();
+    let result = twinkle_default_dir_name(&url);
+
+    assert_eq!(result.unwrap(), Path::new("Twinkle").to_path_buf());
 }
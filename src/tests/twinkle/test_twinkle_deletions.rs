@@ -0,0 +1,107 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::twinkle::objects::repository::TwinkleRepository;
+use crate::twinkle::twinkle_sync::twinkle_sync_down;
+
+use crate::twinkle::twinkle_deletions::{
+    twinkle_deletions_check,
+    twinkle_deletions_confirm,
+    twinkle_deletions_exceeded,
+    twinkle_deletions_pause,
+    twinkle_deletions_undo,
+};
+
+use super::fixtures::{ fixture_path, fixture_seed };
+
+
+fn delete_files(repo: &TwinkleRepository, files: Range<usize>) {
+    for i in files {
+        let file = format!("{i}.txt");
+        fs::remove_file(repo.abs_path(Path::new(&file))).unwrap();
+        repo.git.add(Path::new(&file)).unwrap();
+    }
+}
+
+
+#[test]
+fn test_twinkle_deletions_exceeded() {
+    assert!(!twinkle_deletions_exceeded(0, 0, 500, 50));
+    assert!(!twinkle_deletions_exceeded(500, 10000, 500, 50));
+    assert!(twinkle_deletions_exceeded(501, 10000, 500, 50));
+
+    assert!(twinkle_deletions_exceeded(51, 100, 500, 50));
+    assert!(!twinkle_deletions_exceeded(50, 100, 500, 50));
+    assert!(!twinkle_deletions_exceeded(9, 10, 500, 50)); // Too few to tell
+
+    // Turned off
+    assert!(!twinkle_deletions_exceeded(10000, 10000, 0, 0));
+    assert!(twinkle_deletions_exceeded(10000, 10000, 0, 50));
+}
+
+
+#[test]
+fn test_twinkle_deletions() {
+    let mut repo = fixture_seed("deletions");
+
+    for i in 0..20 {
+        fs::write(repo.abs_path(Path::new(&format!("{i}.txt"))), i.to_string()).unwrap();
+    }
+
+    repo.git.run("add", &["--all"]).unwrap();
+    repo.git.commit(repo.user(), "+20").unwrap();
+
+    // Either limit
+    repo.set_deletions_max_files(5).unwrap();
+    repo.set_deletions_max_percent(0).unwrap();
+
+    delete_files(&repo, 0..6);
+    assert!(twinkle_deletions_check(&repo, &repo.git.status().unwrap()).unwrap());
+
+    repo.set_deletions_max_files(0).unwrap();
+    repo.set_deletions_max_percent(50).unwrap();
+    assert!(!twinkle_deletions_check(&repo, &repo.git.status().unwrap()).unwrap());
+
+    delete_files(&repo, 6..12);
+    assert!(twinkle_deletions_check(&repo, &repo.git.status().unwrap()).unwrap());
+
+    // Stays local
+    repo.git.commit(repo.user(), "−12").unwrap();
+    let head = repo.current_head().unwrap();
+
+    twinkle_deletions_pause(&repo, &head).unwrap();
+    assert_eq!(repo.deletions_paused(), Some(head.clone()));
+
+    twinkle_sync_down(&mut repo).unwrap(); // Would fail to fetch, as the remote is empty
+
+    // Files are back
+    repo.git.run("checkout", &["--quiet", "HEAD^", "--", "."]).unwrap();
+    repo.git.run("reset", &["--quiet"]).unwrap();
+
+    assert_eq!(twinkle_deletions_undo(&repo).unwrap(), head);
+    assert!(repo.deletions_paused().is_none());
+    assert!(repo.git.status().unwrap().is_empty());
+    assert_ne!(repo.current_head().unwrap(), head);
+
+    // Meant it
+    delete_files(&repo, 0..12);
+    repo.git.commit(repo.user(), "−12").unwrap();
+    let head = repo.current_head().unwrap();
+
+    twinkle_deletions_pause(&repo, &head).unwrap();
+    assert_eq!(twinkle_deletions_confirm(&repo).unwrap(), head);
+
+    assert!(repo.deletions_paused().is_none());
+    assert_eq!(repo.current_head().unwrap(), head);
+    assert!(twinkle_deletions_confirm(&repo).is_err());
+
+    _ = fs::remove_dir_all(fixture_path("deletions"));
+}
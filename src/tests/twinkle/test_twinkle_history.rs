@@ -0,0 +1,68 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::fs;
+
+use crate::twinkle::twinkle_history::{ twinkle_history_deepen, twinkle_history_fetch };
+
+use super::fixtures::{ fixture_clone, fixture_commit_file, fixture_path, fixture_push, fixture_seed };
+
+
+#[test]
+fn test_twinkle_history() {
+    let seed = fixture_seed("history");
+
+    for i in 1..=5 {
+        fixture_commit_file(&seed, "notes.md", &i.to_string());
+    }
+
+    fixture_push(&seed);
+
+    // Depth
+    let recent = fixture_clone("history", "recent", Some(1));
+    assert!(recent.git.rev_parse_is_shallow());
+    assert_eq!(recent.git.log_first_parent().unwrap().len(), 1);
+
+    let some = fixture_clone("history", "some", Some(3));
+    assert_eq!(some.git.log_first_parent().unwrap().len(), 3);
+
+    let full = fixture_clone("history", "full", None);
+    assert!(!full.git.rev_parse_is_shallow());
+    assert_eq!(full.git.log_first_parent().unwrap().len(), 6);
+    assert!(!twinkle_history_fetch(&full).unwrap());
+
+    // On demand
+    assert!(twinkle_history_fetch(&recent).unwrap());
+    assert!(!recent.git.rev_parse_is_shallow());
+    assert_eq!(recent.git.log_first_parent().unwrap().len(), 6);
+
+    // The remote's history diverged from before the one commit that's here
+    let shallow = fixture_clone("history", "shallow", Some(1));
+
+    seed.git.run("reset", &["--hard", "--quiet", "HEAD~2"]).unwrap();
+    fixture_commit_file(&seed, "todo.md", "1");
+    seed.git.run("push", &["--no-verify", "--quiet", "--force", "origin", "main"]).unwrap();
+
+    let (remote, branch) = ("origin".to_string(), "main".to_string());
+    shallow.git.fetch(&remote, &branch).unwrap();
+    assert!(shallow.git.merge_base_common("HEAD", "FETCH_HEAD").is_none());
+
+    twinkle_history_deepen(&shallow, &remote, &branch).unwrap();
+
+    let base = shallow.git.merge_base_common("HEAD", "FETCH_HEAD").unwrap();
+    assert_eq!(base, seed.git.rev_parse_verify("HEAD^").unwrap());
+    assert!(!shallow.git.rev_parse_is_shallow());
+
+    // Goes back far enough already
+    some.git.fetch(&remote, &branch).unwrap();
+    twinkle_history_deepen(&some, &remote, &branch).unwrap();
+
+    assert!(some.git.merge_base_common("HEAD", "FETCH_HEAD").is_some());
+    assert!(some.git.rev_parse_is_shallow());
+
+    _ = fs::remove_dir_all(fixture_path("history"));
+}
@@ -9,7 +9,7 @@ use std::fs;
 use std::path::Path;
 
 use crate::git::objects::environment::GitEnvironment;
-use crate::ssh::objects::url::SshUrl;
+use crate::git::objects::remote_url::GitRemoteUrl;
 
 use crate::twinkle::defaults::config::twinkle_default_git_settings;
 use crate::twinkle::defaults::info::twinkle_default_info_attributes;
@@ -21,7 +21,7 @@ use crate::twinkle::twinkle_init::twinkle_init;
 fn test_twinkle_init() {
     let path = Path::new("/tmp/twinkle_tests");
     let remote_url = "git@github.com:hbons/SparkleShare"
-        .parse::<SshUrl>().unwrap();
+        .parse::<GitRemoteUrl>().unwrap();
 
     if path.exists() {
         fs::remove_dir_all(path).unwrap();
@@ -38,7 +38,7 @@ fn test_twinkle_init() {
 
     assert_eq!(git.config_get("twinkle.enabled").unwrap().stdout, "true");
     assert!(git.config_get("twinkle.id").is_some());
-    assert_eq!(git.config_get("remote.origin.url").unwrap().stdout, remote_url.to_string_standard());
+    assert_eq!(git.config_get("remote.origin.url").unwrap().stdout, remote_url.to_string());
 
     for rule in twinkle_default_git_settings() {
         assert_eq!(git.config_get(rule.0).unwrap().stdout, rule.1);
@@ -52,7 +52,7 @@ fn test_twinkle_init() {
 
     assert_eq!(
         twinkle_default_info_attributes().len(),
-        fs::read_to_string(&path).unwrap().lines().count(),
+        fs::read_to_string(path).unwrap().lines().count(),
     );
 
 
@@ -60,7 +60,7 @@ fn test_twinkle_init() {
 
     assert_eq!(
         twinkle_default_info_exclude().len(),
-        fs::read_to_string(&path).unwrap().lines().count(),
+        fs::read_to_string(path).unwrap().lines().count(),
     );
 
 
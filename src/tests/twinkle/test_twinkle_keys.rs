@@ -0,0 +1,352 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::git::objects::environment::GitEnvironment;
+use crate::git::objects::remote_url::GitRemoteUrl;
+use crate::ssh::agent::SshAgent;
+use crate::ssh::keygen::ssh_keygen;
+use crate::ssh::keys::fingerprint::Fingerprint;
+use crate::ssh::keys::host_key::HostKey;
+use crate::ssh::keys::key_type::KeyType;
+use crate::ssh::objects::forge::KnownForges;
+use crate::ssh::objects::url::SshUrl;
+
+use crate::twinkle::objects::repository::TwinkleRepository;
+use crate::twinkle::twinkle_util::twinkle_ssh_command;
+use crate::twinkle::twinkle_keys::{
+    twinkle_keypair_certificate_error,
+    twinkle_keypair_delete,
+    twinkle_keypair_find,
+    twinkle_keypair_for,
+    twinkle_keypair_from_agent,
+    twinkle_keypair_load,
+    twinkle_keypair_list,
+    twinkle_keypair_renew,
+    twinkle_keypair_repos,
+    twinkle_keypair_set_certificate,
+    twinkle_keytype_for,
+    twinkle_hostkey_changed,
+    twinkle_hostkey_for,
+    twinkle_hostkey_is_stored,
+    twinkle_hostkey_negotiate,
+    twinkle_hostkey_set_changed,
+    twinkle_hostkey_trust,
+};
+
+
+#[test]
+fn test_twinkle_keypair_list() {
+    let path = Path::new("/tmp/twinkle_tests_keys");
+    let keys_dir = path.join("keys");
+
+    if path.exists() {
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    assert!(twinkle_keypair_list(&keys_dir).unwrap().is_empty());
+
+    twinkle_keypair_for(&"gitlab.com".into(), KeyType::ED25519, &keys_dir).unwrap();
+    twinkle_keypair_for(&"github.com".into(), KeyType::ED25519, &keys_dir).unwrap();
+    fs::write(keys_dir.join("github.com.ed25519.key.host"), "").unwrap(); // Not a key pair
+
+    let key_pairs = twinkle_keypair_list(&keys_dir).unwrap();
+    let hosts: Vec<&str> = key_pairs.iter().map(|(host, _)| host.as_str()).collect();
+    assert_eq!(hosts, vec!["github.com", "gitlab.com"]);
+
+    let key_pair = twinkle_keypair_find("github.com", &keys_dir).unwrap().unwrap();
+    assert_eq!(key_pair.key_type, KeyType::ED25519);
+    assert!(twinkle_keypair_find("codeberg.org", &keys_dir).unwrap().is_none());
+
+    twinkle_keypair_delete(&key_pair).unwrap();
+    assert!(twinkle_keypair_find("github.com", &keys_dir).unwrap().is_none());
+
+    _ = fs::remove_dir_all(path);
+}
+
+
+#[test]
+fn test_twinkle_keypair_repos() {
+    let path = Path::new("/tmp/twinkle_tests_keys_repos");
+
+    if path.exists() {
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    let remotes = [
+        ("notes", "git@github.com:hbons/notes"),
+        ("site", "ssh://git@github.com:22/hbons/site"),
+        ("other", "git@codeberg.org:hbons/other"),
+        ("backup", "file:///srv/backup.git"),
+    ];
+
+    for (name, remote_url) in remotes {
+        fs::create_dir_all(path.join(name)).unwrap();
+        GitEnvironment::new(&path.join(name)).run("init", &["--quiet"]).unwrap();
+
+        TwinkleRepository::new(&path.join(name))
+            .set_remote_url("origin", &remote_url.parse::<GitRemoteUrl>().unwrap())
+            .unwrap();
+    }
+
+    let paths = twinkle_keypair_repos("github.com", path);
+    assert_eq!(paths, vec![path.join("notes"), path.join("site")]);
+
+    assert!(twinkle_keypair_repos("gitlab.com", path).is_empty());
+    assert!(twinkle_keypair_repos("github.com", &path.join("missing")).is_empty());
+
+    _ = fs::remove_dir_all(path);
+}
+
+
+#[test]
+fn test_twinkle_keypair_key_types() {
+    let keys_dir = Path::new("/tmp/twinkle_tests_keys_types");
+
+    if keys_dir.exists() {
+        fs::remove_dir_all(keys_dir).unwrap();
+    }
+
+    let key_pair = twinkle_keypair_for(&"ecdsa.example.com".into(), KeyType::ECDSA, keys_dir).unwrap();
+    assert_eq!(key_pair.key_type, KeyType::ECDSA);
+    assert!(key_pair.public_key.starts_with("ecdsa-sha2-nistp256 "));
+
+    let key_pair = twinkle_keypair_for(&"rsa.example.com".into(), KeyType::RSA, keys_dir).unwrap();
+    assert_eq!(key_pair.key_type, KeyType::RSA);
+    assert!(key_pair.public_key.starts_with("ssh-rsa "));
+
+    assert_eq!(twinkle_keytype_for("ecdsa.example.com", keys_dir), Some(KeyType::ECDSA));
+    assert_eq!(twinkle_keytype_for("rsa.example.com", keys_dir), Some(KeyType::RSA));
+    assert_eq!(twinkle_keytype_for("new.example.com", keys_dir), None);
+
+    _ = fs::remove_dir_all(keys_dir);
+}
+
+
+#[test]
+fn test_twinkle_hostkey_negotiate_stored() {
+    let keys_dir = Path::new("/tmp/twinkle_tests_keys_negotiate");
+
+    if keys_dir.exists() {
+        fs::remove_dir_all(keys_dir).unwrap();
+    }
+
+    fs::create_dir_all(keys_dir).unwrap();
+
+    // A host that was set up with RSA keeps using RSA, without a new scan
+    let public_key = "AAAAB3NzaC1yc2EAAAADAQABAAABAQCsj2bNKTBSpIYDEGk9KxsGh3mySTRgMtXL583qmBpzeQ";
+    fs::write(
+        keys_dir.join("git.example.com.rsa.key.host"),
+        format!("git.example.com ssh-rsa {public_key}"),
+    ).unwrap();
+
+    let url = "git@git.example.com:hbons/Twinkle".parse::<SshUrl>().unwrap();
+    let host_key = twinkle_hostkey_negotiate(&url, keys_dir, &keys_dir.join("forges")).unwrap();
+
+    assert_eq!(host_key.key_type, KeyType::RSA);
+    assert_eq!(host_key.public_key, public_key);
+    assert!(host_key.is_trusted);
+
+    _ = fs::remove_dir_all(keys_dir);
+}
+
+
+#[test]
+fn test_twinkle_keypair_load() {
+    let path = Path::new("/tmp/twinkle_tests_keys_load");
+    let keys_dir = path.join("keys");
+
+    if path.exists() {
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    fs::create_dir_all(path).unwrap();
+
+    // Keys without a passphrase don't need an agent
+    let key_pair = twinkle_keypair_for(&"github.com".into(), KeyType::ED25519, &keys_dir).unwrap();
+    assert!(!twinkle_keypair_load(&key_pair, None).unwrap());
+
+    let key_pair = ssh_keygen(&path.join("encrypted.key"), KeyType::ED25519, None, Some("secret")).unwrap();
+    assert!(twinkle_keypair_load(&key_pair, None).is_err());
+
+    // Agent identities
+    let agent = SshAgent::start(&path.join("agent.sock")).unwrap();
+    let identity = ssh_keygen(&path.join("identity.key"), KeyType::ED25519, None, None).unwrap();
+
+    assert!(twinkle_keypair_from_agent("github.com", &identity.public_key, &keys_dir).is_err()); // Already has a key
+
+    let key_pair = twinkle_keypair_from_agent("gitlab.com", &identity.public_key, &keys_dir).unwrap();
+    assert!(key_pair.is_agent_identity());
+    assert_eq!(key_pair.public_key, identity.public_key);
+    assert_eq!(key_pair.private_key_path, keys_dir.join("gitlab.com.ed25519.key"));
+
+    assert!(twinkle_keypair_load(&key_pair, None).is_err());
+    assert!(twinkle_keypair_load(&key_pair, Some(&agent)).is_err()); // Not loaded yet
+
+    agent.add(&identity).unwrap();
+    assert!(!twinkle_keypair_load(&key_pair, Some(&agent)).unwrap());
+
+    drop(agent);
+    _ = fs::remove_dir_all(path);
+}
+
+
+#[test]
+fn test_twinkle_keypair_security_key() {
+    let keys_dir = Path::new("/tmp/twinkle_tests_keys_security_key");
+
+    if keys_dir.exists() {
+        fs::remove_dir_all(keys_dir).unwrap();
+    }
+
+    let public_key = "sk-ssh-ed25519@openssh.com AAAAGnNrLXNzaC1lZDI1NTE5QG9wZW5zc2guY29tAAAAIAABAgMEBQYHCAkKCwwNDg8QERITFBUWFxgZGhscHR4fAAAABHNzaDo= alice@yubikey";
+
+    let key_pair = twinkle_keypair_from_agent("github.com", public_key, keys_dir).unwrap();
+    assert_eq!(key_pair.key_type, KeyType::ED25519SK);
+    assert_eq!(key_pair.private_key_path, keys_dir.join("github.com.ed25519-sk.key"));
+    assert!(key_pair.is_agent_identity());
+
+    // Pairs with the server's Ed25519 host key, without generating a new key
+    assert_eq!(twinkle_keytype_for("github.com", keys_dir), Some(KeyType::ED25519));
+
+    let found = twinkle_keypair_for(&"github.com".into(), KeyType::ED25519, keys_dir).unwrap();
+    assert_eq!(found.key_type, KeyType::ED25519SK);
+    assert!(!keys_dir.join("github.com.ed25519.key").exists());
+
+    assert_eq!(twinkle_keypair_list(keys_dir).unwrap().len(), 1);
+    assert!(twinkle_keypair_renew(&"github.com".into(), KeyType::ED25519SK, keys_dir).is_err());
+    assert!(keys_dir.join("github.com.ed25519-sk.key").exists());
+
+    _ = fs::remove_dir_all(keys_dir);
+}
+
+
+#[test]
+fn test_twinkle_hostkey_changed() {
+    let keys_dir = Path::new("/tmp/twinkle_tests_keys_changed");
+
+    if keys_dir.exists() {
+        fs::remove_dir_all(keys_dir).unwrap();
+    }
+
+    fs::create_dir_all(keys_dir).unwrap();
+
+    let url = "git@github.com:hbons/Twinkle".parse::<SshUrl>().unwrap();
+    let host_key = HostKey::for_known_host(&url, KeyType::ED25519, KnownForges::builtin().get("github.com")).unwrap();
+    twinkle_hostkey_trust(&host_key, keys_dir).unwrap();
+
+    assert!(twinkle_hostkey_changed(&host_key, keys_dir).is_none());
+
+    let new = "SHA256:uNiVztksCsDhcc0u9e8BujQXVUpKZIDTMczCvj3tD2s".parse::<Fingerprint>().unwrap();
+    twinkle_hostkey_set_changed(&host_key, Some(&new), keys_dir).unwrap();
+    assert!(keys_dir.join("github.com.ed25519.key.host.changed").exists());
+
+    let change = twinkle_hostkey_changed(&host_key, keys_dir).unwrap();
+    assert_eq!(change.host, "github.com");
+    assert_eq!(change.old, host_key.fingerprint);
+    assert_eq!(change.new, Some(new));
+
+    // Trusting a key unblocks the host
+    twinkle_hostkey_trust(&host_key, keys_dir).unwrap();
+    assert!(twinkle_hostkey_changed(&host_key, keys_dir).is_none());
+
+    _ = fs::remove_dir_all(keys_dir);
+}
+
+
+#[test]
+fn test_twinkle_hostkey_trust_ports() {
+    let keys_dir = Path::new("/tmp/twinkle_tests_keys_ports");
+
+    if keys_dir.exists() {
+        fs::remove_dir_all(keys_dir).unwrap();
+    }
+
+    let url = "git@github.com:hbons/Twinkle".parse::<SshUrl>().unwrap();
+    let host_key = HostKey::for_known_host(&url, KeyType::ED25519, KnownForges::builtin().get("github.com")).unwrap();
+    twinkle_hostkey_trust(&host_key, keys_dir).unwrap();
+
+    let other_port = HostKey {
+        port: Some(2222),
+        public_key: "AAAAC3NzaC1lZDI1NTE5AAAAIAfuCHKVTjquxvt6CM6tdG4SLp1Btn/nOeHHE5UOzRdf".into(),
+        ..host_key.clone()
+    };
+
+    twinkle_hostkey_trust(&other_port, keys_dir).unwrap();
+    twinkle_hostkey_trust(&other_port, keys_dir).unwrap(); // Replaces, doesn't add
+
+    let content = fs::read_to_string(keys_dir.join("github.com.ed25519.key.host")).unwrap();
+    assert_eq!(content.lines().count(), 2);
+
+    assert!(twinkle_hostkey_is_stored(&host_key, keys_dir));
+    assert!(twinkle_hostkey_is_stored(&other_port, keys_dir));
+
+    let url_with_port = "ssh://git@github.com:2222/hbons/Twinkle".parse::<SshUrl>().unwrap();
+    let found = twinkle_hostkey_for(&url_with_port, KeyType::ED25519, keys_dir, &keys_dir.join("forges")).unwrap();
+    assert_eq!(found.public_key, other_port.public_key);
+
+    let found = twinkle_hostkey_for(&url, KeyType::ED25519, keys_dir, &keys_dir.join("forges")).unwrap();
+    assert_eq!(found.public_key, host_key.public_key);
+
+    _ = fs::remove_dir_all(keys_dir);
+}
+
+
+#[test]
+fn test_twinkle_keypair_certificate() {
+    let path = Path::new("/tmp/twinkle_tests_keys_certificate");
+    let keys_dir = path.join("keys");
+
+    if path.exists() {
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    let key_pair = twinkle_keypair_for(&"git.example.org".into(), KeyType::ED25519, &keys_dir).unwrap();
+    let other = twinkle_keypair_for(&"other.example.org".into(), KeyType::ED25519, &keys_dir).unwrap();
+    let ca = ssh_keygen(&path.join("ca.key"), KeyType::ED25519, None, None).unwrap();
+
+    let sign = |validity: &str| {
+        fs::copy(&key_pair.public_key_path, path.join("user.pub")).unwrap();
+
+        let status = Command::new("ssh-keygen")
+            .args(["-q", "-s"]).arg(&ca.private_key_path)
+            .args(["-I", "alice", "-n", "git", "-V", validity])
+            .arg(path.join("user.pub"))
+            .output()
+            .unwrap()
+            .status;
+
+        assert!(status.success());
+        path.join("user-cert.pub")
+    };
+
+    assert!(twinkle_keypair_set_certificate(&other, &sign("+1h")).is_err()); // Not its key
+
+    let key_pair = twinkle_keypair_set_certificate(&key_pair, &sign("+1h")).unwrap();
+    let certificate = key_pair.certificate.as_ref().unwrap();
+    assert_eq!(certificate.key_id, "alice");
+    assert_eq!(certificate.path, keys_dir.join("git.example.org.ed25519.key-cert.pub"));
+    assert!(twinkle_keypair_certificate_error(&key_pair).is_none());
+
+    let url = "git@git.example.org:project.git".parse::<SshUrl>().unwrap();
+    assert!(twinkle_ssh_command(Some(&key_pair), Some(&url))
+        .contains("-o CertificateFile=/tmp/twinkle_tests_keys_certificate/keys/git.example.org.ed25519.key-cert.pub"));
+
+    // Renewed in place
+    sign("20200101:20200102");
+    let key_pair = twinkle_keypair_find("git.example.org", &keys_dir).unwrap().unwrap();
+    let e = twinkle_keypair_certificate_error(&key_pair).unwrap();
+    assert!(e.to_string().starts_with("Authentication failed: SSH certificate \"alice\" expired on 2020-01-02"));
+
+    twinkle_keypair_delete(&key_pair).unwrap();
+    assert!(path.join("user-cert.pub").exists());
+
+    _ = fs::remove_dir_all(path);
+}
@@ -0,0 +1,65 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::fs;
+use std::net::UdpSocket;
+use std::thread;
+use std::time::{ Duration, Instant };
+
+use crate::twinkle::defaults::common::COMMON_LAN_ADDRESS;
+use crate::twinkle::twinkle_lan::{ twinkle_lan_address, twinkle_lan_announce, twinkle_lan_bind, twinkle_lan_watch };
+
+use super::fixtures::{ fixture_init, fixture_path, fixture_seed };
+
+
+#[test]
+fn test_twinkle_lan() {
+    let alice = fixture_seed("lan");
+    let bob = fixture_init("lan", "bob");
+    let carol = fixture_init("lan", "carol"); // Syncs something else
+
+    bob.set_id(&alice.id().unwrap()).unwrap();
+
+    assert!(!alice.lan_enabled());
+    assert_eq!(twinkle_lan_address(&alice).unwrap().to_string(), COMMON_LAN_ADDRESS);
+
+    // Loopback instead of multicast
+    let port = UdpSocket::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let address = format!("127.0.0.1:{port}");
+
+    for repo in [&alice, &bob, &carol] {
+        repo.set_lan_address(&address).unwrap();
+    }
+
+    let alice_c = alice.clone();
+    thread::spawn(move || { _ = twinkle_lan_watch(&alice_c); });
+    thread::sleep(Duration::from_millis(200));
+
+    // More than one repository can listen, though only multicast and
+    // broadcast reach them all
+    let other = twinkle_lan_bind(&address.parse().unwrap());
+    assert!(other.is_ok());
+    drop(other);
+
+    twinkle_lan_announce(&alice).unwrap(); // Our own
+    twinkle_lan_announce(&carol).unwrap();
+    thread::sleep(Duration::from_millis(200));
+    assert!(!alice.has_remote_changes());
+
+    twinkle_lan_announce(&bob).unwrap();
+    let start = Instant::now();
+
+    while !alice.has_remote_changes() {
+        assert!(start.elapsed() < Duration::from_secs(10));
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    alice.set_lan_address("nonsense").unwrap();
+    assert!(twinkle_lan_announce(&alice).is_err());
+
+    _ = fs::remove_dir_all(fixture_path("lan"));
+}
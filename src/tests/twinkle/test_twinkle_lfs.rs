@@ -0,0 +1,137 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use crate::git::objects::lfs_file::GitLfsFile;
+
+use crate::twinkle::twinkle_lfs::{
+    TWINKLE_LFS_BINARY_MIN,
+    TWINKLE_LFS_PATTERNS,
+    TwinkleLfsMigration,
+    TwinkleLfsUsage,
+    twinkle_lfs_has_plain_files,
+    twinkle_lfs_is_binary,
+    twinkle_lfs_migrations,
+    twinkle_lfs_pattern_for,
+    twinkle_lfs_pattern_matches,
+    twinkle_lfs_store_size,
+    twinkle_lfs_usage,
+};
+
+use super::fixtures::{ fixture_path, fixture_seed };
+
+
+#[test]
+fn test_twinkle_lfs_pattern_matches() {
+    assert!(twinkle_lfs_pattern_matches("*.psd", "cover.psd"));
+    assert!(twinkle_lfs_pattern_matches("*.psd", ".psd"));
+    assert!(twinkle_lfs_pattern_matches("cover-v?.*", "cover-v2.psd"));
+    assert!(twinkle_lfs_pattern_matches("*-final*.blend", "scene-final-final.blend"));
+    assert!(twinkle_lfs_pattern_matches("*", "anything"));
+
+    assert!(!twinkle_lfs_pattern_matches("*.psd", "cover.psd.txt"));
+    assert!(!twinkle_lfs_pattern_matches("*.psd", "cover.PSD"));
+    assert!(!twinkle_lfs_pattern_matches("cover-v?.psd", "cover-v10.psd"));
+}
+
+
+#[test]
+fn test_twinkle_lfs_usage() {
+    let file = |path: &str, size: u64, downloaded: bool| GitLfsFile { path: PathBuf::from(path), size, downloaded };
+
+    let usage = twinkle_lfs_usage(&[
+        file("cover.psd", 5, true),
+        file("videos/intro.mp4", 100, false),
+        file("videos/2025/outro.mp4", 50, true),
+        file("audio/theme.wav", 20, true),
+    ]);
+
+    assert_eq!(usage, vec![
+        TwinkleLfsUsage { dir: PathBuf::from("videos"), local: 50, remote: 150 },
+        TwinkleLfsUsage { dir: PathBuf::from("audio"), local: 20, remote: 20 },
+        TwinkleLfsUsage { dir: PathBuf::from("."), local: 5, remote: 5 },
+    ]);
+
+    assert!(twinkle_lfs_usage(&[]).is_empty());
+}
+
+
+#[test]
+fn test_twinkle_lfs() {
+    let repo = fixture_seed("lfs");
+    fs::create_dir_all(repo.abs_path(Path::new("art"))).unwrap();
+    assert_eq!(repo.lfs_patterns(), TWINKLE_LFS_PATTERNS);
+    assert!(repo.lfs_prune());
+    assert_eq!(repo.lfs_recent_days(), 0);
+    assert_eq!(twinkle_lfs_store_size(&repo), 0);
+
+    fs::create_dir_all(repo.abs_path(Path::new(".git/lfs/objects/4d/7a"))).unwrap();
+    fs::write(repo.abs_path(Path::new(".git/lfs/objects/4d/7a/4d7a2146")), vec![0; 1024]).unwrap();
+    assert_eq!(twinkle_lfs_store_size(&repo), 1024);
+
+    fs::write(repo.abs_path(Path::new("art/cover.psd")), "Not even binary").unwrap();
+    let binary = |header: &[u8]| [header, &vec![0; TWINKLE_LFS_BINARY_MIN as usize]].concat();
+
+    fs::write(repo.abs_path(Path::new("art/logo.png")), binary(&[0x89, b'P', b'N', b'G'])).unwrap();
+    fs::write(repo.abs_path(Path::new("art/firmware")), binary(&[0x7f, b'E', b'L', b'F'])).unwrap();
+    fs::write(repo.abs_path(Path::new("art/icon.ico")), [0, 0, 1, 0]).unwrap();
+    fs::write(repo.abs_path(Path::new("art/notes.txt")), "Small").unwrap();
+    fs::write(repo.abs_path(Path::new("art/log.txt")), vec![b'a'; 2048]).unwrap();
+
+    assert!(twinkle_lfs_is_binary(&repo.abs_path(Path::new("art/logo.png"))));
+    assert!(!twinkle_lfs_is_binary(&repo.abs_path(Path::new("art/notes.txt"))));
+    assert!(!twinkle_lfs_is_binary(&repo.abs_path(Path::new("art/missing.png"))));
+
+    repo.set_lfs_size_threshold(1024).unwrap();
+
+    let pattern_for = |p: &str| twinkle_lfs_pattern_for(&repo, Path::new(p));
+
+    assert_eq!(pattern_for("art/cover.psd"), Some("*.psd".to_string()));
+    assert_eq!(pattern_for("art/logo.png"), Some("*.png".to_string()));
+    assert_eq!(pattern_for("art/firmware"), Some("art/firmware".to_string()));
+    assert_eq!(pattern_for("art/log.txt"), Some("art/log.txt".to_string()));
+    assert_eq!(pattern_for("art/notes.txt"), None);
+    assert_eq!(pattern_for("art/icon.ico"), None); // Too small to bother
+    assert_eq!(pattern_for(".gitattributes"), None);
+
+    // Already in regular Git history, so "*.pdf" would change how it's stored
+    fs::write(repo.abs_path(Path::new("art/old.pdf")), b"%PDF\0").unwrap();
+    repo.git.add(Path::new("art/old.pdf")).unwrap();
+    repo.git.commit(repo.user(), "+ \"art/old.pdf\"").unwrap();
+
+    fs::write(repo.abs_path(Path::new("art/new.pdf")), binary(b"%PDF")).unwrap();
+    assert!(twinkle_lfs_has_plain_files(&repo, "*.pdf", Path::new("art/new.pdf")));
+    assert!(!twinkle_lfs_has_plain_files(&repo, "*.png", Path::new("art/logo.png")));
+    assert_eq!(pattern_for("art/new.pdf"), Some("art/new.pdf".to_string()));
+
+    repo.set_lfs_patterns(&["*.txt"]).unwrap();
+    assert_eq!(pattern_for("art/notes.txt"), Some("*.txt".to_string()));
+    assert_eq!(pattern_for("art/cover.psd"), None);
+
+    // Left behind by earlier versions, one line per file
+    fs::write(repo.abs_path(Path::new(".gitattributes")), [
+        "*.mp4 filter=lfs diff=lfs merge=lfs -text",
+        "art/logo.png filter=lfs diff=lfs merge=lfs -text",
+        "art/firmware filter=lfs diff=lfs merge=lfs -text",
+        "art/my[[:space:]]notes.txt filter=lfs diff=lfs merge=lfs -text",
+        "art/cover.psd text",
+    ].join("\n")).unwrap();
+
+    fs::rename(
+        repo.abs_path(Path::new("art/notes.txt")),
+        repo.abs_path(Path::new("art/my notes.txt")),
+    ).unwrap();
+
+    assert_eq!(twinkle_lfs_migrations(&repo).unwrap(), vec![
+        TwinkleLfsMigration { path: "art/logo.png".to_string(), pattern: "*.png".to_string() },
+        TwinkleLfsMigration { path: "art/my notes.txt".to_string(), pattern: "*.txt".to_string() },
+    ]);
+
+    _ = fs::remove_dir_all(fixture_path("lfs"));
+}
@@ -0,0 +1,65 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use crate::git::objects::file_status::GitFileStatus;
+use crate::ssh::objects::forge::KnownForges;
+
+use crate::twinkle::twinkle_limits::{ twinkle_limit_for, twinkle_oversize_files };
+use crate::twinkle::twinkle_sync::twinkle_sync_stage;
+
+use super::fixtures::{ fixture_path, fixture_seed };
+
+
+const MB: u64 = 1024 * 1024;
+
+
+#[test]
+fn test_twinkle_limits() {
+    let repo = fixture_seed("limits");
+    fs::create_dir_all(repo.abs_path(Path::new("videos"))).unwrap();
+    repo.set_lfs_enabled(false).unwrap();
+
+    // Without LFS, the forge's lower limit applies
+    let github = KnownForges::builtin().get("github.com").cloned();
+    assert_eq!(twinkle_limit_for(&repo, github.as_ref(), false).unwrap().0, 100 * MB);
+    assert_eq!(twinkle_limit_for(&repo, github.as_ref(), true).unwrap().0, 2048 * MB);
+    assert!(twinkle_limit_for(&repo, None, false).is_none());
+
+    repo.set_lfs_max_file_size(MB).unwrap();
+    assert_eq!(twinkle_limit_for(&repo, github.as_ref(), false).unwrap().0, 100 * MB);
+    assert_eq!(twinkle_limit_for(&repo, github.as_ref(), true).unwrap().1, "twinkle.lfs.maxFileSize is 1 MB");
+
+    repo.set_max_file_size(1024).unwrap();
+    assert_eq!(twinkle_limit_for(&repo, github.as_ref(), false).unwrap().1, "twinkle.maxFileSize is 1 KB");
+
+    // Skipped, even when added through their folder
+    fs::write(repo.abs_path(Path::new("videos/big.mov")), vec![0; 2048]).unwrap();
+    fs::write(repo.abs_path(Path::new("videos/small.txt")), "Small").unwrap();
+
+    let oversize = twinkle_oversize_files(&repo).unwrap();
+    assert_eq!(oversize.len(), 1);
+    assert_eq!(oversize[0].path, PathBuf::from("videos/big.mov"));
+    assert_eq!(oversize[0].to_string(), "`videos/big.mov` is 2 KB, twinkle.maxFileSize is 1 KB");
+
+    assert_eq!(twinkle_sync_stage(&repo).unwrap(), oversize);
+
+    let status = repo.git.status_all().unwrap();
+    let status_of = |file: &str| status.iter().find(|change| change.path == Path::new(file)).unwrap().status_x.clone();
+
+    assert_eq!(status_of("videos/small.txt"), Some(GitFileStatus::Added));
+    assert_eq!(status_of("videos/big.mov"), Some(GitFileStatus::Untracked));
+
+    // Until the limit is raised
+    repo.set_max_file_size(MB).unwrap();
+    assert!(twinkle_sync_stage(&repo).unwrap().is_empty());
+    assert!(repo.git.status().unwrap().iter().all(|change| change.status_x == Some(GitFileStatus::Added)));
+
+    _ = fs::remove_dir_all(fixture_path("limits"));
+}
@@ -0,0 +1,70 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use crate::git::objects::lfs_lock::GitLfsLock;
+use crate::git::objects::user::GitUser;
+
+use crate::twinkle::twinkle_lfs::twinkle_lfs_is_lockable;
+use crate::twinkle::twinkle_lock::{ TwinkleLock, twinkle_lock, twinkle_lock_holder };
+
+use super::fixtures::{ fixture_path, fixture_seed };
+
+
+#[test]
+fn test_twinkle_lock_holder() {
+    let users: Vec<GitUser> = [
+        "Hylke Bons <123+hbons@users.noreply.github.com>",
+        "Alice <alice@example.org>",
+        "bob <robert@example.org>",
+    ].iter().map(|user| user.parse().unwrap()).collect();
+
+    assert_eq!(twinkle_lock_holder("hbons", &users).unwrap().name(), "Hylke Bons");
+    assert_eq!(twinkle_lock_holder("Alice", &users).unwrap().email(), "alice@example.org");
+    assert_eq!(twinkle_lock_holder("alice@example.org", &users).unwrap().name(), "Alice");
+    assert_eq!(twinkle_lock_holder("bob", &users).unwrap().email(), "robert@example.org");
+    assert!(twinkle_lock_holder("carol", &users).is_none());
+    assert!(twinkle_lock_holder("hbons", &[]).is_none());
+
+    let lock = GitLfsLock {
+        id: "123".into(),
+        path: PathBuf::from("art/cover.psd"),
+        owner: "alice".into(),
+        ours: false,
+    };
+
+    let mut twinkle_lock = TwinkleLock { lock, holder: None };
+    assert_eq!(twinkle_lock.to_string(), "`art/cover.psd` is locked by alice");
+
+    twinkle_lock.holder = twinkle_lock_holder("alice", &users);
+    assert_eq!(twinkle_lock.to_string(), "`art/cover.psd` is locked by Alice <alice@example.org>");
+
+    twinkle_lock.lock.ours = true;
+    assert_eq!(twinkle_lock.to_string(), "`art/cover.psd` is locked by you");
+}
+
+
+#[test]
+fn test_twinkle_lock() {
+    let repo = fixture_seed("lock");
+    fs::create_dir_all(repo.abs_path(Path::new("art"))).unwrap();
+    assert!(repo.lfs_lockable().is_empty());
+    assert!(!twinkle_lfs_is_lockable(&repo, Path::new("art/cover.psd")));
+
+    repo.set_lfs_lockable(&["*.psd", "*.blend"]).unwrap();
+    assert!(twinkle_lfs_is_lockable(&repo, Path::new("art/cover.psd")));
+    assert!(!twinkle_lfs_is_lockable(&repo, Path::new("art/notes.txt")));
+
+    // Refused before asking the server
+    fs::write(repo.abs_path(Path::new("art/notes.txt")), "Notes").unwrap();
+    let error = twinkle_lock(&repo, Path::new("art/notes.txt")).unwrap_err();
+    assert!(error.to_string().contains("not lockable"));
+
+    _ = fs::remove_dir_all(fixture_path("lock"));
+}
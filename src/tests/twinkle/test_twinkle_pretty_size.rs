@@ -0,0 +1,19 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use crate::twinkle::twinkle_pretty::twinkle_pretty_size;
+
+
+#[test]
+fn test_twinkle_pretty_size() {
+    assert_eq!(twinkle_pretty_size(0), "0 bytes");
+    assert_eq!(twinkle_pretty_size(1023), "1023 bytes");
+    assert_eq!(twinkle_pretty_size(1536), "1.5 KB");
+    assert_eq!(twinkle_pretty_size(100 * 1024 * 1024), "100 MB");
+    assert_eq!(twinkle_pretty_size(20 * 1024 * 1024 * 1024), "20 GB");
+    assert_eq!(twinkle_pretty_size(u64::MAX), "16777216 TB");
+}
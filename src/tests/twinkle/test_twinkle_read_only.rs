@@ -0,0 +1,68 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::fs;
+use std::path::Path;
+
+use crate::twinkle::defaults::common::COMMON_FIRST_FILE;
+use crate::twinkle::objects::repository::TwinkleRepository;
+use crate::twinkle::twinkle_read_only::twinkle_read_only_aside_dir;
+use crate::twinkle::twinkle_sync::{ twinkle_sync_down, twinkle_sync_up };
+
+use super::fixtures::{ fixture_commit_file, fixture_path, fixture_push, fixture_seed_and_clone };
+
+
+fn push_change(seed: &TwinkleRepository, file: &str, content: &str) {
+    fixture_commit_file(seed, file, content);
+    fixture_push(seed);
+}
+
+
+#[test]
+fn test_twinkle_read_only() {
+    let (seed, mut repo) = fixture_seed_and_clone("read_only", &[]);
+    repo.set_read_only(true).unwrap();
+
+    // Stray edits are never committed
+    let head = repo.current_head().unwrap();
+    fs::write(repo.abs_path(Path::new(COMMON_FIRST_FILE)), "Edited").unwrap();
+    fs::write(repo.abs_path(Path::new("notes.txt")), "Mine").unwrap();
+
+    twinkle_sync_up(&mut repo).unwrap();
+    assert_eq!(repo.current_head().unwrap(), head);
+
+    // ...but moved aside, so the remote's changes always come in
+    push_change(&seed, COMMON_FIRST_FILE, "Upstream");
+    twinkle_sync_down(&mut repo).unwrap();
+
+    assert!(repo.git.status().unwrap().is_empty());
+    assert_eq!(fs::read_to_string(repo.abs_path(Path::new(COMMON_FIRST_FILE))).unwrap(), "Upstream");
+    assert_eq!(repo.current_head().unwrap(), seed.current_head().unwrap());
+
+    let aside = fs::read_dir(twinkle_read_only_aside_dir(&repo)).unwrap()
+        .next().unwrap().unwrap().path();
+
+    assert_eq!(fs::read_to_string(aside.join(COMMON_FIRST_FILE)).unwrap(), "Edited");
+    assert_eq!(fs::read_to_string(aside.join("notes.txt")).unwrap(), "Mine");
+
+    // Only reported, so they stay until they're in the way
+    repo.set_read_only_move_aside(false).unwrap();
+    fs::write(repo.abs_path(Path::new("notes.txt")), "Mine").unwrap();
+
+    push_change(&seed, "other.txt", "Upstream");
+    twinkle_sync_down(&mut repo).unwrap();
+    assert!(repo.abs_path(Path::new("notes.txt")).exists());
+
+    fs::write(repo.abs_path(Path::new(COMMON_FIRST_FILE)), "Edited again").unwrap();
+    push_change(&seed, COMMON_FIRST_FILE, "Upstream again");
+
+    assert!(twinkle_sync_down(&mut repo).is_err());
+    assert!(!repo.git.is_in_merge());
+    assert_eq!(fs::read_to_string(repo.abs_path(Path::new(COMMON_FIRST_FILE))).unwrap(), "Edited again");
+
+    _ = fs::remove_dir_all(fixture_path("read_only"));
+}
@@ -0,0 +1,170 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::fs;
+use std::net::TcpListener;
+use std::sync::mpsc::sync_channel;
+use std::thread;
+use std::time::{ Duration, Instant };
+
+use tungstenite::{ connect, Message };
+
+use crate::twinkle::twinkle_channel::twinkle_channel_listen;
+use crate::twinkle::twinkle_relay::{
+    RELAY_MAX_CHANNELS,
+    RELAY_MAX_SUBSCRIBERS,
+    RELAY_QUEUE_SIZE,
+    TwinkleRelay,
+    twinkle_relay_serve,
+};
+
+use super::fixtures::{ fixture_path, fixture_seed };
+
+
+const CHANNEL: &str = "5d41402abc4b2a76b9719d911017c592ae1aec3d5c6e1d3bd0d5a3a6e1f1e8a0";
+const OTHER_CHANNEL: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+
+fn wait_until(condition: impl Fn() -> bool) {
+    let start = Instant::now();
+
+    while !condition() {
+        assert!(start.elapsed() < Duration::from_secs(10));
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+
+#[test]
+fn test_twinkle_relay_fan_out() {
+    let relay = TwinkleRelay::new();
+    let channel = CHANNEL.to_string();
+
+    let (alice, alice_inbox) = sync_channel::<String>(RELAY_QUEUE_SIZE);
+    let (bob, bob_inbox) = sync_channel::<String>(RELAY_QUEUE_SIZE);
+
+    relay.subscribe(&channel, 1, &alice).unwrap();
+    relay.subscribe(&channel, 2, &bob).unwrap();
+    relay.subscribe(&channel, 2, &bob).unwrap();
+    assert_eq!(relay.subscriber_count(&channel), 2);
+
+    // Not back to the announcer
+    assert_eq!(relay.announce(&channel, "1b2c3d", 1), 1);
+    assert!(alice_inbox.try_recv().is_err());
+    assert_eq!(bob_inbox.try_recv().unwrap(), format!("announce {CHANNEL} 1b2c3d"));
+
+    assert_eq!(relay.announce(&OTHER_CHANNEL.to_string(), "1b2c3d", 1), 0);
+
+    // A slow reader misses out, but stays subscribed
+    for _ in 0..RELAY_QUEUE_SIZE {
+        assert_eq!(relay.announce(&channel, "1b2c3d", 1), 1);
+    }
+
+    assert_eq!(relay.announce(&channel, "1b2c3d", 1), 0);
+    assert_eq!(relay.subscriber_count(&channel), 2);
+
+    drop(bob_inbox);
+    relay.announce(&channel, "1b2c3d", 1);
+    assert_eq!(relay.subscriber_count(&channel), 1);
+
+    relay.unsubscribe(&channel, 1);
+    assert_eq!(relay.subscriber_count(&channel), 0);
+
+    // Bounded per channel
+    for id in 0..RELAY_MAX_SUBSCRIBERS as u64 {
+        relay.subscribe(&channel, id, &alice).unwrap();
+    }
+
+    assert!(relay.subscribe(&channel, RELAY_MAX_SUBSCRIBERS as u64, &alice).is_err());
+    assert!(relay.subscribe(&OTHER_CHANNEL.to_string(), RELAY_MAX_SUBSCRIBERS as u64, &alice).is_ok());
+
+    relay.remove(RELAY_MAX_SUBSCRIBERS as u64);
+    assert_eq!(relay.subscriber_count(&OTHER_CHANNEL.to_string()), 0);
+}
+
+
+#[test]
+fn test_twinkle_relay_max_channels() {
+    let relay = TwinkleRelay::new();
+    let (alice, _alice_inbox) = sync_channel::<String>(RELAY_QUEUE_SIZE);
+
+    let channels: Vec<String> = (0..=RELAY_MAX_CHANNELS).map(|i| format!("{i:064x}")).collect();
+
+    for channel in &channels[..RELAY_MAX_CHANNELS] {
+        relay.subscribe(channel, 1, &alice).unwrap();
+    }
+
+    assert!(relay.subscribe(&channels[RELAY_MAX_CHANNELS], 1, &alice).is_err());
+    assert_eq!(relay.subscriber_count(&channels[RELAY_MAX_CHANNELS]), 0);
+
+    // Already on it
+    assert!(relay.subscribe(&channels[0], 1, &alice).is_ok());
+
+    // Per connection
+    assert!(relay.subscribe(&channels[RELAY_MAX_CHANNELS], 2, &alice).is_ok());
+
+    relay.unsubscribe(&channels[0], 1);
+    assert!(relay.subscribe(&channels[RELAY_MAX_CHANNELS], 1, &alice).is_ok());
+}
+
+
+#[test]
+fn test_twinkle_relay_max_connections() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    let relay = TwinkleRelay::with_max_connections(1);
+    let relay_c = relay.clone();
+    thread::spawn(move || { _ = twinkle_relay_serve(&listener, &relay_c); });
+
+    let (mut alice, _) = connect(&url).unwrap();
+    alice.send(Message::text(format!("subscribe {CHANNEL}"))).unwrap();
+    wait_until(|| relay.subscriber_count(&CHANNEL.to_string()) == 1);
+
+    // Refused
+    assert!(connect(&url).is_err());
+    assert_eq!(relay.connection_count(), 1);
+
+    alice.close(None).unwrap();
+    _ = alice.read(); // Wait for the relay to close too
+    wait_until(|| relay.connection_count() == 0);
+
+    assert!(connect(&url).is_ok());
+}
+
+
+#[test]
+fn test_twinkle_relay() {
+    let repo = fixture_seed("relay");
+    let channel = repo.as_notify_channel_name().unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    repo.set_notify_url(&url).unwrap();
+
+    let relay = TwinkleRelay::new();
+    let relay_c = relay.clone();
+    thread::spawn(move || { _ = twinkle_relay_serve(&listener, &relay_c); });
+
+    let repo_c = repo.clone();
+    thread::spawn(move || { _ = twinkle_channel_listen(&repo_c, false); });
+    wait_until(|| relay.subscriber_count(&channel) == 1);
+
+    // Another client pushed
+    let (mut peer, _) = connect(&url).unwrap();
+    let unknown = "0".repeat(40);
+
+    peer.send(Message::text(format!("announce {OTHER_CHANNEL} {unknown}"))).unwrap();
+    peer.send(Message::text(format!("announce {channel} {unknown}"))).unwrap();
+    wait_until(|| repo.has_remote_changes());
+
+    // Nonsense gets the connection closed
+    peer.send(Message::text("subscribe my-photos")).unwrap();
+    assert!(peer.read().unwrap().is_close());
+
+    _ = fs::remove_dir_all(fixture_path("relay"));
+}
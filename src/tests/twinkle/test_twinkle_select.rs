@@ -0,0 +1,102 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use crate::twinkle::twinkle_clone::{ twinkle_clone_complete, twinkle_clone_start };
+use crate::twinkle::twinkle_sync::twinkle_sync_stage;
+
+use crate::twinkle::twinkle_select::{
+    twinkle_select_add,
+    twinkle_select_all,
+    twinkle_select_contains,
+    twinkle_select_normalize,
+    twinkle_select_remove,
+};
+
+use super::fixtures::{ fixture_commit_file, fixture_path, fixture_push, fixture_remote_url, fixture_seed };
+
+
+#[test]
+fn test_twinkle_select_normalize() {
+    assert_eq!(twinkle_select_normalize(Path::new("art/")).unwrap(), PathBuf::from("art"));
+    assert_eq!(twinkle_select_normalize(Path::new("./art/covers")).unwrap(), PathBuf::from("art/covers"));
+
+    assert!(twinkle_select_normalize(Path::new("")).is_err());
+    assert!(twinkle_select_normalize(Path::new(".")).is_err());
+    assert!(twinkle_select_normalize(Path::new("/art")).is_err());
+    assert!(twinkle_select_normalize(Path::new("art/../..")).is_err());
+    assert!(twinkle_select_normalize(Path::new(".git/hooks")).is_err());
+    assert!(twinkle_select_normalize(Path::new("art,docs")).is_err());
+
+    let selection = vec![PathBuf::from("art/covers"), PathBuf::from("docs")];
+
+    assert!(twinkle_select_contains(&selection, Path::new("README.md")));
+    assert!(twinkle_select_contains(&selection, Path::new(".twinkle/config")));
+    assert!(twinkle_select_contains(&selection, Path::new("docs/index.md")));
+    assert!(twinkle_select_contains(&selection, Path::new("art/covers/front.psd")));
+    assert!(!twinkle_select_contains(&selection, Path::new("art/logo.psd")));
+    assert!(!twinkle_select_contains(&selection, Path::new("documents/index.md")));
+    assert!(twinkle_select_contains(&[], Path::new("art/logo.psd")));
+}
+
+
+#[test]
+fn test_twinkle_select() {
+    let path = fixture_path("select");
+
+    let seed = fixture_seed("select");
+    fixture_commit_file(&seed, "art/cover.psd", "Cover");
+    fixture_commit_file(&seed, "docs/index.md", "Index");
+    fixture_push(&seed);
+
+    // Only docs is checked out
+    fs::create_dir_all(path.join("clones")).unwrap();
+
+    let mut repo = twinkle_clone_start(&fixture_remote_url("select"), None, &path.join("clones"), Some(1)).unwrap();
+    repo.set_select(&[PathBuf::from("docs")]).unwrap();
+    twinkle_clone_complete(&mut repo, None).unwrap();
+    repo.set_lfs_enabled(false).unwrap();
+
+    assert!(repo.abs_path(Path::new("docs/index.md")).exists());
+    assert!(!repo.abs_path(Path::new("art/cover.psd")).exists());
+
+    // Never seen as deleted, and new files outside of the selection are left alone
+    fs::create_dir_all(repo.abs_path(Path::new("art"))).unwrap();
+    fs::write(repo.abs_path(Path::new("art/sketch.psd")), "Sketch").unwrap();
+    fs::write(repo.abs_path(Path::new("docs/new.md")), "New").unwrap();
+
+    twinkle_sync_stage(&repo).unwrap();
+    let staged = repo.git.run("diff", &["--cached", "--name-only"]).unwrap().stdout;
+    assert_eq!(staged, "docs/new.md");
+
+    fs::remove_dir_all(repo.abs_path(Path::new("art"))).unwrap();
+    repo.git.run("commit", &["--quiet", "--message=Add new"]).unwrap();
+
+    twinkle_select_add(&repo, Path::new("art/")).unwrap();
+    assert_eq!(repo.select(), [PathBuf::from("art"), PathBuf::from("docs")]);
+    assert!(repo.abs_path(Path::new("art/cover.psd")).exists());
+
+    // Keeps changes that haven't been synced
+    fs::write(repo.abs_path(Path::new("docs/index.md")), "Edited").unwrap();
+    assert!(twinkle_select_remove(&repo, Path::new("docs")).is_err());
+    repo.git.run("checkout", &["--", "docs/index.md"]).unwrap();
+
+    twinkle_select_remove(&repo, Path::new("docs")).unwrap();
+    assert!(!repo.abs_path(Path::new("docs/index.md")).exists());
+    assert!(repo.git.status_all().unwrap().is_empty());
+
+    assert!(twinkle_select_remove(&repo, Path::new("docs")).is_err());
+    assert!(twinkle_select_remove(&repo, Path::new("art")).is_err()); // The last one
+
+    twinkle_select_all(&repo).unwrap();
+    assert!(repo.select().is_empty());
+    assert!(repo.abs_path(Path::new("docs/index.md")).exists());
+
+    _ = fs::remove_dir_all(path);
+}
@@ -0,0 +1,146 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::fs;
+use std::path::Path;
+
+use crate::ssh::keys::key_type::KeyType;
+use crate::ssh::objects::config::SshConfig;
+use crate::ssh::objects::url::SshUrl;
+
+use crate::twinkle::twinkle_keys::{ twinkle_hostkey_for, twinkle_keypair_for, twinkle_keytype_for };
+use crate::twinkle::twinkle_util::twinkle_ssh_command;
+
+use crate::twinkle::twinkle_ssh::{
+    twinkle_ssh_config_export,
+    twinkle_ssh_config_for,
+    twinkle_ssh_config_import,
+    twinkle_ssh_forge_for,
+    twinkle_ssh_forges,
+    twinkle_ssh_known_hosts_import,
+    twinkle_ssh_known_hosts_name,
+    twinkle_ssh_scan_url,
+};
+
+
+const SSH_CONFIG: &str = "\
+Host git.example.org
+    HostName 192.0.2.10
+    Port 2222
+    ProxyJump jump.example.org
+
+Host aliased.example.org
+    HostKeyAlias build-server
+";
+
+const KNOWN_HOSTS: &str = "\
+# Comment
+|1|F1E1KeoE/eEWhi10WpGv4OdiO6Y=|3988QV0VE8wmZL7suNrYQLITLCg= ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIHashedHashedHashedHashedHashedHashedHashed
+@cert-authority *.example.org ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAICertCertCertCertCertCertCertCertCertCertCert
+[192.0.2.10]:2222,other.example.org ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIOMqqnkVzrm0SdG6UOoqKLsabgH5C9okWi0dh2l9GKJl
+[192.0.2.10]:2222 ecdsa-sha2-nistp256 AAAAE2VjZHNhLXNoYTItbmlzdHAyNTYAAAAIbmlzdHAyNTYAAABBBEmKSENjQEezOmxkZMy7opKgwFB9nkt5YRrYMjNuG5N87uRgg6CLrbo5wAdT/y6v0mKV0U2w0WZ2YB/++Tpockg=
+192.0.2.10 ssh-rsa AAAAB3NzaC1yc2EAAAADAQABAAABAQWrongPort
+build-server ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIAliasAliasAliasAliasAliasAliasAliasAliasAli
+";
+
+
+#[test]
+fn test_twinkle_ssh_import() {
+    let path = Path::new("/tmp/twinkle_tests_ssh_import");
+    let keys_dir = path.join("keys");
+
+    if path.exists() {
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    fs::create_dir_all(path).unwrap();
+    fs::write(path.join("config"), SSH_CONFIG).unwrap();
+    fs::write(path.join("known_hosts"), KNOWN_HOSTS).unwrap();
+
+    let host = "git.example.org";
+    let config = twinkle_ssh_config_import(host, &path.join("config"), &keys_dir).unwrap();
+    assert_eq!(twinkle_ssh_known_hosts_name(host, &config), "[192.0.2.10]:2222");
+
+    let host_keys = twinkle_ssh_known_hosts_import(host, &config, &path.join("known_hosts"), &keys_dir).unwrap();
+    let key_types: Vec<KeyType> = host_keys.iter().map(|host_key| host_key.key_type).collect();
+    assert_eq!(key_types, vec![KeyType::ED25519, KeyType::ECDSA]);
+    assert_eq!(twinkle_keytype_for(host, &keys_dir), Some(KeyType::ED25519));
+
+    // Trusted keys are stored under the Twinkle host name
+    let url: SshUrl = format!("ssh://git@{host}/project.git").parse().unwrap();
+    let host_key = twinkle_hostkey_for(&url, KeyType::ECDSA, &keys_dir, &path.join("forges")).unwrap();
+    assert_eq!(host_key.host, host);
+    assert!(host_key.public_key.starts_with("AAAAE2VjZHNh"));
+
+    let config = twinkle_ssh_config_for(&url, &keys_dir);
+    assert_eq!(config.HostName.as_deref(), Some("192.0.2.10"));
+    assert_eq!(config.Port, Some(2222));
+    assert_eq!(config.ProxyJump.as_deref(), Some("jump.example.org"));
+    assert_eq!(config.HostKeyAlias.as_deref(), Some(host));
+
+    let scan_url = twinkle_ssh_scan_url(&url, &keys_dir);
+    assert_eq!(scan_url.host, "192.0.2.10");
+    assert_eq!(scan_url.port, Some(2222));
+
+    let key_pair = twinkle_keypair_for(&host.into(), KeyType::ED25519, &keys_dir).unwrap();
+    let command = twinkle_ssh_command(Some(&key_pair), Some(&url));
+    assert!(command.contains("-o HostKeyAlias=git.example.org"));
+    assert!(command.contains("-o ProxyJump=jump.example.org"));
+
+    let block = twinkle_ssh_config_export(&url, &key_pair, &keys_dir).to_string();
+    assert!(block.starts_with("Host git.example.org\n"));
+    assert!(block.contains("    IdentityFile /tmp/twinkle_tests_ssh_import/keys/git.example.org.ed25519.key\n"));
+
+    // The same host on another port has its own entry
+    let url_with_port: SshUrl = format!("ssh://git@{host}:2200/project.git").parse().unwrap();
+    assert!(twinkle_ssh_config_for(&url_with_port, &keys_dir).HostKeyAlias.as_deref() == Some("[git.example.org]:2200"));
+
+    // The alias is only used for finding the host in known_hosts
+    let host = "aliased.example.org";
+    let config = twinkle_ssh_config_import(host, &path.join("config"), &keys_dir).unwrap();
+    assert_eq!(twinkle_ssh_known_hosts_name(host, &config), "build-server");
+    assert_eq!(twinkle_ssh_known_hosts_import(host, &config, &path.join("known_hosts"), &keys_dir).unwrap().len(), 1);
+    assert!(!keys_dir.join("aliased.example.org.ssh_config").exists());
+
+    let host = "unknown.example.org";
+    assert!(twinkle_ssh_known_hosts_import(host, &SshConfig::default(), &path.join("known_hosts"), &keys_dir).is_err());
+
+    fs::remove_dir_all(path).unwrap();
+}
+
+
+#[test]
+fn test_twinkle_ssh_forges() {
+    let path = Path::new("/tmp/twinkle_tests_ssh_forges");
+    let keys_dir = path.join("keys");
+    let forges_file = path.join("forges");
+
+    if path.exists() {
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    assert!(twinkle_ssh_forge_for("git.example.org", &forges_file).is_none());
+    assert!(twinkle_ssh_forge_for("github.com", &forges_file).is_some());
+
+    fs::create_dir_all(path).unwrap();
+    fs::write(&forges_file, "\
+Forge git.example.org
+    HostKey ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIOMqqnkVzrm0SdG6UOoqKLsabgH5C9okWi0dh2l9GKJl
+").unwrap();
+
+    assert!(twinkle_ssh_forges(&forges_file).get("git.example.org").is_some());
+
+    // Pinned by the team, so no scanning needed
+    let url = "ssh://git@git.example.org:2222/hbons/Twinkle".parse::<SshUrl>().unwrap();
+    let host_key = twinkle_hostkey_for(&url, KeyType::ED25519, &keys_dir, &forges_file).unwrap();
+
+    assert_eq!(host_key.host, "git.example.org");
+    assert_eq!(host_key.port, Some(2222));
+    assert!(host_key.is_trusted);
+
+    _ = fs::remove_dir_all(path);
+}
@@ -0,0 +1,82 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use crate::twinkle::twinkle_sync::twinkle_sync_down;
+
+use crate::twinkle::twinkle_trash::{
+    twinkle_trash_empty,
+    twinkle_trash_expire,
+    twinkle_trash_init,
+    twinkle_trash_list,
+    twinkle_trash_restore,
+};
+
+use super::fixtures::{ fixture_path, fixture_push, fixture_seed_and_clone };
+
+
+#[test]
+fn test_twinkle_trash() {
+    let path = fixture_path("trash");
+
+    let plans: String = (1..=10).map(|i| format!("Plan {i}\n")).collect();
+
+    let (seed, mut repo) = fixture_seed_and_clone("trash", &[
+        ("docs/notes.md", "Notes"),
+        ("todo.md", "Todo"),
+        ("plans.md", &plans),
+    ]);
+
+    let trash_dir = twinkle_trash_init(&repo, &path.join("data")).unwrap();
+    assert_eq!(repo.trash_dir(), Some(trash_dir.clone()));
+    assert!(trash_dir.starts_with(path.join("data/trash")));
+
+    // Someone else deletes one file, changes another, and renames and changes a third
+    fs::remove_file(seed.abs_path(Path::new("docs/notes.md"))).unwrap();
+    fs::write(seed.abs_path(Path::new("todo.md")), "Done").unwrap();
+    fs::remove_file(seed.abs_path(Path::new("plans.md"))).unwrap();
+    fs::write(seed.abs_path(Path::new("docs/plans.md")), plans.replace("Plan 10", "Plan X")).unwrap();
+    seed.git.run("add", &["--all"]).unwrap();
+    seed.git.commit(seed.user(), "~1, −1, →1").unwrap();
+    fixture_push(&seed);
+
+    twinkle_sync_down(&mut repo).unwrap();
+    assert!(!repo.abs_path(Path::new("docs/notes.md")).exists());
+
+    let items = twinkle_trash_list(&repo).unwrap();
+    let paths: Vec<&PathBuf> = items.iter().map(|item| &item.path).collect();
+    assert_eq!(paths, [Path::new("docs/notes.md"), Path::new("plans.md"), Path::new("todo.md")]);
+    assert_eq!(fs::read_to_string(&items[1].file).unwrap(), plans);
+    assert_eq!(fs::read_to_string(&items[2].file).unwrap(), "Todo");
+
+    // Back where it was, or next to what's there now
+    assert_eq!(twinkle_trash_restore(&repo, Path::new("docs")).unwrap(), [PathBuf::from("docs/notes.md")]);
+    assert_eq!(fs::read_to_string(repo.abs_path(Path::new("docs/notes.md"))).unwrap(), "Notes");
+
+    assert_eq!(twinkle_trash_restore(&repo, Path::new("todo.md")).unwrap(), [PathBuf::from("todo (restored).md")]);
+    assert_eq!(fs::read_to_string(repo.abs_path(Path::new("todo (restored).md"))).unwrap(), "Todo");
+    assert_eq!(fs::read_to_string(repo.abs_path(Path::new("todo.md"))).unwrap(), "Done");
+
+    assert!(twinkle_trash_restore(&repo, Path::new("missing.md")).is_err());
+
+    // Expiring
+    assert_eq!(twinkle_trash_expire(&repo).unwrap(), 0);
+
+    fs::create_dir_all(trash_dir.join("2020-01-01 12.00.00")).unwrap();
+    fs::write(trash_dir.join("2020-01-01 12.00.00/old.md"), "Old").unwrap();
+    assert_eq!(twinkle_trash_list(&repo).unwrap().len(), 4);
+
+    assert_eq!(twinkle_trash_expire(&repo).unwrap(), 1);
+    assert_eq!(twinkle_trash_list(&repo).unwrap().len(), 3);
+
+    assert_eq!(twinkle_trash_empty(&repo).unwrap(), 3);
+    assert!(twinkle_trash_list(&repo).unwrap().is_empty());
+
+    _ = fs::remove_dir_all(path);
+}
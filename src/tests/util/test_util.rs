@@ -0,0 +1,24 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use crate::util::util_parse_size;
+
+
+#[test]
+fn test_util_parse_size() {
+    assert_eq!(util_parse_size("0").unwrap(), 0);
+    assert_eq!(util_parse_size("100").unwrap(), 100);
+    assert_eq!(util_parse_size("2k").unwrap(), 2048);
+    assert_eq!(util_parse_size("1M").unwrap(), 1024 * 1024);
+    assert_eq!(util_parse_size("1g").unwrap(), 1024 * 1024 * 1024);
+
+    assert!(util_parse_size("").is_err());
+    assert!(util_parse_size("k").is_err());
+    assert!(util_parse_size("-1k").is_err());
+    assert!(util_parse_size("lots").is_err());
+    assert!(util_parse_size(&format!("{}g", u64::MAX)).is_err()); // Overflows
+}
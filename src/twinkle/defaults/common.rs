@@ -16,6 +16,11 @@ pub const COMMON_CONFIG_FILE: &str = ".twinkle/config";
 pub const COMMON_FIRST_FILE: &str = "TWINKLE.md";
 pub const COMMON_FIRST_COMMIT_MESSAGE: &str = "Set up Twinkle";
 
+pub const COMMON_CLONE_DEPTH: u32 = 1; // Older history is fetched when needed
+
+pub const COMMON_NOTIFY_URL: &str = "wss://notify.sparkleshare.org";
+pub const COMMON_LAN_ADDRESS: &str = "239.255.70.86:23286"; // Multicast, doesn't leave the local network
+
 
 // Durations
 
@@ -30,3 +35,11 @@ pub fn twinkle_default_sync_up_delay_max() -> Duration {
 pub fn twinkle_default_sync_up_delay_bump() -> Duration {
     Duration::from_secs(15)
 }
+
+pub fn twinkle_default_notify_retry_delay() -> Duration {
+    Duration::from_secs(60)
+}
+
+pub fn twinkle_default_notify_ping_interval() -> Duration {
+    Duration::from_secs(120)
+}
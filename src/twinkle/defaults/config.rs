@@ -11,22 +11,50 @@ use std::thread;
 pub const K_ENABLED: &str = "enabled";
 pub const K_ID: &str = "id";
 pub const K_READONLY: &str = "readonly";
+pub const K_READONLY_MOVE_ASIDE: &str = "readonly.moveAside";
 pub const K_POLLING_INTERVAL: &str = "pollingInterval";
 pub const K_LAST_SYNC: &str = "lastSync";
 pub const K_LAST_CHECK: &str = "lastCheck";
 pub const K_MAX_FILE_SIZE: &str = "maxFileSize";
+pub const K_SELECT: &str = "select"; // Can be given more than once
 
 pub const K_CO_AUTHOR: &str = "coAuthor";
 
+// In .twinkle/config, shared by all clients
+pub const K_GENERATION: &str = "generation";
+pub const K_NEXT_BRANCH: &str = "nextBranch";
+pub const K_NEXT_COMMIT: &str = "nextCommit";
+
 
 // Notify
 pub const K_NOTIFY_ENABLED: &str = "notify.enabled";
 pub const K_NOTIFY_URL: &str = "notify.url";
 
+// LAN
+pub const K_LAN_ENABLED: &str = "lan.enabled";
+pub const K_LAN_ADDRESS: &str = "lan.address";
+
 // LFS
 pub const K_LFS_ENABLED: &str = "lfs.enabled";
 pub const K_LFS_SIZE_THRESHOLD: &str = "lfs.sizeThreshold";
 pub const K_LFS_MAX_FILE_SIZE: &str = "lfs.maxFileSize";
+pub const K_LFS_PATTERNS: &str = "lfs.patterns";
+pub const K_LFS_LOCKABLE: &str = "lfs.lockable";
+pub const K_LFS_PRUNE: &str = "lfs.prune";
+pub const K_LFS_RECENT_DAYS: &str = "lfs.recentDays";
+
+pub const K_COMPACT_ENABLED: &str = "compact.enabled";
+pub const K_COMPACT_DAYS: &str = "compact.days";
+pub const K_COMPACT_PERIOD: &str = "compact.period";
+
+pub const K_DELETIONS_MAX_FILES: &str = "deletions.maxFiles";
+pub const K_DELETIONS_MAX_PERCENT: &str = "deletions.maxPercent";
+pub const K_DELETIONS_PAUSED: &str = "deletions.paused";
+
+pub const K_TRASH_DIR: &str = "trash.dir";
+pub const K_TRASH_DAYS: &str = "trash.days";
+
+pub const K_SSH_FORGES_FILE: &str = "ssh.forgesFile";
 
 
 const SECTION: &str = "twinkle"; // TODO
@@ -45,17 +73,51 @@ fn _twinkle_default_settings()
         (key(K_ENABLED), "true"),
         (key(K_ID), "80fa2cca2f73dd2105185daec982df7f20ac372ab5209bece55fdd04dc110c53"),
         (key(K_READONLY), "false"),
+        (key(K_READONLY_MOVE_ASIDE), "true"),
         (key(K_POLLING_INTERVAL), "3m"),
         (key(K_LAST_SYNC), "0"),
         (key(K_LAST_CHECK), "0"),
+        (key(K_MAX_FILE_SIZE), "0"), // No limit
+        (key(K_SELECT), "docs"), // Everything if unset
 
         // Notify
         (key(K_NOTIFY_ENABLED), "true"),
         (key(K_NOTIFY_URL), "wss://notify.sparkleshare.org"),
 
+        // LAN
+        (key(K_LAN_ENABLED), "false"),
+        (key(K_LAN_ADDRESS), "239.255.70.86:23286"),
+
         // LFS
         (key(K_LFS_ENABLED), "true"),
         (key(K_LFS_SIZE_THRESHOLD), "1m"),
+        (key(K_LFS_MAX_FILE_SIZE), "0"),
+        (key(K_LFS_PATTERNS), "*.psd *.blend *.mp4 *.mov *.wav"),
+        (key(K_LFS_LOCKABLE), ""), // Like "*.psd *.blend"
+        (key(K_LFS_PRUNE), "true"),
+        (key(K_LFS_RECENT_DAYS), "0"), // Only what's checked out
+
+        // Compact
+        (key(K_COMPACT_ENABLED), "false"),
+        (key(K_COMPACT_DAYS), "90"), // History older than this is compacted
+        (key(K_COMPACT_PERIOD), "30"), // Days per snapshot
+
+        // Deletions
+        (key(K_DELETIONS_MAX_FILES), "500"), // More in one commit pauses syncing. 0 for no limit
+        (key(K_DELETIONS_MAX_PERCENT), "50"), // Of the tracked files. 0 for no limit
+        (key(K_DELETIONS_PAUSED), "ab83b62f5027c66be4826c73f07daeb25fd04219"), // Only while paused
+
+        // Trash
+        (key(K_TRASH_DIR), "~/.local/share/twinkle/trash/<id>"), // Set when syncing starts
+        (key(K_TRASH_DAYS), "30"), // 0 turns the trash off
+
+        // SSH
+        (key(K_SSH_FORGES_FILE), "~/.config/twinkle/forges"), // Set when syncing starts
+
+        // .twinkle/config
+        (key(K_GENERATION), "1"), // Bumped on every compaction
+        (key(K_NEXT_BRANCH), "main-2"), // Only on a compacted branch
+        (key(K_NEXT_COMMIT), "ab83b62f5027c66be4826c73f07daeb25fd04219"),
     ]
 }
 
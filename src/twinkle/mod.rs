@@ -8,7 +8,6 @@
 pub mod defaults {
     pub mod common;
     pub mod config;
-    pub mod hosts;
     pub mod info;
 }
 
@@ -19,12 +18,25 @@ pub mod objects {
     pub mod repository_notify;
 }
 
+pub mod twinkle_channel;
 pub mod twinkle_clone;
+pub mod twinkle_compact;
+pub mod twinkle_credentials;
+pub mod twinkle_deletions;
+pub mod twinkle_history;
 pub mod twinkle_init;
 pub mod twinkle_keys;
+pub mod twinkle_lan;
 pub mod twinkle_lfs;
+pub mod twinkle_limits;
+pub mod twinkle_lock;
 pub mod twinkle_notify;
 pub mod twinkle_pretty;
+pub mod twinkle_read_only;
+pub mod twinkle_relay;
 pub mod twinkle_resolve;
+pub mod twinkle_select;
+pub mod twinkle_ssh;
+pub mod twinkle_trash;
 pub mod twinkle_util;
 pub mod twinkle_sync;
@@ -7,22 +7,26 @@
 
 use std::fs::File;
 use std::io::Write;
+use std::path::{ Path, PathBuf };
 use std::error::Error;
 use std::time::Duration;
 
+use crate::git::objects::id::GitId;
+use crate::git::objects::remote_url::GitRemoteUrl;
 use crate::git::objects::user::GitUser;
 use crate::log;
 use crate::ssh::keys::key_pair::KeyPair;
-use crate::ssh::objects::config::SshConfig;
-use crate::ssh::objects::url::SshUrl;
 
-use crate::twinkle::twinkle_lfs::TWINKLE_LFS_THRESHOLD;
-use crate::twinkle::defaults::common::twinkle_default_polling_interval;
+use crate::util::util_parse_size;
+use crate::twinkle::twinkle_lfs::{ TWINKLE_LFS_PATTERNS, TWINKLE_LFS_THRESHOLD };
+use crate::twinkle::twinkle_util::twinkle_ssh_command;
+use crate::twinkle::defaults::common::{ COMMON_LAN_ADDRESS, COMMON_NOTIFY_URL, twinkle_default_polling_interval };
 use crate::twinkle::objects::repository::TwinkleRepository;
 
 use crate::git::config::{
     K_COMMIT_GPG_SIGN,
     K_CORE_SSH_COMMAND,
+    K_CREDENTIAL_HELPER,
     K_TAG_GPG_SIGN, K_USER_EMAIL,
     K_USER_NAME,
     K_USER_SIGNING_KEY,
@@ -30,14 +34,35 @@ use crate::git::config::{
 };
 
 use crate::twinkle::defaults::config::{
+    K_COMPACT_DAYS,
+    K_COMPACT_ENABLED,
+    K_COMPACT_PERIOD,
+    K_DELETIONS_MAX_FILES,
+    K_DELETIONS_MAX_PERCENT,
+    K_DELETIONS_PAUSED,
     K_ENABLED,
     K_ID,
+    K_LAN_ADDRESS,
+    K_LAN_ENABLED,
     K_LAST_CHECK,
     K_LAST_SYNC,
     K_LFS_ENABLED,
+    K_LFS_LOCKABLE,
+    K_LFS_MAX_FILE_SIZE,
+    K_LFS_PATTERNS,
+    K_LFS_PRUNE,
+    K_LFS_RECENT_DAYS,
     K_LFS_SIZE_THRESHOLD,
+    K_MAX_FILE_SIZE,
+    K_NOTIFY_ENABLED,
+    K_NOTIFY_URL,
     K_POLLING_INTERVAL,
     K_READONLY,
+    K_READONLY_MOVE_ASIDE,
+    K_SELECT,
+    K_SSH_FORGES_FILE,
+    K_TRASH_DAYS,
+    K_TRASH_DIR,
     key
 };
 
@@ -104,22 +129,42 @@ impl TwinkleRepository {
 
         Ok(())
     }
+
+    /// Whether local edits in a read-only repository are moved aside, or only reported
+    pub fn read_only_move_aside(&self) -> bool {
+        if let Some(output) = self.git.config_get(&key(K_READONLY_MOVE_ASIDE)) {
+            if let Ok(value) = output.stdout.parse::<bool>() {
+                return value;
+            }
+        }
+
+        true
+    }
+
+    pub fn set_read_only_move_aside(&self, value: bool) -> Result<(), Box<dyn Error>>{
+        self.git.config_set(
+            &key(K_READONLY_MOVE_ASIDE),
+            &value.to_string()
+        )?;
+
+        Ok(())
+    }
 }
 
 
 // remote_url
 impl TwinkleRepository {
-    pub fn remote_url(&self) -> Option<SshUrl> { // TODO: use .remote()
+    pub fn remote_url(&self) -> Option<GitRemoteUrl> { // TODO: use .remote()
         self.git.config_get(K_REMOTE_ORIGIN_URL)
             .and_then(|v|
-                v.stdout.trim().parse::<SshUrl>().ok()
+                v.stdout.trim().parse::<GitRemoteUrl>().ok()
             )
     }
 
-    pub fn set_remote_url(&self, remote: &str, value: &SshUrl) -> Result<(), Box<dyn Error>>{
+    pub fn set_remote_url(&self, remote: &str, value: &GitRemoteUrl) -> Result<(), Box<dyn Error>>{
         self.git.config_set(
             &format!("remote.{remote}.url"),
-            &value.to_string_standard(),
+            &value.to_string(),
         )?;
 
         Ok(())
@@ -180,6 +225,89 @@ pub fn parse_polling_interval(s: &str) -> u64 {
 }
 
 
+// notify
+impl TwinkleRepository {
+    /// Whether to listen for and announce pushes on the notification channel
+    pub fn notify_enabled(&self) -> bool {
+        if let Some(output) = self.git.config_get(&key(K_NOTIFY_ENABLED)) {
+            if let Ok(value) = output.stdout.parse::<bool>() {
+                return value;
+            }
+        }
+
+        true
+    }
+
+    pub fn set_notify_enabled(&self, value: bool) -> Result<(), Box<dyn Error>>{
+        self.git.config_set(
+            &key(K_NOTIFY_ENABLED),
+            &value.to_string()
+        )?;
+
+        Ok(())
+    }
+
+
+    pub fn notify_url(&self) -> String {
+        match self.git.config_get(&key(K_NOTIFY_URL)) {
+            Some(output) if !output.stdout.is_empty() => output.stdout,
+            _ => COMMON_NOTIFY_URL.to_string(),
+        }
+    }
+
+    pub fn set_notify_url(&self, value: &str) -> Result<(), Box<dyn Error>>{
+        self.git.config_set(
+            &key(K_NOTIFY_URL),
+            value,
+        )?;
+
+        Ok(())
+    }
+}
+
+
+// lan
+impl TwinkleRepository {
+    /// Whether to announce pushes to, and listen for, peers on the local network
+    pub fn lan_enabled(&self) -> bool {
+        if let Some(output) = self.git.config_get(&key(K_LAN_ENABLED)) {
+            if let Ok(value) = output.stdout.parse::<bool>() {
+                return value;
+            }
+        }
+
+        false
+    }
+
+    pub fn set_lan_enabled(&self, value: bool) -> Result<(), Box<dyn Error>>{
+        self.git.config_set(
+            &key(K_LAN_ENABLED),
+            &value.to_string()
+        )?;
+
+        Ok(())
+    }
+
+
+    /// Multicast group or broadcast address, and the port peers listen on
+    pub fn lan_address(&self) -> String {
+        match self.git.config_get(&key(K_LAN_ADDRESS)) {
+            Some(output) if !output.stdout.is_empty() => output.stdout,
+            _ => COMMON_LAN_ADDRESS.to_string(),
+        }
+    }
+
+    pub fn set_lan_address(&self, value: &str) -> Result<(), Box<dyn Error>>{
+        self.git.config_set(
+            &key(K_LAN_ADDRESS),
+            value,
+        )?;
+
+        Ok(())
+    }
+}
+
+
 // last_synced, last_checked
 impl TwinkleRepository {
     pub fn last_checked(&self) -> Option<i64> {
@@ -217,6 +345,210 @@ impl TwinkleRepository {
 }
 
 
+// max_file_size
+impl TwinkleRepository {
+    /// Larger files aren't synced, with or without LFS. None if there's no limit
+    pub fn max_file_size(&self) -> Option<u64> {
+        self.git.config_get(&key(K_MAX_FILE_SIZE))
+            .map(|output| parse_lfs_size(&output.stdout))
+            .filter(|size| *size > 0)
+    }
+
+    pub fn set_max_file_size(&self, value: u64) -> Result<(), Box<dyn Error>>{
+        self.git.config_set(
+            &key(K_MAX_FILE_SIZE),
+            &value.to_string(),
+        )?;
+
+        Ok(())
+    }
+}
+
+
+// select
+impl TwinkleRepository {
+    /// The folders that are checked out. Everything if empty
+    pub fn select(&self) -> Vec<PathBuf> {
+        self.git.config_get_all(&key(K_SELECT))
+            .into_iter()
+            .map(PathBuf::from)
+            .collect()
+    }
+
+    pub fn set_select(&self, value: &[PathBuf]) -> Result<(), Box<dyn Error>>{
+        let value: Vec<&str> = value.iter()
+            .map(|dir| dir.to_str().ok_or("Path is not valid UTF-8"))
+            .collect::<Result<_, _>>()?;
+
+        self.git.config_set_all(&key(K_SELECT), &value)
+    }
+}
+
+
+// compact
+impl TwinkleRepository {
+    pub fn compact_enabled(&self) -> bool {
+        if let Some(output) = self.git.config_get(&key(K_COMPACT_ENABLED)) {
+            if let Ok(value) = output.stdout.parse::<bool>() {
+                return value;
+            }
+        }
+
+        false
+    }
+
+    pub fn set_compact_enabled(&self, value: bool) -> Result<(), Box<dyn Error>>{
+        self.git.config_set(
+            &key(K_COMPACT_ENABLED),
+            &value.to_string(),
+        )?;
+
+        Ok(())
+    }
+
+
+    /// History older than this many days is compacted
+    pub fn compact_days(&self) -> u32 {
+        self.git.config_get(&key(K_COMPACT_DAYS))
+            .and_then(|output| output.stdout.parse::<u32>().ok())
+            .unwrap_or(90)
+    }
+
+    pub fn set_compact_days(&self, value: u32) -> Result<(), Box<dyn Error>>{
+        self.git.config_set(
+            &key(K_COMPACT_DAYS),
+            &value.to_string(),
+        )?;
+
+        Ok(())
+    }
+
+
+    /// Days of compacted history per snapshot commit
+    pub fn compact_period(&self) -> u32 {
+        self.git.config_get(&key(K_COMPACT_PERIOD))
+            .and_then(|output| output.stdout.parse::<u32>().ok())
+            .filter(|days| *days > 0)
+            .unwrap_or(30)
+    }
+
+    pub fn set_compact_period(&self, value: u32) -> Result<(), Box<dyn Error>>{
+        self.git.config_set(
+            &key(K_COMPACT_PERIOD),
+            &value.to_string(),
+        )?;
+
+        Ok(())
+    }
+}
+
+
+// deletions
+impl TwinkleRepository {
+    /// Deleting more files than this in one commit pauses syncing. 0 for no limit
+    pub fn deletions_max_files(&self) -> usize {
+        self.git.config_get(&key(K_DELETIONS_MAX_FILES))
+            .and_then(|output| output.stdout.parse::<usize>().ok())
+            .unwrap_or(500)
+    }
+
+    pub fn set_deletions_max_files(&self, value: usize) -> Result<(), Box<dyn Error>>{
+        self.git.config_set(
+            &key(K_DELETIONS_MAX_FILES),
+            &value.to_string(),
+        )?;
+
+        Ok(())
+    }
+
+
+    /// Same, as a percentage of the tracked files
+    pub fn deletions_max_percent(&self) -> u32 {
+        self.git.config_get(&key(K_DELETIONS_MAX_PERCENT))
+            .and_then(|output| output.stdout.parse::<u32>().ok())
+            .unwrap_or(50)
+    }
+
+    pub fn set_deletions_max_percent(&self, value: u32) -> Result<(), Box<dyn Error>>{
+        self.git.config_set(
+            &key(K_DELETIONS_MAX_PERCENT),
+            &value.to_string(),
+        )?;
+
+        Ok(())
+    }
+
+
+    /// The unpushed commit that deleted too many files, while waiting for confirmation
+    pub fn deletions_paused(&self) -> Option<GitId> {
+        self.git.config_get(&key(K_DELETIONS_PAUSED))
+            .map(|output| output.stdout)
+            .filter(|commit| !commit.is_empty())
+    }
+
+    pub fn set_deletions_paused(&self, value: Option<&GitId>) -> Result<(), Box<dyn Error>>{
+        match value {
+            Some(commit) => { self.git.config_set(&key(K_DELETIONS_PAUSED), commit)?; },
+            None => { self.git.config_unset(&key(K_DELETIONS_PAUSED))?; },
+        }
+
+        Ok(())
+    }
+}
+
+// trash
+impl TwinkleRepository {
+    /// Where files are copied to before a sync deletes or overwrites them
+    pub fn trash_dir(&self) -> Option<PathBuf> {
+        self.git.config_get(&key(K_TRASH_DIR))
+            .map(|output| PathBuf::from(output.stdout))
+    }
+
+    pub fn set_trash_dir(&self, value: &Path) -> Result<(), Box<dyn Error>>{
+        self.git.config_set(
+            &key(K_TRASH_DIR),
+            value.to_str().ok_or("Path is not valid UTF-8")?,
+        )?;
+
+        Ok(())
+    }
+
+
+    /// Days to keep files in the trash. 0 turns the trash off
+    pub fn trash_days(&self) -> u32 {
+        self.git.config_get(&key(K_TRASH_DAYS))
+            .and_then(|output| output.stdout.parse::<u32>().ok())
+            .unwrap_or(30)
+    }
+
+    pub fn set_trash_days(&self, value: u32) -> Result<(), Box<dyn Error>>{
+        self.git.config_set(
+            &key(K_TRASH_DAYS),
+            &value.to_string(),
+        )?;
+
+        Ok(())
+    }
+}
+
+// ssh
+impl TwinkleRepository {
+    /// The app's forges file, for looking up the remote's host
+    pub fn forges_file(&self) -> Option<PathBuf> {
+        self.git.config_get(&key(K_SSH_FORGES_FILE))
+            .map(|output| PathBuf::from(output.stdout))
+    }
+
+    pub fn set_forges_file(&self, value: &Path) -> Result<(), Box<dyn Error>>{
+        self.git.config_set(
+            &key(K_SSH_FORGES_FILE),
+            value.to_str().ok_or("Path is not valid UTF-8")?,
+        )?;
+
+        Ok(())
+    }
+}
+
 // lfs
 impl TwinkleRepository {
     pub fn lfs_enabled(&self) -> bool {
@@ -254,21 +586,123 @@ impl TwinkleRepository {
 
         Ok(())
     }
+
+
+    /// Larger files aren't synced. None if there's no limit
+    pub fn lfs_max_file_size(&self) -> Option<u64> {
+        self.git.config_get(&key(K_LFS_MAX_FILE_SIZE))
+            .map(|output| parse_lfs_size(&output.stdout))
+            .filter(|size| *size > 0)
+    }
+
+    pub fn set_lfs_max_file_size(&self, value: u64) -> Result<(), Box<dyn Error>>{
+        self.git.config_set(
+            &key(K_LFS_MAX_FILE_SIZE),
+            &value.to_string(),
+        )?;
+
+        Ok(())
+    }
+
+
+    /// New files matching these are always stored with LFS, like "*.psd"
+    pub fn lfs_patterns(&self) -> Vec<String> {
+        match self.git.config_get(&key(K_LFS_PATTERNS)) {
+            Some(output) => output.stdout
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|pattern| !pattern.is_empty())
+                .map(String::from)
+                .collect(),
+            None => TWINKLE_LFS_PATTERNS.iter().map(|pattern| pattern.to_string()).collect(),
+        }
+    }
+
+    pub fn set_lfs_patterns(&self, value: &[&str]) -> Result<(), Box<dyn Error>>{
+        self.git.config_set(
+            &key(K_LFS_PATTERNS),
+            &value.join(" "),
+        )?;
+
+        Ok(())
+    }
+
+
+    /// Files matching these can be locked, and are read-only until then
+    pub fn lfs_lockable(&self) -> Vec<String> {
+        match self.git.config_get(&key(K_LFS_LOCKABLE)) {
+            Some(output) => output.stdout
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|pattern| !pattern.is_empty())
+                .map(String::from)
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn set_lfs_lockable(&self, value: &[&str]) -> Result<(), Box<dyn Error>>{
+        self.git.config_set(
+            &key(K_LFS_LOCKABLE),
+            &value.join(" "),
+        )?;
+
+        Ok(())
+    }
+
+
+    /// Whether to delete old LFS objects after syncing
+    pub fn lfs_prune(&self) -> bool {
+        if let Some(output) = self.git.config_get(&key(K_LFS_PRUNE)) {
+            if let Ok(value) = output.stdout.parse::<bool>() {
+                return value;
+            }
+        }
+
+        true
+    }
+
+    pub fn set_lfs_prune(&self, value: bool) -> Result<(), Box<dyn Error>>{
+        self.git.config_set(
+            &key(K_LFS_PRUNE),
+            &value.to_string(),
+        )?;
+
+        Ok(())
+    }
+
+
+    /// Days of history to keep LFS objects for, besides what's checked out
+    pub fn lfs_recent_days(&self) -> u32 {
+        self.git.config_get(&key(K_LFS_RECENT_DAYS))
+            .and_then(|output| output.stdout.parse::<u32>().ok())
+            .unwrap_or(0)
+    }
+
+    pub fn set_lfs_recent_days(&self, value: u32) -> Result<(), Box<dyn Error>>{
+        self.git.config_set(
+            &key(K_LFS_RECENT_DAYS),
+            &value.to_string(),
+        )?;
+
+        Ok(())
+    }
 }
 
+/// 0 when it's not a size, which means no limit
 pub fn parse_lfs_size(s: &str) -> u64 {
-    let (number, multiplier) = match s.as_bytes().last() {
-        Some(b'k' | b'K') => (&s[..s.len() - 1], 1024),
-        Some(b'm' | b'M') => (&s[..s.len() - 1], 1024 * 1024),
-        Some(b'g' | b'G') => (&s[..s.len() - 1], 1024 * 1024 * 1024),
-        _ => (s, 1),
-    };
-
-    number.parse::<u64>().unwrap_or(0) * multiplier
+    util_parse_size(s).unwrap_or(0)
 }
 
 
 impl TwinkleRepository {
+    /// The key pair is found through the signing key path
+    pub fn key_pair(&self) -> Option<KeyPair> {
+        let key_path = PathBuf::from(self.git.config_get(K_USER_SIGNING_KEY)?.stdout);
+        let public_key_path = key_path.with_extension("key.pub");
+
+        KeyPair::from_files(&key_path, &public_key_path).ok()
+    }
+
+
     pub fn set_user_signing_key(&self, key_pair: &KeyPair) -> Result<(), Box<dyn Error>>{
         let key_path = &key_pair.private_key_path.to_string_lossy();
         self.git.config_set(K_USER_SIGNING_KEY, key_path)?;
@@ -290,20 +724,18 @@ impl TwinkleRepository {
 
     // Write a minimal SSH command to the .git/config for debugging purposes
     pub fn set_core_ssh_command(&self, key_pair: Option<&KeyPair>) -> Result<(), Box<dyn Error>>{
-        let config = match key_pair {
-            Some(key_pair) => {
-                SshConfig {
-                    IdentitiesOnly: true,
-                    IdentityFile: Some(key_pair.private_key_path.clone()),
-                    UserKnownHostsFile: Some(key_pair.private_key_path.with_extension("key.host")),
-                    ..Default::default()
-                }
-            },
-            None => SshConfig::default(),
-        };
-
-        self.git.config_set(K_CORE_SSH_COMMAND, &format!("ssh -F /dev/null {config}"))?;
+        let remote_url = self.remote_url();
+        let ssh_url = remote_url.as_ref().and_then(|url| url.as_ssh());
+
+        self.git.config_set(K_CORE_SSH_COMMAND, &twinkle_ssh_command(key_pair, ssh_url))?;
+
+        Ok(())
+    }
+
 
+    /// HTTPS remotes get their tokens from `twinkle credential`
+    pub fn set_credential_helper(&self, value: &str) -> Result<(), Box<dyn Error>>{
+        self.git.config_set(K_CREDENTIAL_HELPER, value)?;
         Ok(())
     }
 }
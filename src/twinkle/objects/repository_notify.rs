@@ -5,6 +5,10 @@
 //   under the terms of the GNU General Public License v3 or any later version.
 
 
+use std::error::Error;
+use std::fmt;
+use std::str;
+
 use sha2::{ Sha256, Digest };
 use crate::twinkle::objects::repository::TwinkleRepository;
 
@@ -24,3 +28,61 @@ impl TwinkleRepository {
         )
     }
 }
+
+
+/// Channel names are hashed, so servers never learn what's being synced
+pub fn is_notify_channel_name(s: &str) -> bool {
+    s.len() == 64 && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+
+/// One message per WebSocket text frame:
+///
+///     subscribe <channel>
+///     unsubscribe <channel>
+///     announce <channel> <commit>
+///
+/// Servers pass announcements on to the channel's other subscribers as is
+#[derive(Clone, Debug, PartialEq)]
+pub enum TwinkleNotifyMessage {
+    Subscribe(TwinkleChannelName),
+    Unsubscribe(TwinkleChannelName),
+    Announce(TwinkleChannelName, String),
+}
+
+
+impl fmt::Display for TwinkleNotifyMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Subscribe(channel)         => write!(f, "subscribe {channel}"),
+            Self::Unsubscribe(channel)       => write!(f, "unsubscribe {channel}"),
+            Self::Announce(channel, message) => write!(f, "announce {channel} {message}"),
+        }
+    }
+}
+
+
+impl str::FromStr for TwinkleNotifyMessage {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().splitn(3, ' ');
+
+        let command = parts.next().unwrap_or_default();
+        let channel = parts.next().ok_or_else(|| format!("Missing channel in `{s}`"))?;
+
+        if !is_notify_channel_name(channel) {
+            return Err(format!("Invalid channel name `{channel}`").into());
+        }
+
+        let channel = channel.to_string();
+
+        match (command, parts.next()) {
+            ("subscribe", None)   => Ok(Self::Subscribe(channel)),
+            ("unsubscribe", None) => Ok(Self::Unsubscribe(channel)),
+            ("announce", Some(message)) if !message.is_empty() && !message.contains(char::is_whitespace) =>
+                Ok(Self::Announce(channel, message.to_string())),
+            _ => Err(format!("Invalid message `{s}`").into()),
+        }
+    }
+}
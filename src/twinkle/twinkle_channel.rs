@@ -0,0 +1,152 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use std::thread;
+
+use tungstenite::{ client_tls, Message, WebSocket };
+use tungstenite::http::Uri;
+use tungstenite::stream::MaybeTlsStream;
+
+use crate::log;
+
+use super::defaults::common::{ twinkle_default_notify_ping_interval, twinkle_default_notify_retry_delay };
+use super::objects::repository::TwinkleRepository;
+use super::objects::repository_notify::{ TwinkleChannelName, TwinkleNotifyMessage };
+
+
+// Clients announce the commit they pushed on the repository's channel,
+// so others fetch right away instead of at the next poll. Polling keeps
+// going, in case the server is down or an announcement is missed.
+
+
+pub type TwinkleChannelSocket = WebSocket<MaybeTlsStream<TcpStream>>;
+
+
+/// Connects to a "ws://" or "wss://" notification server
+pub fn twinkle_channel_connect(url: &str) -> Result<TwinkleChannelSocket, Box<dyn Error>> {
+    let uri: Uri = url.parse()?;
+    let host = uri.host().ok_or_else(|| format!("Missing host in `{url}`"))?;
+
+    let port = match (uri.scheme_str(), uri.port_u16()) {
+        (Some("ws" | "wss"), Some(port)) => port,
+        (Some("ws"), None)  => 80,
+        (Some("wss"), None) => 443,
+        _ => return Err(format!("Not a WebSocket URL: `{url}`").into()),
+    };
+
+    let stream = TcpStream::connect((host.trim_start_matches('[').trim_end_matches(']'), port))?;
+    stream.set_read_timeout(Some(twinkle_default_notify_ping_interval()))?;
+
+    let (socket, _) = client_tls(url, stream)
+        .map_err(|e| format!("Could not connect to `{url}`: {e}"))?;
+
+    Ok(socket)
+}
+
+
+/// Listens until the connection drops, reconnecting after a delay
+pub fn twinkle_channel_watch(repo: &TwinkleRepository) -> Result<(), Box<dyn Error>> {
+    let mut catch_up = false;
+
+    loop {
+        if let Err(e) = twinkle_channel_listen(repo, catch_up) {
+            log::debug(&format!("Notify | Disconnected from `{}`: {e}", repo.notify_url()));
+        }
+
+        catch_up = true;
+        thread::sleep(twinkle_default_notify_retry_delay());
+    }
+}
+
+
+/// Subscribes to the repository's channel and flags remote changes when
+/// a commit we don't have is announced. With `catch_up`, also flags them
+/// right after subscribing, as announcements may have been missed.
+pub fn twinkle_channel_listen(repo: &TwinkleRepository, catch_up: bool) -> Result<(), Box<dyn Error>> {
+    let channel = repo.as_notify_channel_name().ok_or("Missing ID")?;
+    let url = repo.notify_url();
+
+    let mut socket = twinkle_channel_connect(&url)?;
+    socket.send(Message::text(TwinkleNotifyMessage::Subscribe(channel.clone()).to_string()))?;
+
+    log::debug(&format!("Notify | Subscribed to `{url}`"));
+
+    if catch_up {
+        repo.set_has_remote_changes(true);
+    }
+
+    let mut awaiting_pong = false;
+
+    loop {
+        match socket.read() {
+            Ok(Message::Text(text)) => {
+                if twinkle_channel_receive(repo, &channel, text.as_str()) {
+                    repo.set_has_remote_changes(true);
+                    log::info("Remote changes announced…");
+                }
+            },
+            Ok(Message::Close(_)) => return Err("Closed by the server".into()),
+            Ok(_) => {},
+            Err(tungstenite::Error::Io(e)) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                if awaiting_pong {
+                    return Err("No response from the server".into());
+                }
+
+                // Quiet channel, check the connection is still there
+                socket.send(Message::Ping(Default::default()))?;
+                awaiting_pong = true;
+                continue;
+            },
+            Err(e) => return Err(e.into()),
+        }
+
+        awaiting_pong = false;
+    }
+}
+
+
+/// Whether a message from the server announces a commit we don't have yet.
+/// Our own announcements come back as commits we already have.
+pub fn twinkle_channel_receive(repo: &TwinkleRepository, channel: &TwinkleChannelName, text: &str) -> bool {
+    let commit = match text.parse::<TwinkleNotifyMessage>() {
+        Ok(TwinkleNotifyMessage::Announce(announced, commit)) if announced == *channel => commit,
+        Ok(_) => return false,
+        Err(e) => {
+            log::debug(&format!("Notify | {e}"));
+            return false;
+        },
+    };
+
+    match repo.branch() {
+        Some(branch) => !repo.git.merge_base(&commit, &branch).unwrap_or(false),
+        None => false,
+    }
+}
+
+
+/// Tells the channel's other subscribers about the commit we're at
+pub fn twinkle_channel_announce(repo: &TwinkleRepository) -> Result<(), Box<dyn Error>> {
+    let channel = repo.as_notify_channel_name().ok_or("Missing ID")?;
+    let commit = repo.current_head()?;
+
+    let mut socket = twinkle_channel_connect(&repo.notify_url())?;
+    socket.send(Message::text(TwinkleNotifyMessage::Announce(channel, commit.clone()).to_string()))?;
+    socket.close(None)?;
+
+    // Wait for the server to close too, so the announcement isn't cut off
+    while let Ok(message) = socket.read() {
+        if message.is_close() {
+            break;
+        }
+    }
+
+    log::debug(&format!("Notify | Announced {commit}"));
+    Ok(())
+}
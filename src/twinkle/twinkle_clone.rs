@@ -9,64 +9,87 @@ use std::error::Error;
 use std::fmt;
 use std::path::Path;
 
+use crate::git::config::K_CREDENTIAL_HELPER;
 use crate::git::objects::environment::GitEnvironment;
+use crate::git::objects::remote_url::GitRemoteUrl;
 
 use crate::log;
 use crate::ssh::keygen::ssh_keygen_fingerprint;
 use crate::ssh::keys::host_key::HostKey;
 use crate::ssh::keys::key_pair::KeyPair;
-use crate::ssh::keys::key_type::KeyType;
-use crate::ssh::objects::url::SshUrl;
 use crate::ssh::util::ssh_util_test_connection;
 use crate::twinkle::twinkle_init::init_id;
 
 use super::objects::repository::TwinkleRepository;
+use super::twinkle_credentials::{ twinkle_credential_error, twinkle_credential_helper };
+use super::twinkle_compact::{ twinkle_compact_follow, twinkle_compact_next };
 use super::twinkle_init::init_first_commit;
 use super::twinkle_init::twinkle_init_common;
-use super::twinkle_keys::twinkle_hostkey_for;
+use super::twinkle_lfs::twinkle_lfs_fetch;
+use super::twinkle_select::twinkle_select_apply;
+use super::twinkle_keys::twinkle_hostkey_is_stored;
+use super::twinkle_keys::twinkle_hostkey_negotiate;
+use super::twinkle_keys::twinkle_hostkey_trust;
 use super::twinkle_keys::twinkle_keypair_for;
+use super::twinkle_ssh::{ twinkle_ssh_config_for, twinkle_ssh_forge_for };
 use super::twinkle_util::twinkle_default_dir_name;
 use super::twinkle_util::twinkle_ssh_command;
 use super::twinkle_util::twinkle_unique_dir;
 
 
+/// Returns `None` for remotes that don't use SSH
 pub fn twinkle_clone_prepare_keys(
-    url: &SshUrl,
+    url: &GitRemoteUrl,
     keys_dir: &Path,
-) -> Result<KeyPair, Box<dyn Error>>
+    forges_file: &Path,
+) -> Result<Option<KeyPair>, Box<dyn Error>>
 {
-    let key_pair = twinkle_keypair_for(&url.host, KeyType::default(), keys_dir)?;
+    let Some(url) = url.as_ssh() else {
+        return Ok(None);
+    };
 
-    let mut host_key = match twinkle_hostkey_for(url, KeyType::default(), keys_dir) {
-        Err(_) => { return Err(Box::new(TwinkleCloneError::NeedsNetwork)); }
+    let mut host_key = match twinkle_hostkey_negotiate(url, keys_dir, forges_file) {
+        Err(_) => { return Err(Box::new(TwinkleCloneError::NeedsNetwork(url.host.clone()))); }
         Ok(host_key) => host_key,
     };
 
+    // The client key uses the same type as the host key
+    let key_pair = twinkle_keypair_for(&url.host, host_key.key_type, keys_dir)?;
+
     if host_key.fingerprint.is_none() {
         let fingerprint = ssh_keygen_fingerprint(&host_key)?;
         host_key.fingerprint = Some(fingerprint);
     }
 
     if !host_key.is_trusted {
-        Err(Box::new(TwinkleCloneError::NeedsTrust(host_key)))
-    } else {
-        match ssh_util_test_connection(url, &host_key, Some(&key_pair)) {
-            Err(_) => Err(Box::new(TwinkleCloneError::NeedsAuth(host_key, key_pair))),
-            Ok(_) => Ok(key_pair),
-        }
+        return Err(Box::new(TwinkleCloneError::NeedsTrust(host_key)));
+    }
+
+    // Pinned host keys are trusted, but ssh still needs them on disk
+    if !twinkle_hostkey_is_stored(&host_key, keys_dir) {
+        twinkle_hostkey_trust(&host_key, keys_dir)?;
+    }
+
+    let host_config = twinkle_ssh_config_for(url, keys_dir);
+    let forge = twinkle_ssh_forge_for(&url.host, forges_file);
+
+    match ssh_util_test_connection(url, &host_key, Some(&key_pair), &host_config, forge.as_ref()) {
+        Err(_) => Err(Box::new(TwinkleCloneError::NeedsAuth(host_key, Box::new(key_pair)))),
+        Ok(_) => Ok(Some(key_pair)),
     }
 }
 
 
 pub fn twinkle_clone_start(
-    url: &SshUrl,
+    url: &GitRemoteUrl,
     key_pair: Option<&KeyPair>,
-    path: &Path
+    path: &Path,
+    depth: Option<u32>, // All of the history if None
 ) -> Result<TwinkleRepository, Box<dyn Error>>
 {
     let git = GitEnvironment {
         working_dir: path.to_path_buf(),
-        GIT_SSH_COMMAND: twinkle_ssh_command(key_pair),
+        GIT_SSH_COMMAND: twinkle_ssh_command(key_pair, url.as_ssh()),
         ..Default::default()
     };
 
@@ -77,20 +100,31 @@ pub fn twinkle_clone_start(
     let dir = twinkle_default_dir_name(url)?;
     let dir = twinkle_unique_dir(&dir);
 
-    let target_git = git.clone(
-        &url.to_string_standard(),
-        Some(dir.as_ref()),
-        Some(1)
-    )?;
+    let target_git = match url {
+        GitRemoteUrl::Https(https_url) => {
+            let helper = twinkle_credential_helper()?;
+
+            git.clone_with_config(
+                &url.to_string(),
+                Some(dir.as_ref()),
+                depth,
+                &[(K_CREDENTIAL_HELPER, &helper)],
+            ).map_err(|e| twinkle_credential_error(https_url, e))?
+        },
+        _ => git.clone(
+            &url.to_string(),
+            Some(dir.as_ref()),
+            depth,
+        )?,
+    };
 
     let mut repo = TwinkleRepository::new(&target_git.working_dir);
     repo.git = target_git;
 
     if let Ok(lfs_files) = repo.git.lfs_ls_files() {
         if !lfs_files.is_empty() {
-            log::info("LFS files detected. Fetching…");
+            log::info("LFS files detected");
             repo.set_lfs_enabled(true)?;
-            repo.git.lfs_fetch()?;
         }
     }
 
@@ -105,9 +139,22 @@ pub fn twinkle_clone_complete(
 {
     twinkle_init_common(repo, key_pair)?;
 
+    // Set before checking out, so only the selected folders are fetched
+    twinkle_select_apply(repo)?;
+
+    if repo.lfs_enabled() && repo.git.lfs_ls_files().is_ok_and(|files| !files.is_empty()) {
+        log::info("Fetching LFS files…");
+        twinkle_lfs_fetch(repo)?;
+    }
+
     repo.git.checkout_branch(&"HEAD".into())?;
     init_id(repo)?;
 
+    // The default branch was compacted
+    if twinkle_compact_next(repo).is_some() {
+        twinkle_compact_follow(repo)?;
+    }
+
     if repo.is_empty() {
         init_first_commit(repo)?;
     }
@@ -118,15 +165,25 @@ pub fn twinkle_clone_complete(
 
 #[derive(Debug)]
 pub enum TwinkleCloneError {
-    NeedsNetwork,
+    NeedsNetwork(String), // Host
     NeedsTrust(HostKey),
-    NeedsAuth(HostKey, KeyPair),
+    NeedsAuth(HostKey, Box<KeyPair>),
+}
+
+impl TwinkleCloneError {
+    pub fn to_exit_code(&self) -> u8 {
+        match self {
+            Self::NeedsNetwork(_) => 1,
+            Self::NeedsTrust(_) => 2,
+            Self::NeedsAuth(_, _) => 3,
+        }
+    }
 }
 
 impl fmt::Display for TwinkleCloneError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            TwinkleCloneError::NeedsNetwork => write!(f, "No network connection"),
+            TwinkleCloneError::NeedsNetwork(host) => write!(f, "Could not connect to {host}"),
             TwinkleCloneError::NeedsTrust(host_key) => write!(f, "Host key not trusted: {}", host_key),
             TwinkleCloneError::NeedsAuth(_host_key, _key_pair) => write!(f, "Authentication failed with host key and key pair"),
         }
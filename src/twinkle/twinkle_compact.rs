@@ -0,0 +1,304 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::path::Path;
+
+use chrono::{ DateTime, Duration, Utc };
+
+use crate::git::objects::commit::GitCommit;
+use crate::git::objects::id::GitId;
+use crate::git::objects::reference::GitReference;
+use crate::log;
+
+use super::defaults::common::COMMON_CONFIG_FILE;
+use super::defaults::config::{ K_GENERATION, K_ID, K_NEXT_BRANCH, K_NEXT_COMMIT, key };
+use super::objects::repository::TwinkleRepository;
+use super::twinkle_sync::twinkle_has_unpushed_commits;
+
+
+// Every change is a commit, so after a year there are tens of thousands of
+// them. Compacting squashes the old ones into a snapshot per period, on a new
+// branch. The old branch gets one last commit that points to it in
+// .twinkle/config, so every client moves over the next time it fetches.
+
+
+/// Compacting by itself is only worth it with this many old commits
+pub const TWINKLE_COMPACT_THRESHOLD: u32 = 1000;
+
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TwinkleSnapshot {
+    pub tree: GitId, // Of the last commit in the period
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub count: usize, // Commits it replaces
+}
+
+
+/// Groups the commits before `cutoff` into a snapshot per period of `period_days`.
+/// Also returns how many commits that is. The ones after are kept as they are.
+pub fn twinkle_compact_snapshots(
+    history: &[GitCommit], // Oldest first
+    cutoff: DateTime<Utc>,
+    period_days: u32,
+) -> (Vec<TwinkleSnapshot>, usize)
+{
+    // Clocks can be off, so everything up to the last old commit is old
+    let count = history.iter()
+        .rposition(|commit| commit.timestamp < cutoff)
+        .map_or(0, |index| index + 1);
+
+    let period = i64::from(period_days.max(1)) * 24 * 60 * 60;
+
+    let mut snapshots: Vec<TwinkleSnapshot> = Vec::new();
+    let mut current_period = None;
+
+    for commit in &history[..count] {
+        let commit_period = commit.timestamp.timestamp().div_euclid(period);
+
+        match snapshots.last_mut() {
+            Some(snapshot) if current_period == Some(commit_period) => {
+                snapshot.tree = commit.tree.clone();
+                snapshot.to = commit.timestamp;
+                snapshot.count += 1;
+            },
+            _ => {
+                snapshots.push(TwinkleSnapshot {
+                    tree: commit.tree.clone(),
+                    from: commit.timestamp,
+                    to: commit.timestamp,
+                    count: 1,
+                });
+
+                current_period = Some(commit_period);
+            },
+        }
+    }
+
+    (snapshots, count)
+}
+
+
+/// "main" becomes "main-2", and "main-2" becomes "main-3"
+pub fn twinkle_compact_branch_name(branch: &str, generation: u32) -> String {
+    let base = match branch.strip_suffix(&format!("-{generation}")) {
+        Some(base) if generation > 1 => base,
+        _ => branch,
+    };
+
+    format!("{base}-{}", generation + 1)
+}
+
+
+/// Commits from before this are compacted
+pub fn twinkle_compact_cutoff(repo: &TwinkleRepository) -> DateTime<Utc> {
+    Utc::now() - Duration::days(repo.compact_days().into())
+}
+
+
+pub fn twinkle_compact_is_due(repo: &TwinkleRepository) -> bool {
+    repo.git.rev_list_count_before(twinkle_compact_cutoff(repo))
+        .is_ok_and(|count| count >= TWINKLE_COMPACT_THRESHOLD)
+}
+
+
+/// Creates the next generation of the branch and moves over to it.
+/// Returns its name, or nothing if there was nothing to compact.
+pub fn twinkle_compact(repo: &TwinkleRepository) -> Result<Option<GitReference>, Box<dyn Error>> {
+    if repo.read_only() {
+        return Err("Read-only. Compacting is up to a client that can push".into());
+    }
+
+    if repo.git.rev_parse_is_shallow() {
+        return Err("Only part of the history is on this machine. Run `twinkle fetch-history` first".into());
+    }
+
+    if twinkle_compact_next(repo).is_some() {
+        return Err("Already compacted. Moving over on the next sync".into());
+    }
+
+    if !repo.git.status_all()?.is_empty() || twinkle_has_unpushed_commits(repo) {
+        return Err("Sync all changes first".into());
+    }
+
+    let branch = repo.branch().ok_or("Not on a branch")?;
+    let remote = repo.remote(&branch);
+    let head = repo.current_head()?;
+
+    let history = repo.git.log_first_parent()?;
+    let (snapshots, count) = twinkle_compact_snapshots(&history, twinkle_compact_cutoff(repo), repo.compact_period());
+
+    if count <= snapshots.len() {
+        return Ok(None); // Nothing to gain
+    }
+
+    let user = repo.user().ok_or("Missing user")?;
+    let generation = twinkle_compact_generation(repo);
+    let next_branch = twinkle_compact_branch_name(&branch, generation);
+
+    let mut parent: Option<GitId> = None;
+
+    for snapshot in &snapshots {
+        let message = format!("Compacted {} changes from {} to {}",
+            snapshot.count,
+            snapshot.from.format("%Y-%m-%d"),
+            snapshot.to.format("%Y-%m-%d"),
+        );
+
+        parent = Some(repo.git.commit_tree(&snapshot.tree, parent.as_ref(), &user, snapshot.to, &message)?);
+    }
+
+    // Recent commits keep their files, author and time
+    for commit in &history[count..] {
+        let message = commit.message.to_string();
+        parent = Some(repo.git.commit_tree(&commit.tree, parent.as_ref(), &commit.author, commit.timestamp, &message)?);
+    }
+
+    let config_file = Path::new(COMMON_CONFIG_FILE);
+
+    repo.git.config_file_set(config_file, &key(K_GENERATION), &(generation + 1).to_string())?;
+    repo.git.add(config_file)?;
+
+    let tree = repo.git.write_tree()?;
+    let message = format!("Start generation {}", generation + 1);
+    let start = repo.git.commit_tree(&tree, parent.as_ref(), &user, Utc::now(), &message)?;
+
+    // Points clients on the old branch to the new one
+    repo.git.config_file_set(config_file, &key(K_NEXT_BRANCH), &next_branch)?;
+    repo.git.config_file_set(config_file, &key(K_NEXT_COMMIT), &start)?;
+    repo.git.add(config_file)?;
+    repo.git.commit(Some(user), &format!("Moved to `{next_branch}`"))?;
+
+    let refspecs = [
+        format!("{start}:refs/heads/{next_branch}"),
+        format!("HEAD:refs/heads/{branch}"),
+    ];
+
+    // Both or neither, so clients are never pointed to a branch that isn't there
+    if let Err(e) = repo.git.push_atomic(&remote, &refspecs) {
+        repo.git.reset_hard_to(&head)?;
+        return Err(format!("Could not push `{next_branch}`. Someone may have synced in the meantime: {e}").into());
+    }
+
+    log::info(&format!("✓ Compacted {count} commits into {} snapshots on `{next_branch}`", snapshots.len()));
+
+    twinkle_compact_follow(repo)?;
+    Ok(Some(next_branch))
+}
+
+
+/// The branch and commit a compacted branch points to
+pub fn twinkle_compact_next(repo: &TwinkleRepository) -> Option<(GitReference, GitId)> {
+    let config_file = Path::new(COMMON_CONFIG_FILE);
+
+    let branch = repo.git.config_file_get(config_file, &key(K_NEXT_BRANCH))?.stdout;
+    let commit = repo.git.config_file_get(config_file, &key(K_NEXT_COMMIT))?.stdout;
+
+    Some((branch, commit))
+}
+
+
+/// Moves over to the branch a compacted branch points to. Changes made since
+/// the compaction, synced or not, come along in one commit on top of it.
+pub fn twinkle_compact_follow(repo: &TwinkleRepository) -> Result<(), Box<dyn Error>> {
+    let (next_branch, start) = twinkle_compact_next(repo).ok_or("Not compacted")?;
+
+    let branch = repo.branch().ok_or("Not on a branch")?;
+    let remote = repo.remote(&branch);
+
+    if next_branch == branch || next_branch.starts_with('-') {
+        return Err(format!("`{next_branch}` is not a valid branch to move to").into());
+    }
+
+    repo.git.fetch(&remote, &next_branch)?;
+
+    // Another repository's branch could be at the same place
+    let next_config = format!("{start}:{COMMON_CONFIG_FILE}");
+    let next_id = repo.git.config_blob_get(&next_config, &key(K_ID)).map(|output| output.stdout);
+
+    if next_id.is_none() || next_id != repo.id() {
+        return Err(format!("`{next_branch}` is not part of this repository. Not moving over").into());
+    }
+
+    if !repo.git.merge_base(&start, &"FETCH_HEAD".into())? {
+        return Err(format!("`{start}` is not on `{next_branch}`. Not moving over").into());
+    }
+
+    let generation = repo.git.config_blob_get(&next_config, &key(K_GENERATION))
+        .map(|output| output.stdout)
+        .ok_or("Missing generation")?;
+
+    // Fetch again, now that the new branch is tracked. Clones made with --depth track only one
+    let fetch_key = format!("remote.{remote}.fetch");
+    let next_ref = format!("refs/heads/{next_branch}");
+
+    if !refspecs_cover(&repo.git.config_get_all(&fetch_key), &next_ref) {
+        let refspec = format!("+{next_ref}:refs/remotes/{remote}/{next_branch}");
+        repo.git.config_set_all(&fetch_key, &[&refspec])?;
+    }
+
+    repo.git.fetch(&remote, &next_branch)?;
+
+    let config_file = Path::new(COMMON_CONFIG_FILE);
+
+    repo.git.config_file_unset(config_file, &key(K_NEXT_BRANCH))?;
+    repo.git.config_file_unset(config_file, &key(K_NEXT_COMMIT))?;
+    repo.git.config_file_set(config_file, &key(K_GENERATION), &generation)?;
+    repo.git.add(config_file)?;
+
+    // The files as they are now. On top of the start of the new branch,
+    // they hold everything that happened on the old one since
+    let tree = repo.git.write_tree()?;
+    let start_tree = repo.git.rev_parse_verify(&format!("{start}^{{tree}}"))?;
+
+    let target =
+        if tree == start_tree || repo.read_only() {
+            start.clone()
+        } else {
+            let user = repo.user().ok_or("Missing user")?;
+            let message = format!("Brought over changes from `{branch}`");
+
+            repo.git.commit_tree(&tree, Some(&start), &user, Utc::now(), &message)?
+        };
+
+    repo.git.checkout_new_branch(&next_branch, &target)?;
+    repo.git.config_set(&format!("branch.{next_branch}.remote"), &remote)?;
+    repo.git.config_set(&format!("branch.{next_branch}.merge"), &format!("refs/heads/{next_branch}"))?;
+
+    // Lets Git clean up the old history
+    repo.git.branch_delete(&branch, false)?;
+    _ = repo.git.branch_delete(&format!("{remote}/{branch}"), true); // May not exist
+
+    if target != start {
+        repo.set_has_local_changes(true);
+    }
+
+    log::info(&format!("✓ Moved from `{branch}` to compacted branch `{next_branch}`"));
+    Ok(())
+}
+
+
+/// Starts at 1, and goes up with every compaction
+pub fn twinkle_compact_generation(repo: &TwinkleRepository) -> u32 {
+    repo.git.config_file_get(Path::new(COMMON_CONFIG_FILE), &key(K_GENERATION))
+        .and_then(|output| output.stdout.parse::<u32>().ok())
+        .unwrap_or(1)
+}
+
+
+/// Whether fetching with `refspecs` updates `git_ref`, like "+refs/heads/*:refs/remotes/origin/*" does
+fn refspecs_cover(refspecs: &[String], git_ref: &str) -> bool {
+    refspecs.iter().any(|refspec| {
+        let source = refspec.trim_start_matches('+').split(':').next().unwrap_or_default();
+
+        match source.split_once('*') {
+            Some((prefix, suffix)) => git_ref.starts_with(prefix) && git_ref[prefix.len()..].ends_with(suffix),
+            None => source == git_ref,
+        }
+    })
+}
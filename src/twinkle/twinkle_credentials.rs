@@ -0,0 +1,146 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::env;
+use std::error::Error;
+use std::fs::{ self, OpenOptions, Permissions };
+use std::os::unix::fs::{ OpenOptionsExt, PermissionsExt };
+use std::path::Path;
+
+use crate::git::objects::environment::GitEnvironment;
+use crate::git::objects::https_url::HttpsUrl;
+use crate::log;
+
+
+// Docs: https://git-scm.com/docs/gitcredentials
+//       https://git-scm.com/docs/git-credential#IOFMT
+//
+// Tokens are stored in a Git config file readable only by the user:
+//
+// [credential "https://git.example.com"]
+//     username = hbons
+//     password = glpat-…
+
+#[derive(Clone, Debug)]
+pub struct TwinkleCredential {
+    pub host: String, // "git.example.com:8443"
+    pub username: String,
+    pub token: String,
+}
+
+
+fn section(host: &str) -> String {
+    format!("credential.https://{host}")
+}
+
+
+pub fn twinkle_credential_get(file: &Path, host: &str) -> Option<TwinkleCredential> {
+    if !file.exists() {
+        return None;
+    }
+
+    let git = GitEnvironment::default();
+    let section = section(host);
+
+    Some(TwinkleCredential {
+        host: host.to_string(),
+        username: git.config_file_get(file, &format!("{section}.username"))?.stdout,
+        token: git.config_file_get(file, &format!("{section}.password"))?.stdout,
+    })
+}
+
+
+pub fn twinkle_credential_set(file: &Path, credential: &TwinkleCredential) -> Result<(), Box<dyn Error>> {
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // Create the file with the right permissions before anything is written to it
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .mode(0o600)
+        .open(file)?;
+
+    let git = GitEnvironment::default();
+    let section = section(&credential.host);
+
+    git.config_file_set(file, &format!("{section}.username"), &credential.username)?;
+    git.config_file_set(file, &format!("{section}.password"), &credential.token)?;
+
+    fs::set_permissions(file, Permissions::from_mode(0o600))?;
+    log::debug(&format!("Credentials | Stored token for `{}`", credential.host));
+
+    Ok(())
+}
+
+
+pub fn twinkle_credential_delete(file: &Path, host: &str) -> Result<(), Box<dyn Error>> {
+    GitEnvironment::default().config_file_remove_section(file, &section(host))?;
+    log::debug(&format!("Credentials | Deleted token for `{host}`"));
+
+    Ok(())
+}
+
+
+/// Answers a `git credential get` request
+///
+/// protocol=https
+/// host=git.example.com
+pub fn twinkle_credential_fill(file: &Path, request: &str) -> Option<String> {
+    let mut protocol = None;
+    let mut host = None;
+
+    for line in request.lines() {
+        match line.split_once('=') {
+            Some(("protocol", value)) => protocol = Some(value),
+            Some(("host", value)) => host = Some(value),
+            _ => (),
+        }
+    }
+
+    if protocol != Some("https") {
+        return None;
+    }
+
+    let credential = twinkle_credential_get(file, host?)?;
+
+    Some(format!(
+        "username={}\npassword={}\n",
+        credential.username,
+        credential.token,
+    ))
+}
+
+
+/// `credential.helper` value that points Git back to this binary
+pub fn twinkle_credential_helper() -> Result<String, Box<dyn Error>> {
+    let exe = env::current_exe()?;
+    Ok(format!("!\"{}\" credential", exe.display()))
+}
+
+
+/// Git can't prompt (GIT_TERMINAL_PROMPT=false), so it errors right away
+pub fn twinkle_credential_is_missing(error: &dyn Error) -> bool {
+    let message = error.to_string();
+
+    message.contains("terminal prompts disabled") ||
+    message.contains("Authentication failed")
+}
+
+
+/// Replaces Git's "terminal prompts disabled" error with something actionable
+pub fn twinkle_credential_error(url: &HttpsUrl, error: Box<dyn Error>) -> Box<dyn Error> {
+    if twinkle_credential_is_missing(&*error) {
+        return format!(
+            "No valid credentials for `{host}`. Store a token with: twinkle credential set https://{host} <username>",
+            host = url.host_and_port(),
+        ).into();
+    }
+
+    error
+}
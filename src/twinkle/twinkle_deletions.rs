@@ -0,0 +1,110 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+
+use crate::git::objects::change::GitChange;
+use crate::git::objects::id::GitId;
+use crate::log;
+
+use super::objects::repository::TwinkleRepository;
+use super::twinkle_pretty::twinkle_pretty_change_counts;
+
+
+// A drive that failed to mount or a stray `rm -rf` looks like every file was
+// deleted. Syncing that deletes them for everyone, so a commit that deletes too
+// many files stays local until it's confirmed with `twinkle confirm-deletions`.
+
+
+/// Deleting fewer files than this never pauses, whatever the percentage
+pub const TWINKLE_DELETIONS_MIN: usize = 10;
+
+
+/// Whether deleting `deleted` of `tracked` files goes over either limit. 0 turns a limit off
+pub fn twinkle_deletions_exceeded(
+    deleted: usize,
+    tracked: usize,
+    max_files: usize,
+    max_percent: u32,
+) -> bool
+{
+    let over_files = max_files > 0 && deleted > max_files;
+
+    let over_percent = max_percent > 0 &&
+        deleted >= TWINKLE_DELETIONS_MIN &&
+        deleted * 100 > tracked * max_percent as usize;
+
+    over_files || over_percent
+}
+
+
+/// Whether committing the staged changes deletes too many files.
+/// Counted like the commit message, so a rename is a deletion too.
+pub fn twinkle_deletions_check(repo: &TwinkleRepository, status: &[GitChange]) -> Result<bool, Box<dyn Error>> {
+    let (added, _, deleted) = twinkle_pretty_change_counts(status);
+
+    if deleted == 0 {
+        return Ok(false);
+    }
+
+    // The index has the staged changes already, so count as it was before
+    let tracked = repo.git.ls_files()?.lines().count();
+    let tracked = (tracked + deleted).saturating_sub(added);
+
+    Ok(twinkle_deletions_exceeded(deleted, tracked, repo.deletions_max_files(), repo.deletions_max_percent()))
+}
+
+
+/// Keeps `commit` from syncing until it's confirmed
+pub fn twinkle_deletions_pause(repo: &TwinkleRepository, commit: &GitId) -> Result<(), Box<dyn Error>> {
+    repo.set_deletions_paused(Some(commit))?;
+
+    log::warning(&format!(
+        "Paused. `{commit}` deletes too many files. \
+         Check the folder, then run `twinkle confirm-deletions` to sync it anyway"
+    ));
+
+    Ok(())
+}
+
+
+/// Whether syncing is paused, with a reminder if it is
+pub fn twinkle_deletions_is_paused(repo: &TwinkleRepository) -> bool {
+    let Some(commit) = repo.deletions_paused() else {
+        return false;
+    };
+
+    log::warning(&format!("Paused. `{commit}` deletes too many files. Run `twinkle confirm-deletions`"));
+    true
+}
+
+
+/// Lets the paused commit sync. Returns it.
+pub fn twinkle_deletions_confirm(repo: &TwinkleRepository) -> Result<GitId, Box<dyn Error>> {
+    let commit = repo.deletions_paused().ok_or("Not paused")?;
+    repo.set_deletions_paused(None)?;
+
+    log::info(&format!("✓ Confirmed the deletions in `{commit}`"));
+    Ok(commit)
+}
+
+
+/// Drops the paused commit, leaving the files as they are now. Files that are
+/// back, like after remounting a drive, are as if they were never deleted.
+pub fn twinkle_deletions_undo(repo: &TwinkleRepository) -> Result<GitId, Box<dyn Error>> {
+    let commit = repo.deletions_paused().ok_or("Not paused")?;
+
+    if repo.current_head()? != commit {
+        return Err(format!("Committed on top of `{commit}` since. Not undoing").into());
+    }
+
+    repo.git.reset_to(&format!("{commit}^"))?;
+    repo.set_deletions_paused(None)?;
+
+    log::info(&format!("✓ Undid `{commit}`"));
+    Ok(commit)
+}
@@ -0,0 +1,54 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+
+use crate::git::objects::reference::GitReference;
+use crate::git::objects::remote::GitRemote;
+use crate::log;
+
+use super::objects::repository::TwinkleRepository;
+
+
+// Clones only get the latest commit by default. Older history is fetched
+// when a merge needs it, or all at once with `twinkle fetch-history`.
+
+
+/// Fetches the older history when HEAD and FETCH_HEAD have no common ancestor
+/// to merge with. That means the commit this clone started from is no longer on
+/// the remote branch, like after a force push, so fetching --deepen can't reach
+/// what's behind it. Only fetching everything can.
+pub fn twinkle_history_deepen(
+    repo: &TwinkleRepository,
+    remote: &GitRemote,
+    branch: &GitReference,
+) -> Result<(), Box<dyn Error>>
+{
+    if !repo.git.rev_parse_is_shallow() ||
+       repo.git.merge_base_common("HEAD", "FETCH_HEAD").is_some() {
+        return Ok(());
+    }
+
+    log::info("No common history with the remote yet. Fetching older history…");
+    repo.git.fetch_unshallow(remote, branch)
+}
+
+
+/// Fetches all of the history. Returns false if it was already here.
+pub fn twinkle_history_fetch(repo: &TwinkleRepository) -> Result<bool, Box<dyn Error>> {
+    if !repo.git.rev_parse_is_shallow() {
+        return Ok(false);
+    }
+
+    let branch = repo.branch().ok_or("Not on a branch")?;
+    let remote = repo.remote(&branch);
+
+    repo.git.fetch_unshallow(&remote, &branch)?;
+
+    log::info("✓ Fetched all of the history");
+    Ok(true)
+}
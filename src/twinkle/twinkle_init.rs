@@ -10,10 +10,11 @@ use std::fs;
 use std::path::Path;
 
 use crate::ssh::keys::key_pair::KeyPair;
-use crate::ssh::objects::url::SshUrl;
+use crate::git::objects::remote_url::GitRemoteUrl;
 use crate::git::objects::user::GitUser;
 use crate::twinkle::defaults::common::COMMON_CONFIG_FILE;
 use crate::twinkle::defaults::config::{ K_ID, key };
+use crate::twinkle::twinkle_credentials::twinkle_credential_helper;
 use crate::twinkle::twinkle_util::twinkle_random_id;
 
 use super::objects::repository::TwinkleRepository;
@@ -25,7 +26,7 @@ use super::defaults::config::twinkle_default_git_settings;
 
 pub fn twinkle_init(
     path: &Path,
-    remote_url: &SshUrl,
+    remote_url: &GitRemoteUrl,
     key_pair: Option<&KeyPair>,
 ) -> Result<TwinkleRepository, Box<dyn Error>>
 {
@@ -124,6 +125,10 @@ pub fn twinkle_init_common(
         repo.set_core_ssh_command(key_pair)?;
     }
 
+    if repo.remote_url().is_some_and(|url| url.as_https().is_some()) {
+        repo.set_credential_helper(&twinkle_credential_helper()?)?;
+    }
+
     init_config(repo)?;
     init_info_attributes(repo)?;
     init_info_exclude(repo)?;
@@ -185,9 +190,9 @@ fn init_lfs(
 }
 
 
-pub fn init_welcome(url: &SshUrl) -> String {
+pub fn init_welcome(url: &GitRemoteUrl) -> String {
     format!(
         "# Hello!\nSync with `{}` was successfully set up.",
-        url.original
+        url.original()
     )
 }
@@ -7,39 +7,59 @@
 
 use std::error::Error;
 use std::fs;
-use std::path::Path;
+use std::os::unix::fs::symlink;
+use std::path::{ Path, PathBuf };
+
+use chrono::Utc;
 
 use crate::log;
 
 use crate::ssh::keys::prelude::*;
-use crate::ssh::keygen::ssh_keygen;
+use crate::ssh::agent::SshAgent;
+use crate::ssh::keygen::{
+    ssh_keygen,
+    ssh_keygen_certificate,
+    ssh_keygen_fingerprint,
+    ssh_keygen_fingerprint_key_pair,
+    ssh_keygen_is_encrypted,
+};
 use crate::ssh::objects::url::SshUrl;
+use crate::ssh::util::SshHostKeyChanged;
+
+use super::objects::repository::TwinkleRepository;
+use super::twinkle_pretty::twinkle_pretty_datetime;
+use super::twinkle_ssh::{ twinkle_ssh_forge_for, twinkle_ssh_scan_url };
 
 
 pub fn twinkle_keypair_new(host: &String, key_type: KeyType, keys_dir: &Path) -> Result<KeyPair, Box<dyn Error>> {
     let key_name = format!("{}.{}.key", host, key_type);
     let key_path = keys_dir.join(&key_name);
 
-    let key_pair = ssh_keygen(&key_path, KeyType::default(), None)?;
+    let key_pair = ssh_keygen(&key_path, key_type, Some(KeySize::default(key_type)), None)?;
     log::debug(&format!("Keys | Created key `{}`", key_pair.private_key_path.to_string_lossy()));
 
     Ok(key_pair)
 }
 
 
+/// The key for `host`, or a new one. A security key from ssh-agent of
+/// the same kind is used instead when there is one.
 pub fn twinkle_keypair_for(host: &String, key_type: KeyType, keys_dir: &Path) -> Result<KeyPair, Box<dyn Error>> {
-    let key_name = format!("{}.{}.key", host, key_type);
-    let key_path = keys_dir.join(&key_name);
+    let key_path = KeyType::all()
+        .into_iter()
+        .filter(|other| other.to_host_key_type() == key_type.to_host_key_type())
+        .map(|other| keys_dir.join(format!("{host}.{other}.key")))
+        .find(|path| path.exists());
 
     let key_pair;
 
-    if !key_path.exists() {
-        key_pair = twinkle_keypair_new(host, key_type, keys_dir)?;
-    } else {
+    if let Some(key_path) = key_path {
         let public_key_path = key_path.with_extension("key.pub");
         key_pair = KeyPair::from_files(&key_path, &public_key_path)?;
 
         log::debug(&format!("Keys | Found key `{}`", key_pair.private_key_path.to_string_lossy()));
+    } else {
+        key_pair = twinkle_keypair_new(host, key_type, keys_dir)?;
     }
 
     Ok(key_pair)
@@ -47,6 +67,10 @@ pub fn twinkle_keypair_for(host: &String, key_type: KeyType, keys_dir: &Path) ->
 
 
 pub fn twinkle_keypair_renew(host: &String, key_type: KeyType, keys_dir: &Path) -> Result<KeyPair, Box<dyn Error>> {
+    if key_type.is_security_key() {
+        return Err(format!("Security keys can't be renewed by Twinkle. Delete this one and add a new key with: twinkle keys agent {host}").into());
+    }
+
     let key_pair = twinkle_keypair_for(host, key_type, keys_dir)?;
     twinkle_keypair_delete(&key_pair)?;
     twinkle_keypair_new(host, key_type, keys_dir)
@@ -56,37 +80,325 @@ pub fn twinkle_keypair_renew(host: &String, key_type: KeyType, keys_dir: &Path)
 pub fn twinkle_keypair_delete(key_pair: &KeyPair) -> Result<(), Box<dyn Error>>{
     fs::remove_file(&key_pair.private_key_path)?;
     fs::remove_file(&key_pair.public_key_path)?;
+
+    if let Some(certificate) = &key_pair.certificate {
+        fs::remove_file(&certificate.path)?;
+    }
+
     log::debug(&format!("Keys | Deleted key `{}`", key_pair.private_key_path.to_string_lossy()));
     Ok(())
 }
 
 
-pub fn twinkle_hostkey_for(ssh_url: &SshUrl, key_type: KeyType, keys_dir: &Path) -> Result<HostKey, Box<dyn Error>> {
-    let mut host_key = HostKey {
-        host: ssh_url.host.clone(),
-        ..Default::default()
+/// Uses a key that's already in ssh-agent instead of generating one
+pub fn twinkle_keypair_from_agent(host: &str, public_key: &str, keys_dir: &Path) -> Result<KeyPair, Box<dyn Error>> {
+    if twinkle_keypair_find(host, keys_dir)?.is_some() {
+        return Err(format!("There already is a key for {host}. Delete it first with: twinkle keys delete {host}").into());
+    }
+
+    let key_type = KeyType::from_public_key(public_key)?;
+    let key_path = keys_dir.join(format!("{host}.{key_type}.key"));
+    let public_key_path = key_path.with_extension("key.pub");
+
+    // SSH and Git pick the matching private key from the agent
+    fs::create_dir_all(keys_dir)?;
+    fs::write(&key_path, format!("{public_key}\n"))?;
+    fs::write(&public_key_path, format!("{public_key}\n"))?;
+
+    log::debug(&format!("Keys | Using agent identity for `{host}`"));
+    KeyPair::from_files(&key_path, &public_key_path)
+}
+
+
+/// Loads a passphrase protected key into ssh-agent for the lifetime of this
+/// process. Keys that are already loaded are left alone. Returns whether it was loaded.
+pub fn twinkle_keypair_load(key_pair: &KeyPair, agent: Option<&SshAgent>) -> Result<bool, Box<dyn Error>> {
+    let needs_agent = key_pair.is_agent_identity() || ssh_keygen_is_encrypted(key_pair);
+
+    if !needs_agent {
+        return Ok(false);
+    }
+
+    let agent = agent.ok_or(
+        "Key needs ssh-agent, but it isn't running. Start it with: eval $(ssh-agent)"
+    )?;
+
+    if agent.contains(key_pair) {
+        return Ok(false);
+    }
+
+    if key_pair.is_agent_identity() {
+        return Err(format!("Agent identity `{}` is not loaded. Add it with: ssh-add",
+            key_pair.public_key_path.to_string_lossy()).into());
+    }
+
+    agent.add(key_pair)?;
+    agent.remove_on_exit(key_pair)?;
+
+    Ok(true)
+}
+
+
+/// Links a certificate signed for the key pair next to it, where ssh looks
+/// for it. Certificates that get renewed in place are picked up automatically.
+pub fn twinkle_keypair_set_certificate(key_pair: &KeyPair, certificate_path: &Path) -> Result<KeyPair, Box<dyn Error>> {
+    let certificate_path = fs::canonicalize(certificate_path)?;
+    let certificate = ssh_keygen_certificate(&certificate_path)?;
+
+    if certificate.fingerprint != Some(ssh_keygen_fingerprint_key_pair(key_pair)?) {
+        return Err(format!("Certificate `{}` wasn't signed for this key", certificate_path.to_string_lossy()).into());
+    }
+
+    let link_path = Certificate::path_for(&key_pair.private_key_path);
+
+    if link_path.symlink_metadata().is_ok() {
+        fs::remove_file(&link_path)?;
+    }
+
+    symlink(&certificate_path, &link_path)?;
+    log::debug(&format!("Keys | Using certificate `{}`", certificate_path.to_string_lossy()));
+
+    KeyPair::from_files(&key_pair.private_key_path, &key_pair.public_key_path)
+}
+
+
+/// An authentication error if the key pair's certificate can't be used right now.
+/// Retrying won't help, it needs a new certificate.
+pub fn twinkle_keypair_certificate_error(key_pair: &KeyPair) -> Option<Box<dyn Error>> {
+    let certificate = key_pair.certificate.as_ref()?;
+    let now = Utc::now();
+
+    if certificate.is_valid(now) {
+        return None;
+    }
+
+    let message = match (certificate.is_expired(now), certificate.valid_before, certificate.valid_after) {
+        (true, Some(before), _) => format!("expired on {}", twinkle_pretty_datetime(before.timestamp())),
+        (false, _, Some(after)) => format!("isn't valid until {}", twinkle_pretty_datetime(after.timestamp())),
+        _ => "isn't valid".to_string(),
     };
 
-    let key_path = keys_dir.join(host_key.to_file_name());
+    Some(format!(
+        "Authentication failed: SSH certificate \"{}\" {message}. Get a new one from your certificate authority",
+        certificate.key_id,
+    ).into())
+}
 
-    if key_path.exists() {
-        host_key = HostKey::from_file(&key_path)?;
-        log::debug(&format!("Keys | Found trusted host key `{}`", key_path.to_string_lossy()));
-    } else {
-        host_key = HostKey::for_known_host(ssh_url, key_type)?;
+
+/// All key pairs in `keys_dir`, with the host they belong to
+pub fn twinkle_keypair_list(keys_dir: &Path) -> Result<Vec<(String, KeyPair)>, Box<dyn Error>> {
+    let mut key_pairs = Vec::new();
+
+    if !keys_dir.exists() {
+        return Ok(key_pairs);
     }
 
+    for entry in fs::read_dir(keys_dir)? {
+        let key_path = entry?.path();
+        let Some(host) = keypair_host(&key_path) else { continue };
+
+        let public_key_path = key_path.with_extension("key.pub");
+
+        match KeyPair::from_files(&key_path, &public_key_path) {
+            Ok(key_pair) => key_pairs.push((host.to_string(), key_pair)),
+            Err(e) => log::warning(&format!("Could not read key `{}`: {e}", key_path.to_string_lossy())),
+        }
+    }
+
+    key_pairs.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(key_pairs)
+}
+
+
+/// "github.com.ed25519.key" -> "github.com"
+fn keypair_host(key_path: &Path) -> Option<String> {
+    let file_name = key_path.file_name()?.to_string_lossy();
+
+    let name = file_name.strip_suffix(".key")?;
+    let (host, key_type) = name.rsplit_once('.')?;
+    key_type.parse::<KeyType>().ok()?;
+
+    Some(host.to_string())
+}
+
+
+/// The existing key pair for a host, of any type
+pub fn twinkle_keypair_find(host: &str, keys_dir: &Path) -> Result<Option<KeyPair>, Box<dyn Error>> {
+    let key_pair = twinkle_keypair_list(keys_dir)?
+        .into_iter()
+        .find(|(key_host, _)| key_host == host)
+        .map(|(_, key_pair)| key_pair);
+
+    Ok(key_pair)
+}
+
+
+/// Repositories in `repos_home` with a remote on `host`
+pub fn twinkle_keypair_repos(host: &str, repos_home: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(repos_home) else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.join(".git").is_dir())
+        .filter(|path| {
+            TwinkleRepository::new(path)
+                .remote_url()
+                .is_some_and(|url| url.as_ssh().is_some_and(|url| url.host == host))
+        })
+        .collect();
+
+    paths.sort();
+    paths
+}
+
+
+/// A trusted host key matching the URL's host and port, or a pinned or scanned one
+pub fn twinkle_hostkey_for(
+    ssh_url: &SshUrl,
+    key_type: KeyType,
+    keys_dir: &Path,
+    forges_file: &Path,
+) -> Result<HostKey, Box<dyn Error>>
+{
+    let key_path = keys_dir.join(hostkey_file_name(&ssh_url.host, key_type));
+
+    let trusted = match key_path.exists() {
+        true => KnownHostsFile::from_file(&key_path)?.host_key(&ssh_url.host, ssh_url.port, key_type),
+        false => None,
+    };
+
+    let host_key = match trusted {
+        Some(host_key) => {
+            log::debug(&format!("Keys | Found trusted host key `{}`", key_path.to_string_lossy()));
+            host_key
+        },
+        None => {
+            let scan_url = twinkle_ssh_scan_url(ssh_url, keys_dir);
+            let forge = twinkle_ssh_forge_for(&scan_url.host, forges_file);
+
+            let mut host_key = HostKey::for_known_host(&scan_url, key_type, forge.as_ref())?;
+            host_key.host = ssh_url.host.clone();
+            host_key.port = ssh_url.port;
+            host_key
+        },
+    };
+
     // log::debug_struct(&host_key);
     Ok(host_key)
 }
 
 
+/// Whether the key is in the trust store, for the same host and port
+pub fn twinkle_hostkey_is_stored(host_key: &HostKey, keys_dir: &Path) -> bool {
+    KnownHostsFile::from_file(&keys_dir.join(host_key.to_file_name()))
+        .is_ok_and(|file| file
+            .host_key(&host_key.host, host_key.port, host_key.key_type)
+            .is_some_and(|stored| stored.public_key == host_key.public_key))
+}
+
+
+/// "github.com.ed25519.key.host", holds the trusted keys of one type for
+/// a host, with an entry for every port in use
+fn hostkey_file_name(host: &str, key_type: KeyType) -> PathBuf {
+    HostKey {
+        host: host.to_string(),
+        key_type,
+        ..Default::default()
+    }.to_file_name()
+}
+
+
+/// The host key type a host was set up with, read from the key file names
+pub fn twinkle_keytype_for(host: &str, keys_dir: &Path) -> Option<KeyType> {
+    KeyType::all().into_iter().find(|key_type| {
+        keys_dir.join(format!("{host}.{key_type}.key")).exists() ||
+        keys_dir.join(format!("{host}.{key_type}.key.host")).exists()
+    })
+    .map(|key_type| key_type.to_host_key_type())
+}
+
+
+/// Uses the key type the host was set up with, or negotiates one for new hosts
+pub fn twinkle_hostkey_negotiate(
+    ssh_url: &SshUrl,
+    keys_dir: &Path,
+    forges_file: &Path,
+) -> Result<HostKey, Box<dyn Error>>
+{
+    match twinkle_keytype_for(&ssh_url.host, keys_dir) {
+        Some(key_type) => twinkle_hostkey_for(ssh_url, key_type, keys_dir, forges_file),
+        None => {
+            let scan_url = twinkle_ssh_scan_url(ssh_url, keys_dir);
+            let forge = twinkle_ssh_forge_for(&scan_url.host, forges_file);
+
+            let mut host_key = HostKey::negotiate(&scan_url, forge.as_ref())?;
+            host_key.host = ssh_url.host.clone();
+            host_key.port = ssh_url.port;
+            log::debug(&format!("Keys | Negotiated key type `{}` for `{}`", host_key.key_type, ssh_url.host));
+
+            Ok(host_key)
+        }
+    }
+}
+
+
+/// Adds the key to the trust store, replacing the key for the same host and port
 pub fn twinkle_hostkey_trust(host_key: &HostKey, keys_dir: &Path) -> Result<(), Box<dyn Error>> {
     let key_name = host_key.to_file_name();
     let key_path = keys_dir.join(key_name);
 
-    fs::write(&key_path, host_key.to_string())?;
+    let mut file = match key_path.exists() {
+        true => KnownHostsFile::from_file(&key_path)?,
+        false => KnownHostsFile::default(),
+    };
+
+    file.set(host_key);
+
+    fs::create_dir_all(keys_dir)?;
+    file.write(&key_path)?;
     log::debug(&format!("Keys | Trusted host key `{}`", key_path.to_string_lossy()));
 
+    let changed_path = changed_path(host_key, keys_dir);
+
+    if changed_path.exists() {
+        fs::remove_file(changed_path)?;
+    }
+
+    Ok(())
+}
+
+
+/// "github.com.ed25519.key.host.changed", holds the fingerprint the host offered instead
+fn changed_path(host_key: &HostKey, keys_dir: &Path) -> PathBuf {
+    keys_dir.join(host_key.to_file_name()).with_extension("host.changed")
+}
+
+
+/// Remembers that the host offered a different key, which blocks syncing
+/// with that host until the new key is trusted
+pub fn twinkle_hostkey_set_changed(
+    host_key: &HostKey,
+    new: Option<&Fingerprint>,
+    keys_dir: &Path,
+) -> Result<(), Box<dyn Error>>
+{
+    let new = new.map(|f| f.to_string()).unwrap_or_default();
+    fs::write(changed_path(host_key, keys_dir), new)?;
+
+    log::debug(&format!("Keys | Host key for `{}` has changed", host_key.host));
     Ok(())
 }
+
+
+pub fn twinkle_hostkey_changed(host_key: &HostKey, keys_dir: &Path) -> Option<SshHostKeyChanged> {
+    let new = fs::read_to_string(changed_path(host_key, keys_dir)).ok()?;
+
+    Some(SshHostKeyChanged {
+        host: host_key.host.clone(),
+        old: host_key.fingerprint.clone().or_else(|| ssh_keygen_fingerprint(host_key).ok()),
+        new: new.trim().parse::<Fingerprint>().ok(),
+    })
+}
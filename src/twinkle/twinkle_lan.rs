@@ -0,0 +1,104 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::net::{ IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket };
+
+use socket2::{ Domain, Protocol, Socket, Type };
+
+use crate::log;
+
+use super::objects::repository::TwinkleRepository;
+use super::objects::repository_notify::TwinkleNotifyMessage;
+use super::twinkle_channel::twinkle_channel_receive;
+
+
+// Machines on the same network announce their pushes to each other over UDP,
+// using the same messages as the notification server. Opt-in, as anyone on
+// the network can see the channel names and commit hashes go by.
+
+
+pub fn twinkle_lan_address(repo: &TwinkleRepository) -> Result<SocketAddr, Box<dyn Error>> {
+    let address = repo.lan_address();
+    address.parse().map_err(|_| format!("Invalid LAN address `{address}`").into())
+}
+
+
+/// Binds to the port of `address`, joining its group if it's a multicast one.
+/// Other Twinkle processes on the same machine can bind to it too.
+pub fn twinkle_lan_bind(address: &SocketAddr) -> Result<UdpSocket, Box<dyn Error>> {
+    let socket = Socket::new(Domain::for_address(*address), Type::DGRAM, Some(Protocol::UDP))?;
+
+    socket.set_reuse_address(true)?;
+    #[cfg(unix)]
+    socket.set_reuse_port(true)?;
+
+    let any: IpAddr = match address {
+        SocketAddr::V4(_) => Ipv4Addr::UNSPECIFIED.into(),
+        SocketAddr::V6(_) => Ipv6Addr::UNSPECIFIED.into(),
+    };
+
+    socket.bind(&SocketAddr::new(any, address.port()).into())?;
+
+    match address.ip() {
+        IpAddr::V4(group) if group.is_multicast() => socket.join_multicast_v4(&group, &Ipv4Addr::UNSPECIFIED)?,
+        IpAddr::V6(group) if group.is_multicast() => socket.join_multicast_v6(&group, 0)?,
+        _ => {},
+    }
+
+    Ok(socket.into())
+}
+
+
+/// Flags remote changes when a peer with the same repository id announces
+/// a commit we don't have
+pub fn twinkle_lan_watch(repo: &TwinkleRepository) -> Result<(), Box<dyn Error>> {
+    let channel = repo.as_notify_channel_name().ok_or("Missing ID")?;
+    let address = twinkle_lan_address(repo)?;
+    let socket = twinkle_lan_bind(&address)?;
+
+    log::debug(&format!("LAN | Listening on {address}"));
+
+    let mut buffer = [0; 512];
+
+    loop {
+        let (length, peer) = socket.recv_from(&mut buffer)?;
+
+        let Ok(text) = std::str::from_utf8(&buffer[..length]) else {
+            continue;
+        };
+
+        if twinkle_channel_receive(repo, &channel, text) {
+            repo.set_has_remote_changes(true);
+            log::info(&format!("Remote changes announced by {}…", peer.ip()));
+        }
+    }
+}
+
+
+/// Tells peers on the network about the commit we're at
+pub fn twinkle_lan_announce(repo: &TwinkleRepository) -> Result<(), Box<dyn Error>> {
+    let channel = repo.as_notify_channel_name().ok_or("Missing ID")?;
+    let commit = repo.current_head()?;
+    let address = twinkle_lan_address(repo)?;
+
+    let socket = match address {
+        SocketAddr::V4(_) => UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?,
+        SocketAddr::V6(_) => UdpSocket::bind((Ipv6Addr::UNSPECIFIED, 0))?,
+    };
+
+    if address.is_ipv4() {
+        socket.set_broadcast(true)?;
+        socket.set_multicast_ttl_v4(1)?; // Stay on the local network
+    }
+
+    let message = TwinkleNotifyMessage::Announce(channel, commit.clone()).to_string();
+    socket.send_to(message.as_bytes(), address)?;
+
+    log::debug(&format!("LAN | Announced {commit} to {address}"));
+    Ok(())
+}
@@ -6,15 +6,25 @@
 
 
 use std::error::Error;
+use std::fs::{ self, File };
+use std::io::Read;
+use std::path::{ Path, PathBuf };
 
 use crate::git::objects::file_status::GitFileStatus;
 use crate::git::objects::change::GitChange;
+use crate::git::objects::lfs_file::GitLfsFile;
 use crate::log;
 
 use super::objects::repository::TwinkleRepository;
+use super::twinkle_pretty::twinkle_pretty_size;
 
 
 pub const TWINKLE_LFS_THRESHOLD: u64 = 1024 * 1024 * 3; // 3 MB;
+pub const TWINKLE_LFS_PATTERNS: [&str; 5] = ["*.psd", "*.blend", "*.mp4", "*.mov", "*.wav"];
+pub const TWINKLE_LFS_BINARY_MIN: u64 = 1024 * 100; // Smaller binaries, like icons, stay in Git
+
+const SNIFF_SIZE: usize = 8000; // Same as Git
+
 
 pub fn twinkle_lfs_track(
     repo: &TwinkleRepository,
@@ -28,18 +38,292 @@ pub fn twinkle_lfs_track(
         return Err("Nothing to track".into())
     }
 
-    let threshold = repo.lfs_size_threshold();
+    let Some(pattern) = twinkle_lfs_pattern_for(repo, &change.path) else {
+        return Ok(());
+    };
+
+    if repo.git.check_attr("filter", &change.path).as_deref() == Some("lfs") {
+        return Ok(()); // Already covered by a pattern
+    }
+
+    repo.git.lfs_track_pattern(&pattern, twinkle_lfs_is_lockable(repo, &change.path))?;
+
+    log::info(
+        &format!( // TODO: Move format! to info()
+            "Tracking with LFS: `{pattern}`",
+        )
+    );
+
+    Ok(())
+}
+
+
+/// What to track a new file with: a configured pattern it matches, "*.ext" for
+/// binary files, or its own path for large text files and binaries without
+/// an extension. Binaries get their own path too when other files with the same
+/// extension are already in regular Git history, as a pattern would change how
+/// those are stored. None if it can go into regular Git history.
+pub fn twinkle_lfs_pattern_for(repo: &TwinkleRepository, path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+
+    if name == ".gitattributes" {
+        return None;
+    }
+
+    if let Some(pattern) = repo.lfs_patterns().into_iter().find(|pattern| twinkle_lfs_pattern_matches(pattern, name)) {
+        return Some(pattern);
+    }
+
+    if repo.size_of(path) >= Some(TWINKLE_LFS_BINARY_MIN) && twinkle_lfs_is_binary(&repo.abs_path(path)) {
+        if let Some(extension) = path.extension().and_then(|extension| extension.to_str()) {
+            let pattern = format!("*.{extension}");
+
+            if !twinkle_lfs_has_plain_files(repo, &pattern, path) {
+                return Some(pattern);
+            }
+        }
+
+        return Some(path.to_string_lossy().to_string());
+    }
+
+    if repo.size_of(path) >= Some(repo.lfs_size_threshold()) {
+        return Some(path.to_string_lossy().to_string());
+    }
+
+    None
+}
+
+
+/// Whether files matching `pattern`, other than `path`, are tracked without LFS.
+/// When that can't be checked, it assumes they are.
+pub fn twinkle_lfs_has_plain_files(repo: &TwinkleRepository, pattern: &str, path: &Path) -> bool {
+    let Ok(tracked) = repo.git.ls_files_matching(pattern) else {
+        return true;
+    };
+
+    let tracked: Vec<PathBuf> = tracked.into_iter().filter(|file| file != path).collect();
+
+    if tracked.is_empty() {
+        return false;
+    }
+
+    let Ok(lfs_files) = repo.git.lfs_ls_files() else {
+        return true;
+    };
+
+    tracked.iter().any(|file| !lfs_files.contains(file))
+}
+
 
-    if repo.size_of(&change.path) >= Some(threshold) {
-        repo.git.lfs_track(&change.path)?;
+/// Whether the file matches one of `lfs.lockable`
+pub fn twinkle_lfs_is_lockable(repo: &TwinkleRepository, path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
 
-        log::info(
-            &format!( // TODO: Move format! to info()
-                "Tracking with LFS: `{}`",
-                change.path.to_string_lossy(),
-            )
-        );
+    repo.lfs_lockable().iter().any(|pattern| twinkle_lfs_pattern_matches(pattern, name))
+}
+
+
+/// Matches a file name against a pattern with `*` and `?` wildcards
+pub fn twinkle_lfs_pattern_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None; // Where to resume after a mismatch
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            },
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            },
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    star = Some((star_p, star_n + 1));
+                },
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+
+/// Whether there's a NUL byte near the start of the file, like Git checks
+pub fn twinkle_lfs_is_binary(path: &Path) -> bool {
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+
+    let mut buffer = Vec::with_capacity(SNIFF_SIZE);
+
+    match file.take(SNIFF_SIZE as u64).read_to_end(&mut buffer) {
+        Ok(_) => buffer.contains(&0),
+        Err(_) => false,
+    }
+}
+
+
+/// A per-path LFS entry in .gitattributes that a pattern can replace
+#[derive(Clone, Debug, PartialEq)]
+pub struct TwinkleLfsMigration {
+    pub path: String,
+    pub pattern: String,
+}
+
+
+/// Finds the per-path entries in .gitattributes that are covered by
+/// a configured pattern, or are binary files with an extension
+pub fn twinkle_lfs_migrations(repo: &TwinkleRepository) -> Result<Vec<TwinkleLfsMigration>, Box<dyn Error>> {
+    let attributes_path = repo.abs_path(Path::new(".gitattributes"));
+
+    if !attributes_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut migrations = Vec::new();
+
+    for line in fs::read_to_string(attributes_path)?.lines() {
+        let mut parts = line.split_whitespace();
+
+        let Some(entry) = parts.next() else {
+            continue;
+        };
+
+        if entry.starts_with('#') || !parts.any(|attribute| attribute == "filter=lfs") {
+            continue;
+        }
+
+        // Git LFS escapes spaces in paths
+        let path = entry.replace("[[:space:]]", " ");
+
+        if path.contains(['*', '?', '[']) {
+            continue; // Already a pattern
+        }
+
+        if let Some(pattern) = twinkle_lfs_pattern_for(repo, Path::new(path.trim_start_matches('/'))) {
+            if pattern != path.trim_start_matches('/') {
+                migrations.push(TwinkleLfsMigration { path, pattern });
+            }
+        }
+    }
+
+    Ok(migrations)
+}
+
+
+/// Replaces per-path entries in .gitattributes with patterns.
+/// The changes are committed on the next sync.
+pub fn twinkle_lfs_migrate(repo: &TwinkleRepository) -> Result<Vec<TwinkleLfsMigration>, Box<dyn Error>> {
+    let migrations = twinkle_lfs_migrations(repo)?;
+
+    for migration in &migrations {
+        repo.git.lfs_track_pattern(&migration.pattern, twinkle_lfs_is_lockable(repo, Path::new(&migration.path)))?;
+        repo.git.lfs_untrack(&migration.path)?;
+
+        log::info(&format!("Tracking with LFS: `{}` instead of `{}`", migration.pattern, migration.path));
+    }
+
+    Ok(migrations)
+}
+
+
+/// Fetches the objects that are checked out, and those of the last
+/// `lfs.recentDays` of history. Only in the selected folders, if any.
+pub fn twinkle_lfs_fetch(repo: &TwinkleRepository) -> Result<(), Box<dyn Error>> {
+    let selection = repo.select();
+
+    match repo.lfs_recent_days() {
+        0 => repo.git.lfs_fetch(&selection),
+        days => repo.git.lfs_fetch_recent(days, &selection),
+    }
+}
+
+
+/// Deletes old objects from .git/lfs, if `lfs.prune` is on.
+/// Objects that haven't been pushed yet are always kept.
+pub fn twinkle_lfs_prune(repo: &TwinkleRepository) -> Result<(), Box<dyn Error>> {
+    if !repo.lfs_prune() {
+        return Ok(());
+    }
+
+    let before = twinkle_lfs_store_size(repo);
+    repo.git.lfs_prune(repo.lfs_recent_days())?;
+    let after = twinkle_lfs_store_size(repo);
+
+    if after < before {
+        log::info(&format!("Pruned {} of LFS objects", twinkle_pretty_size(before - after)));
     }
 
     Ok(())
 }
+
+
+/// LFS file sizes in a top-level directory of the repository
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TwinkleLfsUsage {
+    pub dir: PathBuf, // "." for files in the root
+    pub local: u64, // Downloaded
+    pub remote: u64, // Tracked in HEAD
+}
+
+
+/// Adds up file sizes per top-level directory, largest first
+pub fn twinkle_lfs_usage(files: &[GitLfsFile]) -> Vec<TwinkleLfsUsage> {
+    let mut usage: Vec<TwinkleLfsUsage> = Vec::new();
+
+    for file in files {
+        let mut components = file.path.components();
+
+        let dir = match (components.next(), components.next()) {
+            (Some(first), Some(_)) => PathBuf::from(first.as_os_str()),
+            _ => PathBuf::from("."),
+        };
+
+        let index = match usage.iter().position(|entry| entry.dir == dir) {
+            Some(index) => index,
+            None => {
+                usage.push(TwinkleLfsUsage { dir, ..Default::default() });
+                usage.len() - 1
+            },
+        };
+
+        usage[index].remote += file.size;
+
+        if file.downloaded {
+            usage[index].local += file.size;
+        }
+    }
+
+    usage.sort_by(|a, b| b.remote.cmp(&a.remote).then_with(|| a.dir.cmp(&b.dir)));
+    usage
+}
+
+
+/// Everything in .git/lfs/objects, including old versions
+pub fn twinkle_lfs_store_size(repo: &TwinkleRepository) -> u64 {
+    fn dir_size(path: &Path) -> u64 {
+        let Ok(entries) = fs::read_dir(path) else {
+            return 0;
+        };
+
+        entries.flatten()
+            .map(|entry| match entry.file_type() {
+                Ok(file_type) if file_type.is_dir() => dir_size(&entry.path()),
+                Ok(_) => entry.metadata().map(|metadata| metadata.len()).unwrap_or(0),
+                Err(_) => 0,
+            })
+            .sum()
+    }
+
+    dir_size(&repo.abs_path(Path::new(".git/lfs/objects")))
+}
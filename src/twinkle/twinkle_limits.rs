@@ -0,0 +1,128 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::git::objects::change::GitChange;
+use crate::git::objects::file_status::GitFileStatus;
+use crate::ssh::objects::forge::{ KnownForge, KnownForges };
+
+use super::objects::repository::TwinkleRepository;
+use super::twinkle_lfs::twinkle_lfs_pattern_for;
+use super::twinkle_pretty::twinkle_pretty_size;
+use super::twinkle_ssh::twinkle_ssh_forge_for;
+
+
+// One huge file would otherwise fail every push, and hold up everyone's
+// changes with it. Files over a limit are left out until they shrink or
+// the limit is raised.
+
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TwinkleOversizeFile {
+    pub path: PathBuf,
+    pub size: u64,
+    pub limit: u64,
+    pub reason: String, // Where the limit comes from
+}
+
+
+impl fmt::Display for TwinkleOversizeFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` is {}, {}",
+            self.path.to_string_lossy(),
+            twinkle_pretty_size(self.size),
+            self.reason,
+        )
+    }
+}
+
+
+/// The lowest limit for a file and where it comes from. None if there's no limit
+pub fn twinkle_limit_for(
+    repo: &TwinkleRepository,
+    forge: Option<&KnownForge>,
+    is_lfs: bool,
+) -> Option<(u64, String)>
+{
+    let mut limits = Vec::new();
+
+    if let Some(limit) = repo.max_file_size() {
+        limits.push((limit, format!("twinkle.maxFileSize is {}", twinkle_pretty_size(limit))));
+    }
+
+    if is_lfs {
+        if let Some(limit) = repo.lfs_max_file_size() {
+            limits.push((limit, format!("twinkle.lfs.maxFileSize is {}", twinkle_pretty_size(limit))));
+        }
+
+        if let Some(forge) = forge {
+            if let Some(limit) = forge.lfs_max_file_size {
+                limits.push((limit, format!("{} allows up to {} with LFS", forge.host, twinkle_pretty_size(limit))));
+            }
+        }
+    } else if let Some(forge) = forge {
+        if let Some(limit) = forge.max_file_size {
+            limits.push((limit, format!("{} allows up to {} without LFS", forge.host, twinkle_pretty_size(limit))));
+        }
+    }
+
+    limits.into_iter().min_by_key(|(limit, _)| *limit)
+}
+
+
+/// Changed files that are over their limit
+pub fn twinkle_oversize_files(repo: &TwinkleRepository) -> Result<Vec<TwinkleOversizeFile>, Box<dyn Error>> {
+    let forge = forge_for(repo);
+    let mut oversize = Vec::new();
+
+    for change in repo.git.status_all()? {
+        let Some(size) = repo.size_of(&change.path) else {
+            continue; // Deleted
+        };
+
+        if let Some((limit, reason)) = twinkle_limit_for(repo, forge.as_ref(), is_lfs(repo, &change)) {
+            if size > limit {
+                oversize.push(TwinkleOversizeFile { path: change.path, size, limit, reason });
+            }
+        }
+    }
+
+    Ok(oversize)
+}
+
+
+/// Whether the file is, or will be, stored with LFS
+fn is_lfs(repo: &TwinkleRepository, change: &GitChange) -> bool {
+    if !repo.lfs_enabled() {
+        return false;
+    }
+
+    let is_new = [change.status_x.as_ref(), change.status_y.as_ref()]
+        .iter()
+        .any(|status| matches!(status, Some(GitFileStatus::Untracked | GitFileStatus::Added)));
+
+    // Like `twinkle_lfs_track`
+    if is_new && twinkle_lfs_pattern_for(repo, &change.path).is_some() {
+        return true;
+    }
+
+    repo.git.check_attr("filter", &change.path).as_deref() == Some("lfs")
+}
+
+
+fn forge_for(repo: &TwinkleRepository) -> Option<KnownForge> {
+    let remote_url = repo.remote_url()?;
+    let host = remote_url.host()?;
+
+    match repo.forges_file() {
+        Some(forges_file) => twinkle_ssh_forge_for(host, &forges_file),
+        None => KnownForges::builtin().get(host).cloned(),
+    }
+}
@@ -0,0 +1,164 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::fmt;
+use std::fs::{ self, Permissions };
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+use crate::git::objects::lfs_lock::GitLfsLock;
+use crate::git::objects::user::GitUser;
+use crate::log;
+
+use super::objects::repository::TwinkleRepository;
+use super::twinkle_lfs::twinkle_lfs_is_lockable;
+
+
+// Binary files can't be merged, so two people editing the same one ends
+// with a conflicted copy. Lockable files are read-only until locked, so
+// whoever holds the lock is the only one making changes.
+
+
+#[derive(Clone, Debug, Default)]
+pub struct TwinkleLock {
+    pub lock: GitLfsLock,
+    pub holder: Option<GitUser>, // From the commit history
+}
+
+
+impl fmt::Display for TwinkleLock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let holder = match &self.holder {
+            Some(user) => user.to_string(),
+            None => self.lock.owner.clone(),
+        };
+
+        match self.lock.ours {
+            true  => write!(f, "`{}` is locked by you", self.lock.path.display()),
+            false => write!(f, "`{}` is locked by {holder}", self.lock.path.display()),
+        }
+    }
+}
+
+
+pub fn twinkle_lock(repo: &TwinkleRepository, path: &Path) -> Result<(), Box<dyn Error>> {
+    if !is_lockable(repo, path) {
+        return Err(format!("`{}` is not lockable. Add a pattern to twinkle.lfs.lockable", path.display()).into());
+    }
+
+    repo.git.lfs_lock(path)?;
+    set_read_only(repo, path, false)?;
+
+    log::info(&format!("Locked `{}`", path.display()));
+    Ok(())
+}
+
+
+/// Files with changes that haven't been synced yet are only unlocked when forced
+pub fn twinkle_unlock(repo: &TwinkleRepository, path: &Path, force: bool) -> Result<(), Box<dyn Error>> {
+    if !force && repo.git.status_all()?.iter().any(|change| change.path == path) {
+        return Err(format!("`{}` has changes that haven't been synced yet", path.display()).into());
+    }
+
+    repo.git.lfs_unlock(path, force)?;
+
+    if is_lockable(repo, path) {
+        set_read_only(repo, path, true)?;
+    }
+
+    log::info(&format!("Unlocked `{}`", path.display()));
+    Ok(())
+}
+
+
+/// Locks on the server, with who holds them
+pub fn twinkle_locks(repo: &TwinkleRepository) -> Result<Vec<TwinkleLock>, Box<dyn Error>> {
+    let authors: Vec<GitUser> = repo.git.log(100)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|commit| commit.author)
+        .collect();
+
+    let locks = repo.git.lfs_locks()?
+        .into_iter()
+        .map(|lock| TwinkleLock {
+            holder: twinkle_lock_holder(&lock.owner, &authors),
+            lock,
+        })
+        .collect();
+
+    Ok(locks)
+}
+
+
+/// Finds the user behind a server name, like "hbons", by name or email
+pub fn twinkle_lock_holder(owner: &str, users: &[GitUser]) -> Option<GitUser> {
+    let owner = owner.to_lowercase();
+
+    users.iter().find(|user| {
+        let name = user.name().to_lowercase();
+        let email = user.email().to_lowercase();
+        let local_part = email.split('@').next().unwrap_or_default();
+
+        // GitHub's private addresses: "123+hbons@users.noreply.github.com"
+        let local_part = local_part.split_once('+').map(|(_, login)| login).unwrap_or(local_part);
+
+        name == owner || email == owner || local_part == owner
+    }).cloned()
+}
+
+
+/// Makes lockable files read-only, except the ones we hold the lock for
+pub fn twinkle_lock_apply(repo: &TwinkleRepository) -> Result<(), Box<dyn Error>> {
+    if repo.lfs_lockable().is_empty() {
+        return Ok(());
+    }
+
+    let user: Vec<GitUser> = repo.user().into_iter().collect();
+
+    // Not all servers can tell which locks are ours
+    let ours: Vec<_> = repo.git.lfs_locks()?
+        .into_iter()
+        .filter(|lock| lock.ours || twinkle_lock_holder(&lock.owner, &user).is_some())
+        .map(|lock| lock.path)
+        .collect();
+
+    for line in repo.git.ls_files()?.lines() {
+        let path = Path::new(line);
+
+        if twinkle_lfs_is_lockable(repo, path) {
+            _ = set_read_only(repo, path, !ours.iter().any(|p| p == path)); // May have been deleted
+        }
+    }
+
+    Ok(())
+}
+
+
+fn is_lockable(repo: &TwinkleRepository, path: &Path) -> bool {
+    twinkle_lfs_is_lockable(repo, path) ||
+    repo.git.check_attr("lockable", path).as_deref() == Some("set")
+}
+
+
+fn set_read_only(repo: &TwinkleRepository, path: &Path, read_only: bool) -> Result<(), Box<dyn Error>> {
+    let path = repo.abs_path(path);
+
+    let mode = fs::metadata(&path)?.permissions().mode();
+
+    let new_mode = match read_only {
+        true  => mode & !0o222, // Nobody can write
+        false => mode | 0o200, // Owner can write
+    };
+
+    if new_mode != mode {
+        fs::set_permissions(&path, Permissions::from_mode(new_mode))?;
+    }
+
+    Ok(())
+}
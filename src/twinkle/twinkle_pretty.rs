@@ -45,24 +45,51 @@ pub fn twinkle_pretty_datetime(seconds_from_epoch: i64) -> String {
 }
 
 
+/// 1536 -> "1.5 KB", 104857600 -> "100 MB"
+pub fn twinkle_pretty_size(bytes: u64) -> String {
+    let units = ["bytes", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+
+    while size >= 1024.0 && unit < units.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    let size = format!("{size:.1}");
+    format!("{} {}", size.trim_end_matches(".0"), units[unit])
+}
+
+
+/// Staged changes as (added, modified, deleted). Renames count as both
+pub fn twinkle_pretty_change_counts(status: &[GitChange]) -> (usize, usize, usize) {
+    status.iter()
+        .filter_map(change_counts)
+        .fold((0, 0, 0), |(a, m, d), (added, modified, deleted)| (a + added, m + modified, d + deleted))
+}
+
+
+fn change_counts(change: &GitChange) -> Option<(usize, usize, usize)> {
+    match change.status_x {
+        Some(GitFileStatus::Added)       => Some((1, 0, 0)),
+        Some(GitFileStatus::Modified)    => Some((0, 1, 0)),
+        Some(GitFileStatus::Deleted)     => Some((0, 0, 1)),
+        Some(GitFileStatus::Renamed(_))  => Some((1, 0, 1)),
+        Some(GitFileStatus::Copied(_))   => Some((1, 0, 0)),
+        _ => None, // Not staged, like skipped files
+    }
+}
+
+
 // '+10, ~7, -3'
 // '~ "README.md"'
 pub fn twinkle_pretty_commit_message(status: &Vec<GitChange>) -> Option<String> {
-    let (mut added, mut modified, mut deleted) = (0, 0, 0);
-    let mut file = String::new();
-
-    for change in status {
-        match change.status_x {
-            Some(GitFileStatus::Added)       => { added += 1; },
-            Some(GitFileStatus::Modified)    => { modified += 1; },
-            Some(GitFileStatus::Deleted)     => { deleted += 1; },
-            Some(GitFileStatus::Renamed(_))  => { deleted += 1; added += 1; },
-            Some(GitFileStatus::Copied(_))   => { added += 1; },
-            _ => ()
-        };
-
-        file = change.path.to_string_lossy().to_string();
-    }
+    let (added, modified, deleted) = twinkle_pretty_change_counts(status);
+
+    let file = status.iter()
+        .rfind(|change| change_counts(change).is_some())
+        .map(|change| change.path.to_string_lossy().to_string())
+        .unwrap_or_default();
 
     match added + modified + deleted {
         0 => None,
@@ -0,0 +1,74 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::Local;
+
+use crate::log;
+
+use super::objects::repository::TwinkleRepository;
+
+
+// Read-only repositories, like mirrors pulled with a read-only deploy key,
+// never commit. Local edits would otherwise block or diverge from the
+// remote's changes, so they're moved out of the way before merging.
+
+
+/// ".git/twinkle/aside", holds a folder of set aside edits per sync
+pub fn twinkle_read_only_aside_dir(repo: &TwinkleRepository) -> PathBuf {
+    repo.path.join(".git/twinkle/aside")
+}
+
+
+/// Moves local edits to a new folder in the aside dir and restores the
+/// last commit. With `twinkle.readonly.moveAside` off they're only reported.
+/// Returns the paths of the edits.
+pub fn twinkle_read_only_set_aside(repo: &TwinkleRepository) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let paths: Vec<PathBuf> = repo.git.status()?
+        .into_iter()
+        .map(|change| change.path)
+        .collect();
+
+    if paths.is_empty() {
+        return Ok(paths);
+    }
+
+    let list: Vec<String> = paths.iter().map(|path| path.display().to_string()).collect();
+
+    if !repo.read_only_move_aside() {
+        log::warning(&format!("Read-only | Local changes won't be synced: {}", list.join(", ")));
+        return Ok(paths);
+    }
+
+    let aside_dir = twinkle_read_only_aside_dir(repo)
+        .join(Local::now().format("%Y-%m-%d %H.%M.%S").to_string());
+
+    for path in &paths {
+        let source = repo.abs_path(path);
+
+        // Deleted files only need restoring
+        if source.symlink_metadata().is_err() {
+            continue;
+        }
+
+        let target = aside_dir.join(path);
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::rename(&source, &target)?;
+    }
+
+    repo.git.reset_hard()?;
+
+    log::info(&format!("Read-only | Moved local changes to `{}`: {}", aside_dir.display(), list.join(", ")));
+    Ok(paths)
+}
@@ -0,0 +1,282 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::ErrorKind;
+use std::net::{ TcpListener, TcpStream };
+use std::sync::{ Arc, Mutex };
+use std::sync::atomic::{ AtomicU64, AtomicUsize, Ordering };
+use std::sync::mpsc::{ SyncSender, TrySendError, sync_channel };
+use std::thread;
+use std::time::Duration;
+
+use tungstenite::{ accept_with_config, Message };
+use tungstenite::protocol::WebSocketConfig;
+
+use crate::log;
+
+use super::objects::repository_notify::{ TwinkleChannelName, TwinkleNotifyMessage };
+
+
+// A notification server for networks that can't reach the public one.
+// It only passes announcements between subscribers of the same channel.
+// Channel names are hashed repository ids and messages are commit hashes,
+// so it never learns anything about what's being synced.
+
+
+pub const RELAY_MAX_CONNECTIONS: usize = 1024; // Each has its own thread
+pub const RELAY_MAX_CHANNELS: usize = 16; // Per connection
+pub const RELAY_MAX_SUBSCRIBERS: usize = 256; // Per channel
+pub const RELAY_QUEUE_SIZE: usize = 16; // Per connection, announcements beyond it are dropped
+const RELAY_MAX_MESSAGE_SIZE: usize = 256;
+
+const RELAY_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const RELAY_READ_TIMEOUT: Duration = Duration::from_millis(250);
+
+
+#[derive(Clone, Debug)]
+pub struct TwinkleRelay {
+    channels: Arc<Mutex<HashMap<TwinkleChannelName, Vec<RelaySubscriber>>>>,
+    next_id: Arc<AtomicU64>,
+    connections: Arc<AtomicUsize>,
+    max_connections: usize,
+}
+
+
+#[derive(Clone, Debug)]
+struct RelaySubscriber {
+    id: u64,
+    sender: SyncSender<String>,
+}
+
+
+impl Default for TwinkleRelay {
+    fn default() -> Self {
+        Self::with_max_connections(RELAY_MAX_CONNECTIONS)
+    }
+}
+
+
+impl TwinkleRelay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+
+    pub fn with_max_connections(max_connections: usize) -> Self {
+        Self {
+            channels: Arc::default(),
+            next_id: Arc::default(),
+            connections: Arc::default(),
+            max_connections,
+        }
+    }
+
+
+    /// Errors when the channel is full, or the connection is on too many channels
+    pub fn subscribe(&self, channel: &TwinkleChannelName, id: u64, sender: &SyncSender<String>) -> Result<(), Box<dyn Error>> {
+        let mut channels = self.channels.lock().map_err(|_| "Poisoned lock")?;
+
+        let is_subscribed = |subscribers: &Vec<RelaySubscriber>| subscribers.iter().any(|subscriber| subscriber.id == id);
+
+        if channels.get(channel).is_some_and(is_subscribed) {
+            return Ok(());
+        }
+
+        if channels.values().filter(|subscribers| is_subscribed(subscribers)).count() >= RELAY_MAX_CHANNELS {
+            return Err(format!("Connection has the maximum of {RELAY_MAX_CHANNELS} channels").into());
+        }
+
+        let subscribers = channels.entry(channel.clone()).or_default();
+
+        if subscribers.len() >= RELAY_MAX_SUBSCRIBERS {
+            return Err(format!("Channel has the maximum of {RELAY_MAX_SUBSCRIBERS} subscribers").into());
+        }
+
+        subscribers.push(RelaySubscriber { id, sender: sender.clone() });
+        Ok(())
+    }
+
+
+    pub fn unsubscribe(&self, channel: &TwinkleChannelName, id: u64) {
+        if let Ok(mut channels) = self.channels.lock() {
+            if let Some(subscribers) = channels.get_mut(channel) {
+                subscribers.retain(|subscriber| subscriber.id != id);
+
+                if subscribers.is_empty() {
+                    channels.remove(channel);
+                }
+            }
+        }
+    }
+
+
+    /// Unsubscribes a connection from every channel
+    pub fn remove(&self, id: u64) {
+        if let Ok(mut channels) = self.channels.lock() {
+            for subscribers in channels.values_mut() {
+                subscribers.retain(|subscriber| subscriber.id != id);
+            }
+
+            channels.retain(|_, subscribers| !subscribers.is_empty());
+        }
+    }
+
+
+    /// Passes the message on to the channel's other subscribers.
+    /// Returns how many it was queued for.
+    pub fn announce(&self, channel: &TwinkleChannelName, message: &str, from_id: u64) -> usize {
+        let Ok(mut channels) = self.channels.lock() else {
+            return 0;
+        };
+
+        let Some(subscribers) = channels.get_mut(channel) else {
+            return 0;
+        };
+
+        let text = TwinkleNotifyMessage::Announce(channel.clone(), message.to_string()).to_string();
+        let mut count = 0;
+
+        subscribers.retain(|subscriber| {
+            if subscriber.id == from_id {
+                return true;
+            }
+
+            match subscriber.sender.try_send(text.clone()) {
+                Ok(_) => { count += 1; true },
+                Err(TrySendError::Full(_)) => true, // Slow reader, it'll catch up by polling
+                Err(TrySendError::Disconnected(_)) => false,
+            }
+        });
+
+        count
+    }
+
+
+    pub fn subscriber_count(&self, channel: &TwinkleChannelName) -> usize {
+        self.channels.lock()
+            .map(|channels| channels.get(channel).map_or(0, Vec::len))
+            .unwrap_or(0)
+    }
+
+
+    pub fn connection_count(&self) -> usize {
+        self.connections.load(Ordering::Acquire)
+    }
+
+
+    /// Counts a new connection. False when there are too many already
+    fn connect(&self) -> bool {
+        self.connections
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                (count < self.max_connections).then_some(count + 1)
+            })
+            .is_ok()
+    }
+
+
+    fn disconnect(&self) {
+        self.connections.fetch_sub(1, Ordering::AcqRel);
+    }
+
+
+    fn next_id(&self) -> u64 {
+        self.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+}
+
+
+/// Listens on `address`, like "0.0.0.0:8080", until the process is stopped
+pub fn twinkle_relay_listen(address: &str) -> Result<(), Box<dyn Error>> {
+    let listener = TcpListener::bind(address)
+        .map_err(|e| format!("Could not listen on `{address}`: {e}"))?;
+
+    log::info(&format!("Relay | Listening on {}", listener.local_addr()?));
+    twinkle_relay_serve(&listener, &TwinkleRelay::new())
+}
+
+
+/// Handles each connection on its own thread, up to the relay's maximum.
+/// Connections beyond it are closed right away.
+pub fn twinkle_relay_serve(listener: &TcpListener, relay: &TwinkleRelay) -> Result<(), Box<dyn Error>> {
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                log::debug(&format!("Relay | {e}"));
+                continue;
+            },
+        };
+
+        if !relay.connect() {
+            log::debug("Relay | Too many connections. Closing the new one");
+            continue; // Dropping the stream closes it
+        }
+
+        let relay = relay.clone();
+
+        thread::spawn(move || {
+            let id = relay.next_id();
+
+            if let Err(e) = twinkle_relay_connection(&relay, stream, id) {
+                log::debug(&format!("Relay | Connection {id}: {e}"));
+            }
+
+            relay.remove(id);
+            relay.disconnect();
+        });
+    }
+
+    Ok(())
+}
+
+
+fn twinkle_relay_connection(relay: &TwinkleRelay, stream: TcpStream, id: u64) -> Result<(), Box<dyn Error>> {
+    let config = WebSocketConfig::default()
+        .max_message_size(Some(RELAY_MAX_MESSAGE_SIZE))
+        .max_frame_size(Some(RELAY_MAX_MESSAGE_SIZE));
+
+    // Don't let a silent client hold on to the thread
+    stream.set_read_timeout(Some(RELAY_HANDSHAKE_TIMEOUT))?;
+    let mut socket = accept_with_config(stream, Some(config)).map_err(|e| e.to_string())?;
+    socket.get_ref().set_read_timeout(Some(RELAY_READ_TIMEOUT))?;
+
+    let (sender, receiver) = sync_channel::<String>(RELAY_QUEUE_SIZE);
+
+    loop {
+        while let Ok(text) = receiver.try_recv() {
+            socket.send(Message::text(text))?;
+        }
+
+        let text = match socket.read() {
+            Ok(Message::Text(text)) => text,
+            Ok(_) => continue, // Pings are answered when reading
+            Err(tungstenite::Error::Io(e)) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => continue,
+            Err(tungstenite::Error::ConnectionClosed) => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+
+        match text.as_str().parse::<TwinkleNotifyMessage>() {
+            Ok(TwinkleNotifyMessage::Subscribe(channel)) => {
+                if let Err(e) = relay.subscribe(&channel, id, &sender) {
+                    socket.close(None)?;
+                    return Err(e);
+                }
+            },
+            Ok(TwinkleNotifyMessage::Unsubscribe(channel)) => relay.unsubscribe(&channel, id),
+            Ok(TwinkleNotifyMessage::Announce(channel, message)) => {
+                let count = relay.announce(&channel, &message, id);
+                log::debug(&format!("Relay | Announced to {count} subscriber(s)"));
+            },
+            Err(e) => {
+                socket.close(None)?;
+                return Err(e);
+            },
+        }
+    }
+}
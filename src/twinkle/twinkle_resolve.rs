@@ -106,7 +106,7 @@ pub fn twinkle_resolve(
     }
 
     if repo.lfs_enabled() {
-        for change in repo.git.status()? {
+        for change in repo.git.status_all()? {
             // Discard any errors (file may have been deleted)
             _ = twinkle_lfs_track(repo, &change);
         }
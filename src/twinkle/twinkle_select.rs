@@ -0,0 +1,146 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::path::{ Component, Path, PathBuf };
+
+use crate::log;
+
+use super::defaults::common::COMMON_CONFIG_FILE;
+use super::objects::repository::TwinkleRepository;
+use super::twinkle_lfs::twinkle_lfs_fetch;
+
+
+// Large repositories don't need to be on every machine in full. Only the
+// selected folders are checked out, and the files in the root. The rest
+// is skipped by Git, so it's never seen as deleted.
+
+
+/// A folder relative to the repository root, like "art/covers"
+pub fn twinkle_select_normalize(dir: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let mut normalized = PathBuf::new();
+
+    for component in dir.components() {
+        match component {
+            Component::Normal(name) => normalized.push(name),
+            Component::CurDir => {},
+            _ => return Err(format!("`{}` is not a folder in the repository", dir.display()).into()),
+        }
+    }
+
+    if normalized.as_os_str().is_empty() || normalized.starts_with(".git") {
+        return Err(format!("`{}` is not a folder in the repository", dir.display()).into());
+    }
+
+    if normalized.to_string_lossy().contains(',') {
+        return Err("Folder names with commas can't be selected".into()); // Separator for LFS
+    }
+
+    Ok(normalized)
+}
+
+
+/// Whether `path` is checked out. Files in the root and Twinkle's own always are
+pub fn twinkle_select_contains(selection: &[PathBuf], path: &Path) -> bool {
+    if selection.is_empty() ||
+       path.parent().is_none_or(|parent| parent.as_os_str().is_empty()) ||
+       path.starts_with(config_dir()) {
+        return true;
+    }
+
+    selection.iter().any(|dir| path.starts_with(dir))
+}
+
+
+pub fn twinkle_select_add(repo: &TwinkleRepository, dir: &Path) -> Result<(), Box<dyn Error>> {
+    let dir = twinkle_select_normalize(dir)?;
+    let mut selection = repo.select();
+
+    if selection.iter().any(|selected| dir.starts_with(selected)) {
+        return Ok(());
+    }
+
+    selection.retain(|selected| !selected.starts_with(&dir)); // Now included
+    selection.push(dir.clone());
+    selection.sort();
+
+    repo.set_select(&selection)?;
+    twinkle_select_apply(repo)?;
+
+    if repo.lfs_enabled() {
+        twinkle_lfs_fetch(repo)?;
+    }
+
+    log::info(&format!("Selected `{}`", dir.display()));
+    Ok(())
+}
+
+
+/// Files in the folder are removed from this machine, not from the repository.
+/// Refuses when they have changes that haven't been synced yet.
+pub fn twinkle_select_remove(repo: &TwinkleRepository, dir: &Path) -> Result<(), Box<dyn Error>> {
+    let dir = twinkle_select_normalize(dir)?;
+    let mut selection = repo.select();
+
+    if !selection.contains(&dir) {
+        return Err(format!("`{}` is not selected", dir.display()).into());
+    }
+
+    if repo.git.status_all()?.iter().any(|change| change.path.starts_with(&dir)) {
+        return Err(format!("`{}` has changes that haven't been synced yet", dir.display()).into());
+    }
+
+    selection.retain(|selected| *selected != dir);
+
+    if selection.is_empty() {
+        return Err("Can't remove the last selected folder. Use `twinkle select all` instead".into());
+    }
+
+    repo.set_select(&selection)?;
+    twinkle_select_apply(repo)?;
+
+    log::info(&format!("Unselected `{}`", dir.display()));
+    Ok(())
+}
+
+
+/// Checks out everything again
+pub fn twinkle_select_all(repo: &TwinkleRepository) -> Result<(), Box<dyn Error>> {
+    repo.set_select(&[])?;
+    twinkle_select_apply(repo)?;
+
+    if repo.lfs_enabled() {
+        twinkle_lfs_fetch(repo)?;
+    }
+
+    Ok(())
+}
+
+
+/// Makes the checkout match the selection in .git/config
+pub fn twinkle_select_apply(repo: &TwinkleRepository) -> Result<(), Box<dyn Error>> {
+    let selection = repo.select();
+
+    if selection.is_empty() {
+        if repo.abs_path(Path::new(".git/info/sparse-checkout")).exists() {
+            repo.git.sparse_checkout_disable()?;
+        }
+
+        return Ok(());
+    }
+
+    let mut dirs = selection;
+    dirs.push(config_dir());
+
+    repo.git.sparse_checkout_set(&dirs)
+}
+
+
+/// ".twinkle"
+fn config_dir() -> PathBuf {
+    Path::new(COMMON_CONFIG_FILE).parent().map(Path::to_path_buf).unwrap_or_default()
+}
@@ -0,0 +1,167 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use crate::log;
+
+use crate::ssh::keys::prelude::*;
+use crate::ssh::objects::config::SshConfig;
+use crate::ssh::objects::config_file::{ SshConfigFile, SshConfigHost };
+use crate::ssh::objects::forge::{ KnownForge, KnownForges };
+use crate::ssh::objects::url::SshUrl;
+
+use super::twinkle_keys::twinkle_hostkey_trust;
+
+
+/// "github.com.ssh_config", holds the imported settings for a host
+fn config_path(host: &str, keys_dir: &Path) -> PathBuf {
+    keys_dir.join(format!("{host}.ssh_config"))
+}
+
+
+/// The built-in forges, and the ones added in the forges file, like "~/.config/twinkle/forges"
+pub fn twinkle_ssh_forges(forges_file: &Path) -> KnownForges {
+    KnownForges::load(forges_file)
+}
+
+
+pub fn twinkle_ssh_forge_for(host: &str, forges_file: &Path) -> Option<KnownForge> {
+    twinkle_ssh_forges(forges_file).get(host).cloned()
+}
+
+
+/// How to reach the host in `url`. Twinkle keeps its own host key files, so host
+/// keys are always looked up by the host name and port in the remote URL.
+pub fn twinkle_ssh_config_for(url: &SshUrl, keys_dir: &Path) -> SshConfig {
+    let host = url.host.as_str();
+    let path = config_path(host, keys_dir);
+
+    let config = match path.exists() {
+        true => SshConfigFile::from_file(&path)
+            .and_then(|file| file.to_config(host))
+            .unwrap_or_else(|e| {
+                log::warning(&format!("Could not read `{}`: {e}", path.to_string_lossy()));
+                SshConfig::default()
+            }),
+        false => SshConfig::default(),
+    };
+
+    SshConfig {
+        HostKeyAlias: Some(KnownHostsFile::host_name(host, url.port)),
+        ..config
+    }
+}
+
+
+/// Copies the settings for `host` from an OpenSSH config file, like ~/.ssh/config.
+/// Returns the settings as found, including any HostKeyAlias.
+pub fn twinkle_ssh_config_import(
+    host: &str,
+    user_config: &Path,
+    keys_dir: &Path,
+) -> Result<SshConfig, Box<dyn Error>>
+{
+    let config = SshConfigFile::from_file(user_config)?.to_config(host)?;
+
+    // HostKeyAlias only matters for finding the host in known_hosts
+    let block = SshConfigHost::from_config(host, &SshConfig {
+        HostKeyAlias: None,
+        ..config.clone()
+    });
+
+    let path = config_path(host, keys_dir);
+
+    if block.options.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path)?;
+        }
+
+        log::debug(&format!("Keys | No SSH settings for `{host}` in `{}`", user_config.to_string_lossy()));
+        return Ok(config);
+    }
+
+    fs::create_dir_all(keys_dir)?;
+    fs::write(&path, block.to_string())?;
+
+    log::debug(&format!("Keys | Imported SSH settings for `{host}` from `{}`", user_config.to_string_lossy()));
+    Ok(config)
+}
+
+
+/// The name OpenSSH would store the host key under in known_hosts:
+/// "github.com", "[git.example.org]:2222" or the HostKeyAlias
+pub fn twinkle_ssh_known_hosts_name(host: &str, config: &SshConfig) -> String {
+    if let Some(alias) = &config.HostKeyAlias {
+        return alias.clone();
+    }
+
+    let name = config.HostName.as_deref().unwrap_or(host);
+    KnownHostsFile::host_name(name, config.Port)
+}
+
+
+/// Trusts the keys for `host` found in an OpenSSH known_hosts file, one per key type
+pub fn twinkle_ssh_known_hosts_import(
+    host: &str,
+    config: &SshConfig,
+    known_hosts: &Path,
+    keys_dir: &Path,
+) -> Result<Vec<HostKey>, Box<dyn Error>>
+{
+    let name = twinkle_ssh_known_hosts_name(host, config);
+    let file = KnownHostsFile::from_file(known_hosts)?;
+
+    let mut host_keys: Vec<HostKey> = Vec::new();
+
+    for mut host_key in file.host_keys(&name, None) {
+        // The first one wins like in ssh
+        if host_keys.iter().any(|k| k.key_type == host_key.key_type) {
+            continue;
+        }
+
+        host_key.host = host.to_string();
+        twinkle_hostkey_trust(&host_key, keys_dir)?;
+        host_keys.push(host_key);
+    }
+
+    if host_keys.is_empty() {
+        return Err(format!("No host keys for {name} in `{}`", known_hosts.to_string_lossy()).into());
+    }
+
+    Ok(host_keys)
+}
+
+
+/// Where to scan for host keys, with the imported host name and port applied
+pub fn twinkle_ssh_scan_url(url: &SshUrl, keys_dir: &Path) -> SshUrl {
+    let config = twinkle_ssh_config_for(url, keys_dir);
+
+    SshUrl {
+        host: config.HostName.unwrap_or_else(|| url.host.clone()),
+        port: url.port.or(config.Port),
+        ..url.clone()
+    }
+}
+
+
+/// An OpenSSH config block that connects the same way Twinkle does, for use
+/// with plain ssh and git
+pub fn twinkle_ssh_config_export(url: &SshUrl, key_pair: &KeyPair, keys_dir: &Path) -> SshConfigHost {
+    let config = twinkle_ssh_config_for(url, keys_dir);
+    let mut block = SshConfigHost::from_config(&url.host, &config);
+
+    block.options.extend([
+        ("IdentityFile".into(), key_pair.private_key_path.to_string_lossy().to_string()),
+        ("IdentitiesOnly".into(), "yes".into()),
+        ("UserKnownHostsFile".into(), key_pair.private_key_path.with_extension("key.host").to_string_lossy().to_string()),
+    ]);
+
+    block
+}
@@ -7,24 +7,53 @@
 
 use std::env::consts::OS;
 use std::error::Error;
+use std::path::{ Path, PathBuf };
 use std::thread;
 use std::time::Duration;
 
 use chrono::Utc;
 
+use crate::git::objects::remote_url::GitRemoteUrl;
 use crate::log;
-use crate::ssh::util::ssh_util_test_connection;
+use crate::ssh::agent::SshAgent;
+use crate::ssh::keygen::ssh_keygen_fingerprint;
+
+use crate::ssh::util::{
+    SshHostKeyChanged,
+    ssh_util_is_host_key_changed,
+    ssh_util_offered_fingerprint,
+    ssh_util_test_connection,
+};
+
 use crate::twinkle::twinkle_init::init_id;
 
 use super::objects::repository::TwinkleRepository;
 use super::defaults::common::twinkle_default_sync_up_delay_max;
 use super::defaults::common::twinkle_default_sync_up_delay_bump;
+use super::twinkle_channel::{ twinkle_channel_announce, twinkle_channel_watch };
+use super::twinkle_compact::{ twinkle_compact, twinkle_compact_follow, twinkle_compact_is_due, twinkle_compact_next };
+use super::twinkle_credentials::{ twinkle_credential_error, twinkle_credential_helper, twinkle_credential_is_missing };
+use super::twinkle_deletions::{ twinkle_deletions_check, twinkle_deletions_is_paused, twinkle_deletions_pause };
+use super::twinkle_history::twinkle_history_deepen;
 use super::twinkle_init::twinkle_init_common;
-use super::twinkle_keys::twinkle_hostkey_for;
-use super::twinkle_lfs::twinkle_lfs_track;
+use super::twinkle_keys::{
+    twinkle_hostkey_changed,
+    twinkle_hostkey_for,
+    twinkle_hostkey_set_changed,
+    twinkle_keypair_certificate_error,
+    twinkle_keypair_load,
+};
+use super::twinkle_lan::{ twinkle_lan_announce, twinkle_lan_watch };
+use super::twinkle_lfs::{ twinkle_lfs_fetch, twinkle_lfs_prune, twinkle_lfs_track };
+use super::twinkle_lock::twinkle_lock_apply;
+use super::twinkle_select::twinkle_select_contains;
+use super::twinkle_trash::{ twinkle_trash_expire, twinkle_trash_incoming };
+use super::twinkle_limits::{ TwinkleOversizeFile, twinkle_oversize_files };
 use super::twinkle_notify::twinkle_notify;
 use super::twinkle_resolve::twinkle_resolve_changes;
 use super::twinkle_pretty::twinkle_pretty_commit_message;
+use super::twinkle_read_only::twinkle_read_only_set_aside;
+use super::twinkle_ssh::{ twinkle_ssh_config_for, twinkle_ssh_forge_for };
 use super::twinkle_util::twinkle_ssh_command;
 
 
@@ -48,13 +77,30 @@ pub fn twinkle_sync_prepare(
     }
 
     let user = repo.user().ok_or("Missing user")?;
+    let remote_url = repo.remote_url().ok_or("Missing remote_url")?;
 
-    if let Some(key_pair) = &user.key_pair {
-        let host_key = twinkle_hostkey_for(
-            &repo.remote_url().ok_or("Missing remote_url")?,
-            key_pair.key_type,
-            key_pair.private_key_path.parent().ok_or("No parent")?
-        )?;
+    if remote_url.as_https().is_some() {
+        // Keep the helper pointing at the current binary
+        repo.set_credential_helper(&twinkle_credential_helper()?)?;
+    }
+
+    let Some(ssh_url) = remote_url.as_ssh() else {
+        log::debug(&format!("Remote `{remote_url}` doesn't use SSH. Skipping SSH setup"));
+        return Ok(());
+    };
+
+    if let Some(key_pair) = &user.key_pair.clone().or_else(|| repo.key_pair()) {
+        let keys_dir = key_pair.private_key_path.parent().ok_or("No parent")?;
+        let forges_file = repo.forges_file().unwrap_or_default(); // Only the built-in ones if not set
+        let host_key = twinkle_hostkey_for(ssh_url, key_pair.key_type.to_host_key_type(), keys_dir, &forges_file)?;
+
+        if let Some(change) = twinkle_hostkey_changed(&host_key, keys_dir) {
+            return Err(host_key_changed_error(change));
+        }
+
+        if let Some(e) = twinkle_keypair_certificate_error(key_pair) {
+            return Err(e);
+        }
 
         twinkle_init_common(repo, Some(key_pair))?;
 
@@ -63,12 +109,22 @@ pub fn twinkle_sync_prepare(
         repo.set_commit_gpg_sign(true)?;
         repo.set_tag_gpg_sign(true)?;
 
-        repo.git.GIT_SSH_COMMAND = twinkle_ssh_command(Some(key_pair));
+        repo.git.GIT_SSH_COMMAND = twinkle_ssh_command(Some(key_pair), Some(ssh_url));
 
-        let remote_url = repo.remote_url().ok_or("Missing remote_url")?;
-        ssh_util_test_connection(&remote_url, &host_key, Some(key_pair))?;
+        let host_config = twinkle_ssh_config_for(ssh_url, keys_dir);
+        let forge = twinkle_ssh_forge_for(&ssh_url.host, &forges_file);
 
-        log::debug(&format!("✓ Authenticated to {}", remote_url.host));
+        if let Err(e) = ssh_util_test_connection(ssh_url, &host_key, Some(key_pair), &host_config, forge.as_ref()) {
+            return match e.downcast::<SshHostKeyChanged>() {
+                Ok(change) => {
+                    twinkle_hostkey_set_changed(&host_key, change.new.as_ref(), keys_dir)?;
+                    Err(host_key_changed_error(*change))
+                },
+                Err(e) => Err(e),
+            };
+        }
+
+        log::debug(&format!("✓ Authenticated to {}", ssh_url.host));
     }
 
     Ok(())
@@ -81,6 +137,11 @@ pub fn twinkle_sync(
     once: bool,
 ) -> Result<(), Box<dyn Error>>
 {
+    // Passphrase protected keys stay in ssh-agent while syncing
+    if let Some(key_pair) = repo.key_pair() {
+        twinkle_keypair_load(&key_pair, SshAgent::from_env().as_ref())?;
+    }
+
     twinkle_sync_prepare(repo)?;
 
     let repo_c1 = repo.clone();
@@ -90,10 +151,30 @@ pub fn twinkle_sync(
     thread::spawn(move || { _ = twinkle_watch_local(&repo_c2); });
     thread::spawn(move || { _ = twinkle_watch_remote(&mut repo_c3, interval); });
 
+    if repo.notify_enabled() {
+        let repo_c4 = repo.clone();
+        thread::spawn(move || { _ = twinkle_channel_watch(&repo_c4); });
+    }
+
+    if repo.lan_enabled() {
+        let repo_c5 = repo.clone();
+
+        thread::spawn(move || {
+            if let Err(e) = twinkle_lan_watch(&repo_c5) {
+                log::warning(&format!("LAN | Not listening for peers: {e}"));
+            }
+        });
+    }
+
     if twinkle_has_unpushed_commits(repo) {
         repo.set_has_local_changes(true);
     }
 
+    // Stopped before moving over to a compacted branch
+    if twinkle_compact_next(repo).is_some() {
+        repo.set_has_remote_changes(true);
+    }
+
     let mut start_sync = false;
 
     // This is the main loop
@@ -117,7 +198,14 @@ pub fn twinkle_sync(
                     repo.set_has_local_changes(false);
                     repo.set_last_synced(Utc::now().timestamp())?;
                 },
-                Err(e) => log::error(&e.to_string()),
+                Err(e) => {
+                    if let Some(e) = twinkle_sync_host_key_changed(repo, &*e) {
+                        repo.set_is_busy(false);
+                        return Err(e);
+                    }
+
+                    log::error(&e.to_string())
+                },
             }
         }
 
@@ -127,7 +215,23 @@ pub fn twinkle_sync(
                     repo.set_has_remote_changes(false);
                     repo.set_last_synced(Utc::now().timestamp())?;
                 },
-                Err(e) => log::error(&e.to_string()),
+                Err(e) => {
+                    if let Some(e) = twinkle_sync_host_key_changed(repo, &*e) {
+                        repo.set_is_busy(false);
+                        return Err(e);
+                    }
+
+                    log::error(&e.to_string())
+                },
+            }
+        }
+
+        if repo.compact_enabled() &&
+           !repo.has_local_changes() &&
+           !repo.has_remote_changes() &&
+           twinkle_compact_is_due(repo) {
+            if let Err(e) = twinkle_compact(repo) {
+                log::warning(&format!("Could not compact history: {e}"));
             }
         }
 
@@ -141,6 +245,43 @@ pub fn twinkle_sync(
 }
 
 
+/// Other errors are retried, but a changed host key stops syncing until it's trusted
+fn twinkle_sync_host_key_changed(repo: &TwinkleRepository, error: &dyn Error) -> Option<Box<dyn Error>> {
+    let message = error.to_string();
+
+    if !ssh_util_is_host_key_changed(&message) {
+        return None;
+    }
+
+    let remote_url = repo.remote_url()?;
+    let ssh_url = remote_url.as_ssh()?;
+    let key_pair = repo.key_pair()?;
+    let keys_dir = key_pair.private_key_path.parent()?;
+    let forges_file = repo.forges_file().unwrap_or_default();
+    let host_key = twinkle_hostkey_for(ssh_url, key_pair.key_type.to_host_key_type(), keys_dir, &forges_file).ok()?;
+
+    let change = SshHostKeyChanged {
+        host: host_key.host.clone(),
+        old: host_key.fingerprint.clone().or_else(|| ssh_keygen_fingerprint(&host_key).ok()),
+        new: ssh_util_offered_fingerprint(&message),
+    };
+
+    if let Err(e) = twinkle_hostkey_set_changed(&host_key, change.new.as_ref(), keys_dir) {
+        log::error(&e.to_string());
+    }
+
+    Some(host_key_changed_error(change))
+}
+
+
+fn host_key_changed_error(change: SshHostKeyChanged) -> Box<dyn Error> {
+    format!(
+        "{change}. Check the new fingerprint with your Git host, then run: twinkle keys retrust {}",
+        change.host,
+    ).into()
+}
+
+
 const WATCH_INTERVAL: u64 = 60;
 
 pub fn twinkle_watch_local(repo: &TwinkleRepository) -> Result<(), Box<dyn Error>> {
@@ -150,6 +291,8 @@ pub fn twinkle_watch_local(repo: &TwinkleRepository) -> Result<(), Box<dyn Error
             if !status.is_empty() {
                 repo.set_has_local_changes(true);
                 log::info("Local changes detected…");
+            } else if twinkle_has_unpushed_commits(repo) && repo.deletions_paused().is_none() {
+                repo.set_has_local_changes(true); // Like after confirming deletions
             }
         }
 
@@ -186,6 +329,15 @@ pub fn twinkle_watch_remote(repo: &mut TwinkleRepository, interval: Option<Durat
 
 
 pub fn twinkle_sync_up(repo: &mut TwinkleRepository) -> Result<(), Box<dyn Error>> {
+    if repo.read_only() {
+        log::info("Read-only. Local changes are set aside when fetching");
+        return Ok(());
+    }
+
+    if twinkle_deletions_is_paused(repo) {
+        return Ok(());
+    }
+
     let mut attempt = 1;
 
     loop {
@@ -196,19 +348,8 @@ pub fn twinkle_sync_up(repo: &mut TwinkleRepository) -> Result<(), Box<dyn Error
             Some(repo.git.GIT_SSH_COMMAND.clone())
         )?;
 
-        let status = repo.git.status()?;
-        let lfs_enabled = repo.lfs_enabled();
-
-        // TODO: loop this, but status() needs to return None when there are no more unstaged changes (status_y)
-        // TODO: need a separate command to check any (staged or unstaged) changes. remove plain status(). status_staged()+status_unstaged()+status_staged_or_unstaged()?
-        for change in status {
-            if lfs_enabled {
-                // Discard any errors (file may have been deleted)
-                _ = twinkle_lfs_track(repo, &change);
-            }
-
-            _ = repo.git.add(&change.path); // TODO: error get eaten and may cause an infinite loop
-        }
+        let oversize = twinkle_sync_stage(repo)?;
+        let is_oversize = |path: &Path| oversize.iter().any(|file| file.path == path);
 
         let status = repo.git.status()?; // TODO: status_staged()
 
@@ -222,11 +363,17 @@ pub fn twinkle_sync_up(repo: &mut TwinkleRepository) -> Result<(), Box<dyn Error
 
         if let Some(message) = twinkle_pretty_commit_message(&status) {
             let user = repo.user().ok_or("User not set")?;
+            let is_mass_deletion = twinkle_deletions_check(repo, &status)?;
 
             repo.set_user(&user)?;
             repo.git.commit(Some(user), &message)?;
 
             log::info(&format!("✓ Committed to `{branch}`. Now at {}", repo.current_head()?));
+
+            // Kept local until confirmed
+            if is_mass_deletion {
+                return twinkle_deletions_pause(repo, &repo.current_head()?);
+            }
         } else {
             if !twinkle_has_unpushed_commits(repo) {
                 log::info(&format!("Nothing new to commit. Still at {}", repo.current_head()?));
@@ -236,17 +383,46 @@ pub fn twinkle_sync_up(repo: &mut TwinkleRepository) -> Result<(), Box<dyn Error
             log::info("✓ Unpushed commits found");
         }
 
-        if repo.read_only() {
-            return Ok(());
-        }
-
         repo.git.lfs_install_pre_push_hook(Some(repo.git.GIT_SSH_COMMAND.clone()))?;
 
         let push = repo.git.push(&remote, &branch);
 
         match push {
-            Ok(_)  => log::info(&format!("✓ Pushed to `{remote}`. Local and remote at {}", repo.current_head()?)),
+            Ok(_)  => {
+                log::info(&format!("✓ Pushed to `{remote}`. Local and remote at {}", repo.current_head()?));
+
+                if repo.lan_enabled() {
+                    if let Err(e) = twinkle_lan_announce(repo) {
+                        log::debug(&format!("LAN | Could not announce: {e}"));
+                    }
+                }
+
+                if repo.notify_enabled() {
+                    let repo_c = repo.clone();
+
+                    // Don't hold up syncing when the server is slow or down
+                    thread::spawn(move || {
+                        if let Err(e) = twinkle_channel_announce(&repo_c) {
+                            log::debug(&format!("Notify | Could not announce: {e}"));
+                        }
+                    });
+                }
+            },
             Err(e) => {
+                if twinkle_credential_is_missing(&*e) {
+                    if let Some(GitRemoteUrl::Https(url)) = repo.remote_url() {
+                        return Err(twinkle_credential_error(&url, e)); // Retrying won't help
+                    }
+                }
+
+                if ssh_util_is_host_key_changed(&e.to_string()) {
+                    return Err(e); // Neither will this
+                }
+
+                if let Some(e) = repo.key_pair().as_ref().and_then(twinkle_keypair_certificate_error) {
+                    return Err(e); // Or this
+                }
+
                 dbg!(e);
                 log::info("✗ Push failed. Fetching…");
                 let fetch = twinkle_sync_down(repo);
@@ -259,8 +435,14 @@ pub fn twinkle_sync_up(repo: &mut TwinkleRepository) -> Result<(), Box<dyn Error
             }
         }
 
-        let status = repo.git.status()?;
-        if !twinkle_has_unpushed_commits(repo) && status.is_empty() {
+        let selection = repo.select();
+
+        let status = repo.git.status_all()?;
+        let is_pending = status.iter().any(|change|
+            !is_oversize(&change.path) && twinkle_select_contains(&selection, &change.path)
+        );
+
+        if !twinkle_has_unpushed_commits(repo) && !is_pending {
             break;
         }
 
@@ -271,6 +453,56 @@ pub fn twinkle_sync_up(repo: &mut TwinkleRepository) -> Result<(), Box<dyn Error
 }
 
 
+/// Stages all changes, except for files over the size limit. Returns those.
+pub fn twinkle_sync_stage(repo: &TwinkleRepository) -> Result<Vec<TwinkleOversizeFile>, Box<dyn Error>> {
+    let status = repo.git.status()?;
+    let lfs_enabled = repo.lfs_enabled();
+    let selection = repo.select();
+
+    let oversize = twinkle_oversize_files(repo)?;
+
+    for file in &oversize {
+        log::warning(&format!("Skipping {file}"));
+    }
+
+    if lfs_enabled {
+        // Per file, as new dirs may hold binaries
+        for change in repo.git.status_all()? {
+            if oversize.iter().any(|file| file.path == change.path) ||
+               !twinkle_select_contains(&selection, &change.path) {
+                continue;
+            }
+
+            // Discard any errors (file may have been deleted)
+            _ = twinkle_lfs_track(repo, &change);
+        }
+    }
+
+    // TODO: loop this, but status() needs to return None when there are no more unstaged changes (status_y)
+    // TODO: need a separate command to check any (staged or unstaged) changes. remove plain status(). status_staged()+status_unstaged()+status_staged_or_unstaged()?
+    for change in status {
+        if oversize.iter().any(|file| file.path == change.path) {
+            continue;
+        }
+
+        if !twinkle_select_contains(&selection, &change.path) {
+            log::warning(&format!("Skipping `{}`, outside of the selected folders", change.path.display()));
+            continue;
+        }
+
+        _ = repo.git.add(&change.path); // TODO: error get eaten and may cause an infinite loop
+    }
+
+    if !oversize.is_empty() {
+        // Adding a dir adds everything in it
+        let paths: Vec<PathBuf> = oversize.iter().map(|file| file.path.clone()).collect();
+        repo.git.reset_paths(&paths)?;
+    }
+
+    Ok(oversize)
+}
+
+
 pub fn twinkle_sync_up_delay(attempt: u64) -> Duration {
     let max  = twinkle_default_sync_up_delay_max().as_secs();
     let bump = twinkle_default_sync_up_delay_bump().as_secs();
@@ -283,6 +515,11 @@ pub fn twinkle_sync_up_delay(attempt: u64) -> Duration {
 
 
 pub fn twinkle_sync_down(repo: &mut TwinkleRepository) -> Result<(), Box<dyn Error>> {
+    // Merging on top of the deletions would make them harder to undo
+    if twinkle_deletions_is_paused(repo) {
+        return Ok(());
+    }
+
     let branch = repo.git.branch_show_current()?;
     let remote =
         if let Some(output) = repo.git.config_get(&format!("branch.{branch}.remote")) {
@@ -291,21 +528,68 @@ pub fn twinkle_sync_down(repo: &mut TwinkleRepository) -> Result<(), Box<dyn Err
             "origin".into()
         };
 
-    repo.git.fetch(&remote, &branch)?;
+    repo.git.fetch(&remote, &branch).map_err(|e|
+        match repo.remote_url() {
+            Some(GitRemoteUrl::Https(url)) => twinkle_credential_error(&url, e),
+            Some(GitRemoteUrl::Ssh(_)) => repo.key_pair()
+                .as_ref()
+                .and_then(twinkle_keypair_certificate_error)
+                .unwrap_or(e),
+            _ => e,
+        }
+    )?;
+
+    // Clones made with --depth may not go back far enough to merge
+    twinkle_history_deepen(repo, &remote, &branch)?;
 
     if repo.lfs_enabled() {
-        repo.git.lfs_fetch()?;
+        twinkle_lfs_fetch(repo)?;
     }
 
+    if repo.read_only() {
+        twinkle_read_only_set_aside(repo)?;
+    }
+
+    // What the merge deletes or overwrites can be restored from here
+    twinkle_trash_incoming(repo, "FETCH_HEAD")?;
+
     if OS == "macos" { repo.git.config_set("core.ignoreCase", "true")?; }
     let merge = repo.git.merge("FETCH_HEAD");
 
     if merge.is_err() {
+        if repo.read_only() {
+            if OS == "macos" { repo.git.config_set("core.ignoreCase", "false")?; }
+            return Err("Read-only. Local changes are in the way of the remote's changes".into()); // Resolving would commit
+        }
+
         twinkle_resolve_changes(repo)?;
     }
 
     if OS == "macos" { repo.git.config_set("core.ignoreCase", "false")?; }
 
+    // The branch was compacted. Continue on the new one
+    if twinkle_compact_next(repo).is_some() {
+        twinkle_compact_follow(repo)?;
+        return twinkle_sync_down(repo);
+    }
+
+    if repo.lfs_enabled() {
+        if let Err(e) = twinkle_lfs_prune(repo) {
+            log::warning(&format!("Could not prune LFS objects: {e}"));
+        }
+
+        // Checking out new versions makes files writable again
+        if let Err(e) = twinkle_lock_apply(repo) {
+            log::warning(&format!("Could not check LFS locks: {e}"));
+        }
+    }
+
+    if repo.trash_dir().is_some() {
+        if let Err(e) = twinkle_trash_expire(repo) {
+            log::warning(&format!("Could not empty old trash: {e}"));
+        }
+    }
+
     log::info(&format!("✓ Fetched and merged. Now at {}", repo.current_head()?));
     Ok(())
 }
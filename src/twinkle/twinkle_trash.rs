@@ -0,0 +1,238 @@
+//   Twinkle, automatic syncing with Git
+//   Copyright (C) 2026  Hylke Bons (hello@planetpeanut.studio)
+//
+//   This program is free software: you can redistribute it and/or modify it
+//   under the terms of the GNU General Public License v3 or any later version.
+
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use chrono::{ Duration, Local, NaiveDateTime };
+
+use crate::git::objects::file_status::GitFileStatus;
+use crate::log;
+
+use super::objects::repository::TwinkleRepository;
+
+
+// Before a sync deletes or overwrites files, the versions that were here are
+// copied to the trash, one folder per sync. They can be brought back with
+// `twinkle trash restore` without knowing Git, until they expire.
+
+
+const TRASH_DIR_FORMAT: &str = "%Y-%m-%d %H.%M.%S";
+
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TwinkleTrashItem {
+    pub path: PathBuf, // Where it was in the repository
+    pub trashed: String, // When, like "2026-10-19 14.03.12"
+    pub file: PathBuf, // In the trash
+}
+
+
+impl fmt::Display for TwinkleTrashItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}  {}", self.trashed, self.path.display())
+    }
+}
+
+
+/// Points the repository at its own folder in the app's data dir
+pub fn twinkle_trash_init(repo: &TwinkleRepository, data_home: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let id = repo.id().ok_or("Missing ID")?;
+    let trash_dir = data_home.join("trash").join(id);
+
+    repo.set_trash_dir(&trash_dir)?;
+    Ok(trash_dir)
+}
+
+
+/// Copies the files that merging `theirs` would delete or overwrite to the trash.
+/// Resolving conflicts comes after merging, so this covers it too. Returns how many.
+pub fn twinkle_trash_incoming(repo: &TwinkleRepository, theirs: &str) -> Result<usize, Box<dyn Error>> {
+    let Some(trash_dir) = repo.trash_dir() else {
+        return Ok(0);
+    };
+
+    if repo.trash_days() == 0 {
+        return Ok(0);
+    }
+
+    let target_dir = trash_dir.join(Local::now().format(TRASH_DIR_FORMAT).to_string());
+    let mut count = 0;
+
+    for change in repo.git.diff_incoming("HEAD", theirs)? {
+        match change.status_x {
+            Some(GitFileStatus::Deleted) |
+            Some(GitFileStatus::Modified) => {},
+            _ => continue, // Only unchanged files show as renamed
+        }
+
+        let source = repo.abs_path(&change.path);
+
+        // Not checked out, or not a regular file
+        if !source.symlink_metadata().is_ok_and(|metadata| metadata.is_file()) {
+            continue;
+        }
+
+        let target = target_dir.join(&change.path);
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::copy(&source, &target)?;
+        count += 1;
+    }
+
+    if count > 0 {
+        log::info(&format!("Trash | Copied {count} file(s) to `{}`", target_dir.display()));
+    }
+
+    Ok(count)
+}
+
+
+/// Everything in the trash, newest first
+pub fn twinkle_trash_list(repo: &TwinkleRepository) -> Result<Vec<TwinkleTrashItem>, Box<dyn Error>> {
+    fn list_dir(dir: &Path, root: &Path, trashed: &str, items: &mut Vec<TwinkleTrashItem>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let file = entry.path();
+
+            match entry.file_type() {
+                Ok(file_type) if file_type.is_dir() => list_dir(&file, root, trashed, items),
+                Ok(_) => items.push(TwinkleTrashItem {
+                    path: file.strip_prefix(root).unwrap_or(&file).to_path_buf(),
+                    trashed: trashed.to_string(),
+                    file,
+                }),
+                Err(_) => continue,
+            }
+        }
+    }
+
+    let mut items = Vec::new();
+
+    for (trashed, dir) in trash_dirs(repo)? {
+        list_dir(&dir, &dir, &trashed, &mut items);
+    }
+
+    items.sort_by(|a, b| b.trashed.cmp(&a.trashed).then_with(|| a.path.cmp(&b.path)));
+    Ok(items)
+}
+
+
+/// Puts back the newest version of `path`, or of everything in it if it's a folder.
+/// Files that are in the way stay, and the restored one is put next to it.
+/// Returns where they went.
+pub fn twinkle_trash_restore(repo: &TwinkleRepository, path: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut restored: Vec<PathBuf> = Vec::new();
+    let mut seen: Vec<PathBuf> = Vec::new();
+
+    for item in twinkle_trash_list(repo)? {
+        if !item.path.starts_with(path) || seen.contains(&item.path) {
+            continue;
+        }
+
+        let target = twinkle_trash_restore_path(repo, &item.path);
+        let abs_target = repo.abs_path(&target);
+
+        if let Some(parent) = abs_target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::copy(&item.file, &abs_target)?;
+        log::info(&format!("Trash | Restored `{}` from {}", target.display(), item.trashed));
+
+        seen.push(item.path);
+        restored.push(target);
+    }
+
+    if restored.is_empty() {
+        return Err(format!("`{}` is not in the trash", path.display()).into());
+    }
+
+    Ok(restored)
+}
+
+
+/// "notes.md", or "notes (restored).md" if that's taken, then "notes (restored 2).md"
+pub fn twinkle_trash_restore_path(repo: &TwinkleRepository, path: &Path) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = path.extension().map(|ext| format!(".{}", ext.to_string_lossy())).unwrap_or_default();
+
+    let mut target = path.to_path_buf();
+    let mut number = 1;
+
+    while repo.abs_path(&target).symlink_metadata().is_ok() {
+        let clue = match number {
+            1 => "restored".to_string(),
+            _ => format!("restored {number}"),
+        };
+
+        target = path.with_file_name(format!("{stem} ({clue}){ext}"));
+        number += 1;
+    }
+
+    target
+}
+
+
+/// Removes what's been in the trash longer than `trash.days`. Returns how many syncs' worth.
+pub fn twinkle_trash_expire(repo: &TwinkleRepository) -> Result<usize, Box<dyn Error>> {
+    let days = repo.trash_days();
+    let cutoff = Local::now().naive_local() - Duration::days(days.into());
+
+    let mut count = 0;
+
+    for (trashed, dir) in trash_dirs(repo)? {
+        // Folders that aren't ours are left alone
+        let Ok(time) = NaiveDateTime::parse_from_str(&trashed, TRASH_DIR_FORMAT) else {
+            continue;
+        };
+
+        if days == 0 || time < cutoff {
+            fs::remove_dir_all(&dir)?;
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
+
+
+/// Removes everything from the trash. Returns how many files that was.
+pub fn twinkle_trash_empty(repo: &TwinkleRepository) -> Result<usize, Box<dyn Error>> {
+    let count = twinkle_trash_list(repo)?.len();
+
+    for (_, dir) in trash_dirs(repo)? {
+        fs::remove_dir_all(&dir)?;
+    }
+
+    Ok(count)
+}
+
+
+/// The folder of each sync, by name
+fn trash_dirs(repo: &TwinkleRepository) -> Result<Vec<(String, PathBuf)>, Box<dyn Error>> {
+    let trash_dir = repo.trash_dir().ok_or("No trash set up yet. It is when syncing starts")?;
+
+    let Ok(entries) = fs::read_dir(&trash_dir) else {
+        return Ok(Vec::new()); // Nothing trashed yet
+    };
+
+    let dirs = entries.flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        .map(|entry| (entry.file_name().to_string_lossy().to_string(), entry.path()))
+        .collect();
+
+    Ok(dirs)
+}
@@ -11,15 +11,19 @@ use std::fs::File;
 use std::io::Read;
 use std::path::{ Path, PathBuf };
 
+use crate::git::objects::remote_url::GitRemoteUrl;
 use crate::ssh::objects::config::SshConfig;
-use crate::ssh::objects::url::SshUrl;
 use crate::ssh::keys::key_pair::KeyPair;
+use crate::ssh::objects::url::SshUrl;
+
+use super::twinkle_ssh::twinkle_ssh_config_for;
 
 
 // "ssh://git@github.com:hbons/Twinkle" -> "Twinkle"
 // "ssh://git@github.com:hbons"         -> "hbons"
-pub fn twinkle_default_dir_name(url: &SshUrl) -> Result<PathBuf, Box<dyn Error>> {
-    let dir = url.path.file_stem().ok_or("Could not determine path")?;
+// "file:///srv/shared/Twinkle.git"     -> "Twinkle"
+pub fn twinkle_default_dir_name(url: &GitRemoteUrl) -> Result<PathBuf, Box<dyn Error>> {
+    let dir = url.path().file_stem().ok_or("Could not determine path")?;
     Ok(PathBuf::from(dir))
 }
 
@@ -40,14 +44,21 @@ pub fn twinkle_unique_dir(dir: &Path) -> PathBuf {
 }
 
 
-pub fn twinkle_ssh_command(key_pair: Option<&KeyPair>) -> String {
+/// Includes the settings imported for the URL's host, see `twinkle_ssh_config_for`
+pub fn twinkle_ssh_command(key_pair: Option<&KeyPair>, ssh_url: Option<&SshUrl>) -> String {
     let config = match key_pair {
         Some(key_pair) => {
-            SshConfig {
+            let config = SshConfig {
                 IdentitiesOnly: true,
+                CertificateFile: key_pair.certificate.as_ref().map(|certificate| certificate.path.clone()),
                 IdentityFile: Some(key_pair.private_key_path.clone()),
                 UserKnownHostsFile: Some(key_pair.private_key_path.with_extension("key.host")),
                 ..Default::default()
+            };
+
+            match (ssh_url, key_pair.private_key_path.parent()) {
+                (Some(ssh_url), Some(keys_dir)) => config.with_host_options(&twinkle_ssh_config_for(ssh_url, keys_dir)),
+                _ => config,
             }
         },
         None => SshConfig::default(),